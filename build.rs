@@ -1,5 +1,10 @@
 use std::{env, fs, path::Path};
 
+#[path = "src/schema_json.rs"]
+mod schema_json;
+
+use schema_json::{generate_cloudflare_schema, LOCAL_SCHEMAS};
+
 fn main() {
     write_cloudflare_schemas();
 }
@@ -9,7 +14,7 @@ fn write_cloudflare_schemas() {
     let schemas_dir = Path::new(&manifest_dir).join("schemas");
     fs::create_dir_all(&schemas_dir).expect("failed to create schemas directory");
 
-    for schema in otlp2records::schema_defs() {
+    for schema in otlp2records::schema_defs().iter().chain(LOCAL_SCHEMAS) {
         let schema_json = generate_cloudflare_schema(schema);
         let schema_path = schemas_dir.join(format!("{}.schema.json", schema.name));
 
@@ -27,22 +32,6 @@ fn write_cloudflare_schemas() {
     }
 
     println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/schema_json.rs");
     println!("cargo:rerun-if-changed=Cargo.toml");
 }
-
-fn generate_cloudflare_schema(schema: &otlp2records::SchemaDef) -> String {
-    let mut fields_json = Vec::new();
-
-    for field in schema.fields {
-        let field_obj = format!(
-            r#"    {{ "name": "{}", "type": "{}", "required": {} }}"#,
-            field.name, field.field_type, field.required
-        );
-        fields_json.push(field_obj);
-    }
-
-    format!(
-        "{{\n  \"fields\": [\n{}\n  ]\n}}\n",
-        fields_json.join(",\n")
-    )
-}
@@ -0,0 +1,186 @@
+//! Circuit breaker for best-effort aggregator writes and pipeline sends.
+//!
+//! After `failure_threshold` consecutive failed writes the breaker opens and
+//! writes are skipped for `cooldown_ms`. The first request after the cooldown
+//! is let through as a probe: success closes the breaker, failure reopens it.
+
+use std::sync::Mutex;
+
+use crate::parse_or;
+
+/// Consecutive failed writes before the breaker opens
+pub const DEFAULT_BREAKER_THRESHOLD: u32 = 5;
+/// How long writes are skipped once the breaker opens
+pub const DEFAULT_BREAKER_COOLDOWN_MS: u64 = 30_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Closed {
+        consecutive_failures: u32,
+    },
+    Open {
+        until_ms: u64,
+    },
+    /// A probe is in flight; others skip until it reports or `until_ms` passes
+    Probing {
+        until_ms: u64,
+    },
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    /// What the breaker guards, e.g. "aggregator writes", for its log line
+    label: String,
+    failure_threshold: u32,
+    cooldown_ms: u64,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    /// A threshold of 0 disables the breaker
+    pub fn new(label: impl Into<String>, failure_threshold: u32, cooldown_ms: u64) -> Self {
+        Self {
+            label: label.into(),
+            failure_threshold,
+            cooldown_ms,
+            state: Mutex::new(State::Closed {
+                consecutive_failures: 0,
+            }),
+        }
+    }
+
+    /// Build from `AGGREGATOR_BREAKER_THRESHOLD` and `AGGREGATOR_BREAKER_COOLDOWN_MS`
+    pub fn from_lookup(mut var: impl FnMut(&str) -> Option<String>) -> Self {
+        Self::new(
+            "aggregator writes",
+            parse_or(
+                var("AGGREGATOR_BREAKER_THRESHOLD"),
+                DEFAULT_BREAKER_THRESHOLD,
+            ),
+            parse_or(
+                var("AGGREGATOR_BREAKER_COOLDOWN_MS"),
+                DEFAULT_BREAKER_COOLDOWN_MS,
+            ),
+        )
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Whether a write should be attempted now
+    pub fn allow(&self, now_ms: u64) -> bool {
+        if self.failure_threshold == 0 {
+            return true;
+        }
+        let mut state = self.state();
+        match *state {
+            State::Closed { .. } => true,
+            State::Open { until_ms } | State::Probing { until_ms } if now_ms < until_ms => false,
+            // Cooldown over, or a probe never reported back: send a probe
+            State::Open { .. } | State::Probing { .. } => {
+                *state = State::Probing {
+                    until_ms: now_ms + self.cooldown_ms,
+                };
+                true
+            }
+        }
+    }
+
+    /// Report the outcome of an attempted write
+    pub fn record(&self, success: bool, now_ms: u64) {
+        if self.failure_threshold == 0 {
+            return;
+        }
+        let mut state = self.state();
+        *state = match (*state, success) {
+            (_, true) => State::Closed {
+                consecutive_failures: 0,
+            },
+            (
+                State::Closed {
+                    consecutive_failures,
+                },
+                false,
+            ) if consecutive_failures + 1 < self.failure_threshold => State::Closed {
+                consecutive_failures: consecutive_failures + 1,
+            },
+            (_, false) => {
+                tracing::warn!(
+                    cooldown_ms = self.cooldown_ms,
+                    "{} failing, skipping them for the cooldown",
+                    self.label
+                );
+                State::Open {
+                    until_ms: now_ms + self.cooldown_ms,
+                }
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_opens_after_threshold_and_skips_during_cooldown() {
+        let breaker = CircuitBreaker::new("test", 3, 1_000);
+        for now in 0..2 {
+            assert!(breaker.allow(now));
+            breaker.record(false, now);
+        }
+        assert!(breaker.allow(2));
+        breaker.record(false, 2);
+
+        assert!(!breaker.allow(3));
+        assert!(!breaker.allow(1_001));
+    }
+
+    #[test]
+    fn test_probe_recovers_or_reopens() {
+        let breaker = CircuitBreaker::new("test", 1, 1_000);
+        breaker.record(false, 0);
+        assert!(!breaker.allow(500));
+
+        // One probe after the cooldown; concurrent requests keep skipping
+        assert!(breaker.allow(1_000));
+        assert!(!breaker.allow(1_001));
+
+        // Failed probe reopens for another cooldown
+        breaker.record(false, 1_010);
+        assert!(!breaker.allow(1_500));
+
+        // Successful probe closes the breaker
+        assert!(breaker.allow(2_010));
+        breaker.record(true, 2_020);
+        assert!(breaker.allow(2_021));
+        assert!(breaker.allow(2_022));
+    }
+
+    #[test]
+    fn test_lost_probe_is_retried_and_success_resets_count() {
+        let breaker = CircuitBreaker::new("test", 2, 1_000);
+        breaker.record(false, 0);
+        breaker.record(true, 1);
+        breaker.record(false, 2);
+        assert!(breaker.allow(3), "success should reset the failure count");
+
+        breaker.record(false, 3);
+        assert!(breaker.allow(1_003));
+        // The probe never reports back; another probe is allowed a cooldown later
+        assert!(!breaker.allow(1_500));
+        assert!(breaker.allow(2_003));
+    }
+
+    #[test]
+    fn test_zero_threshold_disables() {
+        let breaker = CircuitBreaker::from_lookup(|name| {
+            (name == "AGGREGATOR_BREAKER_THRESHOLD").then(|| "0".to_string())
+        });
+        for now in 0..10 {
+            breaker.record(false, now);
+            assert!(breaker.allow(now));
+        }
+    }
+}
@@ -1,6 +1,7 @@
 // src/aggregator/mod.rs
 //! Signal aggregator using Durable Objects for baseline RED metrics.
 
+mod circuit_breaker;
 mod stats;
 
 #[cfg(target_arch = "wasm32")]
@@ -12,6 +13,7 @@ mod sender;
 #[cfg(not(target_arch = "wasm32"))]
 mod sender;
 
+pub use circuit_breaker::CircuitBreaker;
 pub use stats::{LogAggregates, TraceAggregates};
 
 #[cfg(target_arch = "wasm32")]
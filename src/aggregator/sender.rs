@@ -1,13 +1,22 @@
 //! AggregatorSender trait and implementations.
 
+#[cfg(target_arch = "wasm32")]
+use super::circuit_breaker::CircuitBreaker;
 #[cfg(target_arch = "wasm32")]
 use futures::stream::{self, StreamExt};
 use serde_json::Value;
 use std::collections::HashMap;
 #[cfg(target_arch = "wasm32")]
+use std::sync::OnceLock;
+#[cfg(target_arch = "wasm32")]
 use tracing::debug;
 use tracing::warn;
 
+/// Concurrent DO writes per request when `AGGREGATOR_CONCURRENCY` is unset
+pub const DEFAULT_AGGREGATOR_CONCURRENCY: usize = 10;
+/// Upper bound for `AGGREGATOR_CONCURRENCY`, to stay within the subrequest budget
+const MAX_AGGREGATOR_CONCURRENCY: usize = 100;
+
 /// Result of sending to aggregator DOs
 #[derive(Debug, Default)]
 pub struct AggregatorSendResult {
@@ -30,6 +39,9 @@ pub trait AggregatorSender {
 pub struct WasmAggregatorSender {
     env: worker::Env,
     enabled: bool,
+    breaker: &'static CircuitBreaker,
+    /// Maximum DO writes in flight at once
+    concurrency: usize,
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -40,7 +52,24 @@ impl WasmAggregatorSender {
             .map(|v| v.to_string() == "true")
             .unwrap_or(false);
 
-        Self { env, enabled }
+        // One breaker per isolate, so failures are remembered across requests
+        static BREAKER: OnceLock<CircuitBreaker> = OnceLock::new();
+        let breaker = BREAKER.get_or_init(|| {
+            CircuitBreaker::from_lookup(|name| env.var(name).ok().map(|v| v.to_string()))
+        });
+
+        let concurrency = aggregator_concurrency_from(
+            env.var("AGGREGATOR_CONCURRENCY")
+                .ok()
+                .map(|v| v.to_string()),
+        );
+
+        Self {
+            env,
+            enabled,
+            breaker,
+            concurrency,
+        }
     }
 
     /// Group records by DO name ({service}:{table}).
@@ -87,6 +116,13 @@ impl AggregatorSender for WasmAggregatorSender {
         }
 
         let by_do = self.group_by_do(grouped);
+        if by_do.is_empty() {
+            return AggregatorSendResult::default();
+        }
+        if !self.breaker.allow(worker::Date::now().as_millis()) {
+            debug!("aggregator circuit open, skipping write");
+            return AggregatorSendResult::default();
+        }
         let mut succeeded = HashMap::new();
         let mut failed = HashMap::new();
 
@@ -98,7 +134,7 @@ impl AggregatorSender for WasmAggregatorSender {
                     (do_name, count, result)
                 }
             })
-            .buffer_unordered(10)
+            .buffer_unordered(self.concurrency)
             .collect()
             .await;
 
@@ -114,6 +150,10 @@ impl AggregatorSender for WasmAggregatorSender {
             }
         }
 
+        self.breaker.record(
+            failed.is_empty() || !succeeded.is_empty(),
+            worker::Date::now().as_millis(),
+        );
         AggregatorSendResult { succeeded, failed }
     }
 }
@@ -235,6 +275,28 @@ pub fn get_service_name(record: &Value) -> String {
     "unknown".to_string()
 }
 
+/// Parse `AGGREGATOR_CONCURRENCY`, clamped to 1..=100.
+/// Unset or unparseable values use the default.
+#[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+pub(crate) fn aggregator_concurrency_from(value: Option<String>) -> usize {
+    let Some(value) = value else {
+        return DEFAULT_AGGREGATOR_CONCURRENCY;
+    };
+    match value.trim().parse::<usize>() {
+        Ok(n) => {
+            let clamped = n.clamp(1, MAX_AGGREGATOR_CONCURRENCY);
+            if clamped != n {
+                warn!(value = n, clamped, "AGGREGATOR_CONCURRENCY out of range");
+            }
+            clamped
+        }
+        Err(_) => {
+            warn!(value = %value, "invalid AGGREGATOR_CONCURRENCY, using default");
+            DEFAULT_AGGREGATOR_CONCURRENCY
+        }
+    }
+}
+
 /// Build DO name from service and table.
 #[cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
 pub fn build_do_name(service_name: &str, table_name: &str) -> String {
@@ -322,6 +384,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_aggregator_concurrency_from() {
+        assert_eq!(
+            aggregator_concurrency_from(None),
+            DEFAULT_AGGREGATOR_CONCURRENCY
+        );
+        assert_eq!(aggregator_concurrency_from(Some(" 25 ".to_string())), 25);
+        assert_eq!(aggregator_concurrency_from(Some("0".to_string())), 1);
+        assert_eq!(aggregator_concurrency_from(Some("500".to_string())), 100);
+        assert_eq!(
+            aggregator_concurrency_from(Some("-3".to_string())),
+            DEFAULT_AGGREGATOR_CONCURRENCY
+        );
+    }
+
     #[tokio::test]
     async fn test_native_sender_only_processes_logs_and_traces() {
         let sender = NativeAggregatorSender::new();
@@ -11,7 +11,35 @@ const SEVERITY_ERROR_THRESHOLD: i64 = 17;
 /// https://opentelemetry.io/docs/specs/otel/trace/api/#set-status
 const STATUS_CODE_ERROR: i64 = 2;
 
-/// Log aggregates: count and error count (severity >= 17).
+/// Record attribute carrying the head-sampling probability (0 < ratio <= 1)
+const SAMPLING_RATIO_ATTRIBUTE: &str = "sampling.ratio";
+
+/// Estimated real events a record represents: the inverse of its sampling ratio.
+/// Records without a valid ratio count once.
+fn sample_weight(record: &Value, attributes_field: &str) -> i64 {
+    let Some(attributes) = record.get(attributes_field).and_then(|v| v.as_str()) else {
+        return 1;
+    };
+    // Avoid parsing the attribute blob for the common unsampled case
+    if !attributes.contains(SAMPLING_RATIO_ATTRIBUTE) {
+        return 1;
+    }
+
+    let ratio = serde_json::from_str::<Value>(attributes)
+        .ok()
+        .and_then(|attrs| {
+            let ratio = attrs.get(SAMPLING_RATIO_ATTRIBUTE)?;
+            ratio
+                .as_f64()
+                .or_else(|| ratio.as_str().and_then(|s| s.parse().ok()))
+        });
+    match ratio {
+        Some(ratio) if ratio > 0.0 && ratio <= 1.0 => (1.0 / ratio).round() as i64,
+        _ => 1,
+    }
+}
+
+/// Log aggregates: count and error count (severity >= 17), scaled by sampling ratio.
 #[derive(Default, Debug)]
 pub struct LogAggregates {
     pub count: i64,
@@ -20,52 +48,128 @@ pub struct LogAggregates {
 
 impl LogAggregates {
     pub fn accumulate(&mut self, record: &Value) {
-        self.count += 1;
+        let weight = sample_weight(record, "log_attributes");
+        self.count += weight;
         if let Some(severity) = record.get("severity_number").and_then(|v| v.as_i64()) {
             if severity >= SEVERITY_ERROR_THRESHOLD {
-                self.error_count += 1;
+                self.error_count += weight;
             }
         }
     }
 }
 
-/// Trace aggregates: count, error count, and latency stats.
-#[derive(Default, Debug)]
+/// Latency histogram buckets: 1-9 steps per decade from 1ms to 900s, plus overflow.
+/// Fixed size so per-service memory stays bounded.
+pub const LATENCY_BUCKETS: usize = 9 * 6 + 1;
+
+/// Inclusive upper bound (ms) of a latency bucket: 1, 2, .., 9, 10, 20, .., 900000.
+/// The last bucket has no upper bound.
+pub fn latency_bucket_upper_ms(index: usize) -> Option<i64> {
+    (index < LATENCY_BUCKETS - 1).then(|| (index as i64 % 9 + 1) * 10i64.pow(index as u32 / 9))
+}
+
+fn latency_bucket(duration_ms: i64) -> usize {
+    (0..LATENCY_BUCKETS - 1)
+        .find(|&i| latency_bucket_upper_ms(i).is_some_and(|upper| duration_ms <= upper))
+        .unwrap_or(LATENCY_BUCKETS - 1)
+}
+
+/// Trace aggregates: count, error count, and latency stats, scaled by sampling ratio.
+#[derive(Debug)]
 pub struct TraceAggregates {
     pub count: i64,
     pub error_count: i64,
     pub latency_sum_us: i64,
     pub latency_min_us: Option<i64>,
     pub latency_max_us: Option<i64>,
+    /// Weighted span counts per `latency_bucket_upper_ms` bucket
+    pub latency_buckets: [i64; LATENCY_BUCKETS],
+}
+
+impl Default for TraceAggregates {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            error_count: 0,
+            latency_sum_us: 0,
+            latency_min_us: None,
+            latency_max_us: None,
+            latency_buckets: [0; LATENCY_BUCKETS],
+        }
+    }
 }
 
 impl TraceAggregates {
     pub fn accumulate(&mut self, record: &Value) {
-        self.count += 1;
+        let weight = sample_weight(record, "span_attributes");
+        self.count += weight;
 
         // Error: status_code == 2
         if let Some(status) = record.get("status_code").and_then(|v| v.as_i64()) {
             if status == STATUS_CODE_ERROR {
-                self.error_count += 1;
+                self.error_count += weight;
             }
         }
 
-        // Latency: VRL outputs "duration" in milliseconds, convert to microseconds
+        // Latency: VRL outputs "duration" in milliseconds
         if let Some(duration_ms) = record.get("duration").and_then(|v| v.as_i64()) {
-            let duration_us = duration_ms * 1000;
-            self.latency_sum_us += duration_us;
-            self.latency_min_us = Some(
-                self.latency_min_us
-                    .map(|min| min.min(duration_us))
-                    .unwrap_or(duration_us),
-            );
-            self.latency_max_us = Some(
-                self.latency_max_us
-                    .map(|max| max.max(duration_us))
-                    .unwrap_or(duration_us),
-            );
+            self.add_duration(duration_ms, weight);
         }
     }
+
+    /// Record one span duration in the latency stats and histogram
+    pub fn record_duration(&mut self, duration_ms: i64) {
+        self.add_duration(duration_ms, 1);
+    }
+
+    fn add_duration(&mut self, duration_ms: i64, weight: i64) {
+        let duration_us = duration_ms * 1000;
+        // Weighted so latency_sum_us / count stays the mean
+        self.latency_sum_us += duration_us * weight;
+        self.latency_min_us = Some(
+            self.latency_min_us
+                .map(|min| min.min(duration_us))
+                .unwrap_or(duration_us),
+        );
+        self.latency_max_us = Some(
+            self.latency_max_us
+                .map(|max| max.max(duration_us))
+                .unwrap_or(duration_us),
+        );
+        self.latency_buckets[latency_bucket(duration_ms)] += weight;
+    }
+
+    /// Approximate latency percentile in ms (`q` in 0.0..=1.0): the upper bound
+    /// of the bucket holding that rank, capped at the observed maximum.
+    /// `None` until a duration is recorded.
+    pub fn percentile(&self, q: f64) -> Option<i64> {
+        let total: i64 = self.latency_buckets.iter().sum();
+        let max_ms = self.latency_max_us? / 1000;
+        if total == 0 {
+            return None;
+        }
+        let rank = ((q.clamp(0.0, 1.0) * total as f64).ceil() as i64).max(1);
+        let mut seen = 0;
+        for (index, count) in self.latency_buckets.iter().enumerate() {
+            seen += count;
+            if seen >= rank {
+                return Some(latency_bucket_upper_ms(index).map_or(max_ms, |u| u.min(max_ms)));
+            }
+        }
+        Some(max_ms)
+    }
+
+    pub fn p50(&self) -> Option<i64> {
+        self.percentile(0.50)
+    }
+
+    pub fn p95(&self) -> Option<i64> {
+        self.percentile(0.95)
+    }
+
+    pub fn p99(&self) -> Option<i64> {
+        self.percentile(0.99)
+    }
 }
 
 #[cfg(test)]
@@ -151,6 +255,40 @@ mod tests {
         assert_eq!(agg.latency_max_us, Some(100_000));
     }
 
+    #[test]
+    fn sampled_records_scale_by_inverse_ratio() {
+        let mut logs = LogAggregates::default();
+        logs.accumulate(&json!({
+            "severity_number": 17,
+            "log_attributes": r#"{"sampling.ratio":0.1}"#
+        }));
+        assert_eq!(logs.count, 10);
+        assert_eq!(logs.error_count, 10);
+
+        let mut traces = TraceAggregates::default();
+        traces.accumulate(&json!({
+            "status_code": 2,
+            "duration": 5,
+            "span_attributes": r#"{"sampling.ratio":"0.25"}"#
+        }));
+        traces.accumulate(&json!({"status_code": 1, "duration": 1}));
+        assert_eq!(traces.count, 5);
+        assert_eq!(traces.error_count, 4);
+        assert_eq!(traces.latency_sum_us, 21_000);
+        assert_eq!(traces.latency_max_us, Some(5000));
+    }
+
+    #[test]
+    fn invalid_sampling_ratio_counts_once() {
+        for ratio in ["0", "1.5", "\"abc\"", "-0.5"] {
+            let mut agg = LogAggregates::default();
+            agg.accumulate(&json!({
+                "log_attributes": format!(r#"{{"sampling.ratio":{}}}"#, ratio)
+            }));
+            assert_eq!(agg.count, 1, "ratio {}", ratio);
+        }
+    }
+
     #[test]
     fn trace_aggregates_error_spans() {
         let mut agg = TraceAggregates::default();
@@ -166,4 +304,60 @@ mod tests {
         assert_eq!(agg.latency_min_us, Some(30_000));
         assert_eq!(agg.latency_max_us, Some(70_000));
     }
+
+    #[test]
+    fn latency_buckets_are_log_linear() {
+        let bounds: Vec<_> = [0, 8, 9, 17, 18, 53]
+            .iter()
+            .map(|&i| latency_bucket_upper_ms(i))
+            .collect();
+        assert_eq!(
+            bounds,
+            [
+                Some(1),
+                Some(9),
+                Some(10),
+                Some(90),
+                Some(100),
+                Some(900_000)
+            ]
+        );
+        assert_eq!(latency_bucket_upper_ms(LATENCY_BUCKETS - 1), None);
+        assert_eq!(latency_bucket(0), 0);
+        assert_eq!(latency_bucket(15), 10); // (10, 20]
+        assert_eq!(latency_bucket(10_000_000), LATENCY_BUCKETS - 1);
+    }
+
+    #[test]
+    fn percentiles_land_in_expected_buckets() {
+        let mut agg = TraceAggregates::default();
+        assert_eq!(agg.p50(), None);
+
+        // 1..=1000ms, one span each
+        for ms in 1..=1000 {
+            agg.record_duration(ms);
+        }
+        // Exact p50 is 500ms, p95 950ms, p99 990ms; each reports its bucket's upper bound
+        assert_eq!(agg.p50(), Some(500));
+        assert_eq!(agg.p95(), Some(1000));
+        assert_eq!(agg.p99(), Some(1000));
+        assert_eq!(agg.percentile(0.0), Some(1));
+        assert_eq!(agg.percentile(0.123), Some(200)); // 123ms is in (100, 200]
+    }
+
+    #[test]
+    fn percentiles_capped_at_max_and_weighted_by_sampling() {
+        let mut agg = TraceAggregates::default();
+        // 90% fast (sampled 1 in 9), 10% in the overflow bucket
+        agg.accumulate(&json!({
+            "duration": 3,
+            "span_attributes": r#"{"sampling.ratio":0.111111}"#
+        }));
+        agg.accumulate(&json!({"duration": 2_000_000}));
+
+        assert_eq!(agg.count, 10);
+        assert_eq!(agg.p50(), Some(3));
+        assert_eq!(agg.percentile(0.9), Some(3));
+        assert_eq!(agg.p95(), Some(2_000_000));
+    }
 }
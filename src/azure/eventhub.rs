@@ -10,7 +10,7 @@ use std::collections::HashMap;
 use tokio::sync::Mutex;
 use tracing::{debug, error};
 
-use crate::pipeline::{PipelineSender, SendResult};
+use crate::pipeline::{FailureReason, PipelineSender, SendFailure, SendResult};
 
 /// Event Hub configuration loaded from environment.
 #[derive(Clone)]
@@ -110,7 +110,9 @@ impl PipelineSender for EventHubSender {
                 }
                 Err(e) => {
                     error!(table = %table, error = %e, "Failed to send to Event Hub");
-                    result.failed.insert(table, e);
+                    result
+                        .failed
+                        .insert(table, SendFailure::new(FailureReason::Network, e));
                 }
             }
         }
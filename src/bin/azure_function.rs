@@ -5,6 +5,7 @@
 
 use axum::{
     body::Bytes,
+    extract::RawQuery,
     http::{header, HeaderMap, StatusCode},
     response::IntoResponse,
     routing::{get, post},
@@ -12,8 +13,9 @@ use axum::{
 };
 use otlp2pipeline::{
     azure::{EventHubConfig, EventHubSender},
-    handle_signal, HandleError, InputFormat, LogsHandler, MetricsHandler, SignalHandler,
-    TracesHandler,
+    error_with_request_id, handle_signal, resolve_request_id, verbose_requested, BodyFormat,
+    Compression, HandleError, HandlerConfig, LogsHandler, MetricsHandler, SignalHandler,
+    TracesHandler, REQUEST_ID_HEADER,
 };
 use std::sync::Arc;
 use tracing::{error, info, warn};
@@ -21,6 +23,13 @@ use tracing::{error, info, warn};
 /// Optional auth token loaded at cold start
 static AUTH_TOKEN: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
 
+/// Handler config loaded from environment at cold start
+static HANDLER_CONFIG: std::sync::OnceLock<HandlerConfig> = std::sync::OnceLock::new();
+
+fn handler_config() -> &'static HandlerConfig {
+    HANDLER_CONFIG.get_or_init(HandlerConfig::from_env)
+}
+
 fn init_auth_token() {
     let token =
         AUTH_TOKEN.get_or_init(|| std::env::var("AUTH_TOKEN").ok().filter(|t| !t.is_empty()));
@@ -74,6 +83,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("Azure Function cold start - initializing");
 
     init_auth_token();
+    handler_config();
 
     let config = EventHubConfig::from_env().map_err(|e| {
         error!(error = %e, "Failed to load Event Hub config");
@@ -113,51 +123,83 @@ async fn health() -> &'static str {
 
 async fn handle_logs(
     headers: HeaderMap,
+    RawQuery(query): RawQuery,
     state: axum::extract::State<Arc<EventHubSender>>,
     body: Bytes,
 ) -> impl IntoResponse {
-    handle_signal_request::<LogsHandler>(headers, &state, body).await
+    let verbose = verbose_requested(query.as_deref());
+    handle_signal_request::<LogsHandler>(headers, verbose, &state, body).await
 }
 
 async fn handle_traces(
     headers: HeaderMap,
+    RawQuery(query): RawQuery,
     state: axum::extract::State<Arc<EventHubSender>>,
     body: Bytes,
 ) -> impl IntoResponse {
-    handle_signal_request::<TracesHandler>(headers, &state, body).await
+    let verbose = verbose_requested(query.as_deref());
+    handle_signal_request::<TracesHandler>(headers, verbose, &state, body).await
 }
 
 async fn handle_metrics(
     headers: HeaderMap,
+    RawQuery(query): RawQuery,
     state: axum::extract::State<Arc<EventHubSender>>,
     body: Bytes,
 ) -> impl IntoResponse {
-    handle_signal_request::<MetricsHandler>(headers, &state, body).await
+    let verbose = verbose_requested(query.as_deref());
+    handle_signal_request::<MetricsHandler>(headers, verbose, &state, body).await
 }
 
 async fn handle_signal_request<H: SignalHandler>(
     headers: HeaderMap,
+    verbose: bool,
     sender: &EventHubSender,
     body: Bytes,
 ) -> impl IntoResponse {
-    if let Err((status, msg)) = check_auth(&headers) {
+    let request_id =
+        resolve_request_id(headers.get(REQUEST_ID_HEADER).and_then(|v| v.to_str().ok()));
+    let (status, body) = signal_response::<H>(&headers, verbose, sender, body, &request_id).await;
+    (status, [(REQUEST_ID_HEADER, request_id)], body)
+}
+
+async fn signal_response<H: SignalHandler>(
+    headers: &HeaderMap,
+    verbose: bool,
+    sender: &EventHubSender,
+    body: Bytes,
+    request_id: &str,
+) -> (StatusCode, String) {
+    if let Err((status, msg)) = check_auth(headers) {
         return (status, msg.to_string());
     }
 
-    let is_gzipped = headers
-        .get(header::CONTENT_ENCODING)
-        .and_then(|v| v.to_str().ok())
-        .map(|v| v.eq_ignore_ascii_case("gzip"))
-        .unwrap_or(false);
+    let compression = Compression::from_content_encoding(
+        headers
+            .get(header::CONTENT_ENCODING)
+            .and_then(|v| v.to_str().ok()),
+    );
 
-    let format = InputFormat::from_content_type(
+    let format = BodyFormat::from_content_type(
         headers
             .get(header::CONTENT_TYPE)
             .and_then(|v| v.to_str().ok()),
     );
 
-    match handle_signal::<H, _>(body, is_gzipped, format, sender).await {
-        Ok(response) => match serde_json::to_string(&response) {
+    match handle_signal::<H, _>(
+        body,
+        compression,
+        format,
+        handler_config(),
+        request_id,
+        sender,
+    )
+    .await
+    {
+        Ok(response) if response.is_no_content(handler_config()) => {
+            (StatusCode::NO_CONTENT, String::new())
+        }
+        Ok(response) => match serde_json::to_string(&response.for_verbosity(verbose)) {
             Ok(json) => (StatusCode::OK, json),
             Err(e) => {
                 error!(error = %e, "Failed to serialize response");
@@ -177,9 +219,12 @@ async fn handle_signal_request<H: SignalHandler>(
                     StatusCode::INTERNAL_SERVER_ERROR,
                     format!("Transform: {}", m),
                 ),
+                HandleError::TooManyTables(m) => {
+                    (StatusCode::BAD_REQUEST, format!("Too many tables: {}", m))
+                }
                 HandleError::SendFailed(m) => (StatusCode::BAD_GATEWAY, format!("Send: {}", m)),
             };
-            (status, msg)
+            (status, error_with_request_id(&msg, request_id))
         }
     }
 }
@@ -5,9 +5,10 @@
 
 use lambda_http::{run, service_fn, Body, Error, Request, Response};
 use otlp2pipeline::{
-    handle_signal,
+    error_with_request_id, handle_signal,
     lambda::firehose::{FirehoseSender, StreamConfig},
-    HandleError, InputFormat, LogsHandler, MetricsHandler, TracesHandler,
+    resolve_request_id, verbose_requested, BodyFormat, Compression, HandleError, HandlerConfig,
+    LogsHandler, MetricsHandler, TracesHandler, REQUEST_ID_HEADER,
 };
 use std::sync::Arc;
 use tracing::{error, info, warn};
@@ -29,6 +30,13 @@ fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
 /// Optional auth token loaded at cold start
 static AUTH_TOKEN: std::sync::OnceLock<Option<String>> = std::sync::OnceLock::new();
 
+/// Handler config loaded from environment at cold start
+static HANDLER_CONFIG: std::sync::OnceLock<HandlerConfig> = std::sync::OnceLock::new();
+
+fn handler_config() -> &'static HandlerConfig {
+    HANDLER_CONFIG.get_or_init(HandlerConfig::from_env)
+}
+
 /// Initialize auth token from environment (call once at cold start)
 fn init_auth_token() {
     let token =
@@ -102,6 +110,7 @@ async fn main() -> Result<(), Error> {
 
     // Load auth token from environment (optional)
     init_auth_token();
+    handler_config();
 
     // Load stream configuration from environment
     let streams = StreamConfig::from_env().map_err(Error::from)?;
@@ -114,6 +123,7 @@ async fn main() -> Result<(), Error> {
 
 async fn handler(event: Request, sender: Arc<FirehoseSender>) -> Result<Response<Body>, Error> {
     let path = event.uri().path().to_string();
+    let verbose = verbose_requested(event.uri().query());
     let method = event.method().clone();
 
     // Health check endpoint (no auth required)
@@ -138,20 +148,27 @@ async fn handler(event: Request, sender: Arc<FirehoseSender>) -> Result<Response
     }
 
     // Parse content metadata from headers
-    let is_gzipped = event
-        .headers()
-        .get("content-encoding")
-        .and_then(|v| v.to_str().ok())
-        .map(|v| v.eq_ignore_ascii_case("gzip"))
-        .unwrap_or(false);
+    let compression = Compression::from_content_encoding(
+        event
+            .headers()
+            .get("content-encoding")
+            .and_then(|v| v.to_str().ok()),
+    );
 
-    let format = InputFormat::from_content_type(
+    let format = BodyFormat::from_content_type(
         event
             .headers()
             .get("content-type")
             .and_then(|v| v.to_str().ok()),
     );
 
+    let request_id = resolve_request_id(
+        event
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok()),
+    );
+
     // Get body as bytes
     // Body is non-exhaustive, so we must handle unknown variants
     let body = event.into_body();
@@ -173,13 +190,37 @@ async fn handler(event: Request, sender: Arc<FirehoseSender>) -> Result<Response
     // Route to appropriate handler
     let result = match path.as_str() {
         "/v1/logs" => {
-            handle_signal::<LogsHandler, _>(body_bytes, is_gzipped, format, &*sender).await
+            handle_signal::<LogsHandler, _>(
+                body_bytes,
+                compression,
+                format,
+                handler_config(),
+                &request_id,
+                &*sender,
+            )
+            .await
         }
         "/v1/traces" => {
-            handle_signal::<TracesHandler, _>(body_bytes, is_gzipped, format, &*sender).await
+            handle_signal::<TracesHandler, _>(
+                body_bytes,
+                compression,
+                format,
+                handler_config(),
+                &request_id,
+                &*sender,
+            )
+            .await
         }
         "/v1/metrics" => {
-            handle_signal::<MetricsHandler, _>(body_bytes, is_gzipped, format, &*sender).await
+            handle_signal::<MetricsHandler, _>(
+                body_bytes,
+                compression,
+                format,
+                handler_config(),
+                &request_id,
+                &*sender,
+            )
+            .await
         }
         _ => {
             return Ok(Response::builder()
@@ -190,10 +231,16 @@ async fn handler(event: Request, sender: Arc<FirehoseSender>) -> Result<Response
     };
 
     match result {
-        Ok(response) => match serde_json::to_string(&response) {
+        Ok(response) if response.is_no_content(handler_config()) => Ok(Response::builder()
+            .status(204)
+            .header(REQUEST_ID_HEADER, &request_id)
+            .body(Body::Empty)
+            .unwrap()),
+        Ok(response) => match serde_json::to_string(&response.for_verbosity(verbose)) {
             Ok(json) => Ok(Response::builder()
                 .status(200)
                 .header("content-type", "application/json")
+                .header(REQUEST_ID_HEADER, &request_id)
                 .body(Body::from(json))
                 .unwrap()),
             Err(e) => {
@@ -201,6 +248,7 @@ async fn handler(event: Request, sender: Arc<FirehoseSender>) -> Result<Response
                 Ok(Response::builder()
                     .status(500)
                     .header("content-type", "application/json")
+                    .header(REQUEST_ID_HEADER, &request_id)
                     .body(Body::from(
                         r#"{"error":"Internal error: response serialization failed"}"#,
                     ))
@@ -217,6 +265,10 @@ async fn handler(event: Request, sender: Arc<FirehoseSender>) -> Result<Response
                     warn!(error = %msg, path = %path, "decode error");
                     (400, format!("Decode error: {}", msg))
                 }
+                HandleError::TooManyTables(msg) => {
+                    warn!(error = %msg, path = %path, "too many tables");
+                    (400, format!("Too many tables: {}", msg))
+                }
                 HandleError::Transform(msg) => {
                     error!(error = %msg, path = %path, "transform error");
                     (500, format!("Transform error: {}", msg))
@@ -228,7 +280,8 @@ async fn handler(event: Request, sender: Arc<FirehoseSender>) -> Result<Response
             };
             Ok(Response::builder()
                 .status(status)
-                .body(Body::from(message))
+                .header(REQUEST_ID_HEADER, &request_id)
+                .body(Body::from(error_with_request_id(&message, &request_id)))
                 .unwrap())
         }
     }
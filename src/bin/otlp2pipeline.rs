@@ -1,8 +1,9 @@
 use anyhow::{bail, Context};
 use clap::Parser;
 use otlp2pipeline::cli::{
-    commands, config, AwsCatalogCommands, AwsCommands, AzureCommands, BucketCommands,
-    CatalogCommands, Cli, CloudflareCommands, Commands, ConnectCommands,
+    commands, config, env_file, AwsCatalogCommands, AwsCommands, AzureCommands, BucketCommands,
+    CatalogCommands, Cli, CloudflareCommands, Commands, ConfigCommands, ConnectCommands,
+    SchemaCommands,
 };
 
 /// Resolved provider from config
@@ -30,6 +31,12 @@ fn require_provider() -> anyhow::Result<Provider> {
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // Load --env-file before parsing so env-backed args see its values
+    let args: Vec<String> = std::env::args().collect();
+    if let (Some(path), override_existing) = env_file::env_file_from_args(&args) {
+        env_file::load_env_file(std::path::Path::new(&path), override_existing)?;
+    }
+
     let cli = Cli::parse();
 
     match cli.command {
@@ -67,7 +74,7 @@ async fn main() -> anyhow::Result<()> {
         },
         Commands::Query(args) => match require_provider()? {
             Provider::Cloudflare => commands::execute_query(args).await?,
-            Provider::Aws => commands::aws::execute_query(args)?,
+            Provider::Aws => commands::aws::execute_query(args).await?,
             Provider::Azure => bail!("Query command not yet implemented for Azure provider"),
         },
 
@@ -85,6 +92,9 @@ async fn main() -> anyhow::Result<()> {
                 CatalogCommands::Partition(partition_args) => {
                     commands::execute_catalog_partition(partition_args).await?
                 }
+                CatalogCommands::SetCompaction(compaction_args) => {
+                    commands::execute_catalog_set_compaction(compaction_args).await?
+                }
             },
             CloudflareCommands::Bucket(args) => match args.command {
                 BucketCommands::Delete(delete_args) => {
@@ -99,7 +109,7 @@ async fn main() -> anyhow::Result<()> {
             AwsCommands::Status(args) => commands::aws::execute_status(args)?,
             AwsCommands::Plan(args) => commands::aws::execute_plan(args)?,
             AwsCommands::Destroy(args) => commands::aws::execute_destroy(args)?,
-            AwsCommands::Query(args) => commands::aws::execute_query(args)?,
+            AwsCommands::Query(args) => commands::aws::execute_query(args).await?,
             AwsCommands::Catalog(args) => match args.command {
                 AwsCatalogCommands::List(list_args) => {
                     commands::aws::execute_catalog_list(list_args)?
@@ -127,6 +137,17 @@ async fn main() -> anyhow::Result<()> {
             ConnectCommands::Codex(codex_args) => {
                 commands::execute_connect_codex(codex_args).await?
             }
+            ConnectCommands::K8sOtelOperator(operator_args) => {
+                commands::execute_connect_k8s_otel_operator(operator_args).await?
+            }
+        },
+        Commands::Inspect(args) => commands::execute_inspect(args)?,
+        Commands::Schema(args) => match args.command {
+            SchemaCommands::Export(export_args) => commands::execute_schema_export(export_args)?,
+        },
+        Commands::DiffSchema(args) => commands::execute_diff_schema(args)?,
+        Commands::Config(args) => match args.command {
+            ConfigCommands::Show(show_args) => commands::execute_config_show(show_args)?,
         },
     }
 
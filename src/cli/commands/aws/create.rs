@@ -9,6 +9,7 @@ use super::deploy::{
 use super::helpers::{
     load_config, resolve_env_name, resolve_region, stack_name, validate_name_lengths,
 };
+use super::summary::DeploySummary;
 use crate::cli::config::{generate_auth_token, Config};
 use crate::cli::CreateArgs;
 
@@ -70,14 +71,17 @@ pub fn execute_create(args: CreateArgs) -> Result<()> {
         ctx.set_stack_outputs(info.outputs);
     }
 
+    // Phases 2-4 loop over tables; --keep-going collects per-table failures
+    let mut summary = DeploySummary::default();
+
     // Phase 2: Create tables via Athena DDL
-    create_tables_via_athena(&cli, &ctx)?;
+    create_tables_via_athena(&cli, &ctx, args.keep_going, &mut summary)?;
 
     // Phase 3: Grant LakeFormation permissions
-    grant_firehose_permissions(&cli, &ctx)?;
+    grant_firehose_permissions(&cli, &ctx, args.keep_going, &mut summary)?;
 
     // Phase 4: Create Firehose streams
-    create_firehose_streams(&cli, &ctx)?;
+    create_firehose_streams(&cli, &ctx, args.keep_going, &mut summary)?;
 
     // Phase 5: Local Lambda build (if --local)
     if args.local {
@@ -103,6 +107,9 @@ pub fn execute_create(args: CreateArgs) -> Result<()> {
         eprintln!("    Config saved to .otlp2pipeline.toml");
     }
 
+    // Report per-table failures (if any) with a nonzero exit
+    summary.finish()?;
+
     // Print success
     eprintln!("\n==========================================");
     eprintln!("Deployment complete!");
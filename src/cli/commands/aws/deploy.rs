@@ -4,6 +4,7 @@ use super::context::{
     s3_tables_data_policy, s3_tables_trust_policy, DeployContext, S3_TABLES_ROLE_NAME,
 };
 use super::schema::{Schema, TABLES};
+use super::summary::DeploySummary;
 use anyhow::{bail, Result};
 use std::thread;
 use std::time::Duration;
@@ -72,7 +73,12 @@ pub fn setup_s3_tables(cli: &AwsCli, ctx: &DeployContext) -> Result<()> {
 }
 
 /// Create tables via Athena DDL (with partition specs)
-pub fn create_tables_via_athena(cli: &AwsCli, ctx: &DeployContext) -> Result<()> {
+pub fn create_tables_via_athena(
+    cli: &AwsCli,
+    ctx: &DeployContext,
+    keep_going: bool,
+    summary: &mut DeploySummary,
+) -> Result<()> {
     eprintln!("\n==> Creating tables via Athena DDL (with partitions)");
 
     // Grant CREATE_TABLE permission on the database
@@ -98,7 +104,7 @@ pub fn create_tables_via_athena(cli: &AwsCli, ctx: &DeployContext) -> Result<()>
     let catalog = format!("s3tablescatalog/{}", ctx.bucket_name);
     let output_location = format!("s3://{}/athena/", ctx.error_bucket_name());
 
-    for table in TABLES {
+    summary.run("table", TABLES, keep_going, |table| {
         eprintln!("\n    Creating table: {}", table);
 
         let schema = Schema::load(table)?;
@@ -117,14 +123,20 @@ pub fn create_tables_via_athena(cli: &AwsCli, ctx: &DeployContext) -> Result<()>
             }
             other => bail!("Unexpected query state for {}: {:?}", table, other),
         }
-    }
+        Ok(())
+    })?;
 
     eprintln!("\n    All tables created with partitions");
     Ok(())
 }
 
 /// Grant LakeFormation permissions to Firehose role
-pub fn grant_firehose_permissions(cli: &AwsCli, ctx: &DeployContext) -> Result<()> {
+pub fn grant_firehose_permissions(
+    cli: &AwsCli,
+    ctx: &DeployContext,
+    keep_going: bool,
+    summary: &mut DeploySummary,
+) -> Result<()> {
     eprintln!("\n==> Granting LakeFormation permissions to Firehose role");
 
     let firehose_role_arn = ctx
@@ -148,7 +160,7 @@ pub fn grant_firehose_permissions(cli: &AwsCli, ctx: &DeployContext) -> Result<(
     eprintln!("    Done");
 
     // Table permissions
-    for table in TABLES {
+    summary.run("grant", TABLES, keep_going, |table| {
         eprintln!("\n    Granting ALL on table '{}'", table);
         let table_resource = serde_json::json!({
             "Table": {
@@ -159,13 +171,17 @@ pub fn grant_firehose_permissions(cli: &AwsCli, ctx: &DeployContext) -> Result<(
         });
         lf.grant_permissions(&firehose_role_arn, &table_resource, &["ALL"], false)?;
         eprintln!("    Done");
-    }
-
-    Ok(())
+        Ok(())
+    })
 }
 
 /// Create Firehose streams via API (AppendOnly mode)
-pub fn create_firehose_streams(cli: &AwsCli, ctx: &DeployContext) -> Result<()> {
+pub fn create_firehose_streams(
+    cli: &AwsCli,
+    ctx: &DeployContext,
+    keep_going: bool,
+    summary: &mut DeploySummary,
+) -> Result<()> {
     eprintln!("\n==> Creating Firehose streams via API (AppendOnly mode)");
 
     let firehose_role_arn = ctx
@@ -222,7 +238,8 @@ pub fn create_firehose_streams(cli: &AwsCli, ctx: &DeployContext) -> Result<()>
 
     let firehose = cli.firehose();
 
-    for (i, table) in TABLES.iter().enumerate() {
+    summary.run("stream", TABLES, keep_going, |table| {
+        let i = TABLES.iter().position(|t| *t == table).unwrap_or_default();
         let stream_name = ctx.firehose_stream_name(table);
         eprintln!("\n    Checking stream: {}", stream_name);
 
@@ -245,7 +262,8 @@ pub fn create_firehose_streams(cli: &AwsCli, ctx: &DeployContext) -> Result<()>
         } else {
             eprintln!("    Stream exists (skipping)");
         }
-    }
+        Ok(())
+    })?;
 
     eprintln!("\n    Firehose streams ready");
     Ok(())
@@ -9,6 +9,7 @@ mod plan;
 mod query;
 mod schema;
 mod status;
+mod summary;
 
 pub use catalog::execute_catalog_list;
 pub use context::DeployContext;
@@ -1,16 +1,23 @@
-use anyhow::{bail, Result};
-use std::process::Command;
+use anyhow::Result;
 
 use super::helpers::{load_config, resolve_env_name, resolve_region};
+use crate::cli::commands::duckdb::resolve_duckdb;
 use crate::cli::commands::naming;
+use crate::cli::commands::query_follow::run_session;
+use crate::cli::commands::query_window::{window_init_sql, window_view_names, QueryWindow};
 use crate::cli::QueryArgs;
 
 /// Execute AWS query command - launches DuckDB connected to S3 Tables
-pub fn execute_query(args: QueryArgs) -> Result<()> {
+pub async fn execute_query(args: QueryArgs) -> Result<()> {
     let config = load_config()?;
 
-    let env_name = resolve_env_name(args.env)?;
+    let env_name = resolve_env_name(args.env.clone())?;
     let region = resolve_region(None, &config);
+    let window = QueryWindow::from_args(
+        args.since.as_deref(),
+        args.until.as_deref(),
+        chrono::Utc::now(),
+    )?;
 
     // Get account_id from config or error
     let account_id = config
@@ -40,20 +47,17 @@ pub fn execute_query(args: QueryArgs) -> Result<()> {
     println!("    Region: {}", region);
     println!("    Account ID: {}", account_id);
     println!("    Table Bucket ARN: {}", table_bucket_arn);
+    if let Some(window) = &window {
+        println!("    Time window: {}", window.describe());
+        println!("    Windowed views: {}", window_view_names());
+    }
     println!();
 
-    // Check for duckdb
-    if Command::new("duckdb").arg("-version").output().is_err() {
-        bail!(
-            "duckdb not found\n\n\
-            Install DuckDB (v1.2.1+ required for S3 Tables):\n  \
-            brew install duckdb\n  \
-            # or download from https://duckdb.org/docs/installation/"
-        );
-    }
+    // Resolve duckdb (PATH, or pinned version from the local cache)
+    let duckdb = resolve_duckdb(args.duckdb_version.as_deref())?;
 
     // Build init SQL
-    let init_sql = format!(
+    let mut init_sql = format!(
         r#"-- DuckDB init for otlp2pipeline AWS environment: {}
 -- Auto-generated by otlp2pipeline CLI
 
@@ -79,8 +83,17 @@ ATTACH '{}' AS s3_tables (
 
 -- Set default schema
 USE s3_tables.default;
+{}
+"#,
+        env_name,
+        table_bucket_arn,
+        window_init_sql(window.as_ref(), "s3_tables.default")
+    );
 
--- Show available tables
+    // Interactive sessions get a banner; --sql output stays clean
+    if args.sql.is_none() {
+        init_sql.push_str(
+            r#"-- Show available tables
 .print ''
 .print '==> Connected to AWS S3 Tables'
 .print '    Catalog: s3_tables'
@@ -96,8 +109,8 @@ SHOW TABLES;
 .print '  DESCRIBE logs;'
 .print ''
 "#,
-        env_name, table_bucket_arn
-    );
+        );
+    }
 
     // Write to temp file
     let init_file = std::env::temp_dir().join(format!(
@@ -110,10 +123,7 @@ SHOW TABLES;
     println!();
 
     // Launch duckdb with init file
-    let status = Command::new("duckdb")
-        .arg("-init")
-        .arg(&init_file)
-        .status()?;
+    let result = run_session(&duckdb, &init_file, &args).await;
 
     // Cleanup
     if let Err(e) = std::fs::remove_file(&init_file) {
@@ -124,14 +134,5 @@ SHOW TABLES;
         );
     }
 
-    if !status.success() {
-        bail!(
-            "DuckDB exited with error (exit code: {}). Check the output above for details.",
-            status
-                .code()
-                .map_or("unknown".to_string(), |c| c.to_string())
-        );
-    }
-
-    Ok(())
+    result
 }
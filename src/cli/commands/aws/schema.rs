@@ -24,6 +24,13 @@ impl Schema {
             None => bail!("missing otlp2records schema: {}", schema_name),
         };
 
+        let extra =
+            crate::schema_json::extra_fields(schema_name)
+                .iter()
+                .map(|(name, field_type)| SchemaField {
+                    name: name.to_string(),
+                    field_type: field_type.to_string(),
+                });
         let fields: Vec<SchemaField> = schema_def
             .fields
             .iter()
@@ -31,6 +38,7 @@ impl Schema {
                 name: field.name.to_string(),
                 field_type: field.field_type.to_string(),
             })
+            .chain(extra)
             .collect();
 
         if fields.is_empty() {
@@ -0,0 +1,104 @@
+// src/cli/commands/aws/summary.rs
+use anyhow::{bail, Result};
+
+/// Per-resource outcomes of the deploy phases that loop over tables
+#[derive(Debug, Default)]
+pub struct DeploySummary {
+    pub succeeded: Vec<String>,
+    /// (resource, error message)
+    pub failed: Vec<(String, String)>,
+}
+
+impl DeploySummary {
+    /// Run `step` for each table, labelling results `{phase} {table}`.
+    /// Without `keep_going` the first failure aborts; with it, failures are collected.
+    pub fn run(
+        &mut self,
+        phase: &str,
+        tables: &[&str],
+        keep_going: bool,
+        mut step: impl FnMut(&str) -> Result<()>,
+    ) -> Result<()> {
+        for table in tables {
+            let resource = format!("{} {}", phase, table);
+            match step(table) {
+                Ok(()) => self.succeeded.push(resource),
+                Err(e) if keep_going => {
+                    eprintln!("    FAILED: {:#}", e);
+                    self.failed.push((resource, format!("{:#}", e)));
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Print succeeded/failed resources, then fail if anything failed
+    pub fn finish(self) -> Result<()> {
+        if self.failed.is_empty() {
+            return Ok(());
+        }
+        eprintln!("\n==> Deploy summary");
+        for resource in &self.succeeded {
+            eprintln!("    ok      {}", resource);
+        }
+        for (resource, error) in &self.failed {
+            eprintln!("    FAILED  {}: {}", resource, error);
+        }
+        bail!(
+            "{} of {} resources failed to deploy",
+            self.failed.len(),
+            self.failed.len() + self.succeeded.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TABLES: &[&str] = &["logs", "traces", "sum", "gauge"];
+
+    fn fail_traces(table: &str) -> Result<()> {
+        if table == "traces" {
+            bail!("AccessDenied");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn keep_going_collects_failures_without_aborting() {
+        let mut summary = DeploySummary::default();
+        let mut attempted = Vec::new();
+        summary
+            .run("table", TABLES, true, |table| {
+                attempted.push(table.to_string());
+                fail_traces(table)
+            })
+            .unwrap();
+
+        assert_eq!(attempted, TABLES);
+        assert_eq!(summary.succeeded.len(), 3);
+        assert_eq!(
+            summary.failed,
+            vec![("table traces".to_string(), "AccessDenied".to_string())]
+        );
+        let err = summary.finish().unwrap_err();
+        assert_eq!(err.to_string(), "1 of 4 resources failed to deploy");
+    }
+
+    #[test]
+    fn without_keep_going_first_failure_aborts() {
+        let mut summary = DeploySummary::default();
+        assert!(summary.run("table", TABLES, false, fail_traces).is_err());
+        assert_eq!(summary.succeeded, vec!["table logs".to_string()]);
+        assert!(summary.failed.is_empty());
+    }
+
+    #[test]
+    fn clean_run_finishes_ok() {
+        let mut summary = DeploySummary::default();
+        summary.run("table", TABLES, true, |_| Ok(())).unwrap();
+        assert!(summary.finish().is_ok());
+    }
+}
@@ -1,8 +1,8 @@
 use anyhow::{bail, Context, Result};
 use std::path::Path;
 
-use crate::cli::{CatalogListArgs, CatalogPartitionArgs};
-use crate::cloudflare::{AddPartitionResult, IcebergClient};
+use crate::cli::{CatalogListArgs, CatalogPartitionArgs, CatalogSetCompactionArgs};
+use crate::cloudflare::{AddPartitionResult, CompactionConfigResult, IcebergClient};
 
 /// Tables to query from the Iceberg catalog
 const TABLES: &[&str] = &["logs", "traces", "gauge", "sum"];
@@ -229,3 +229,59 @@ pub async fn execute_catalog_partition(args: CatalogPartitionArgs) -> Result<()>
 
     Ok(())
 }
+
+pub async fn execute_catalog_set_compaction(args: CatalogSetCompactionArgs) -> Result<()> {
+    let tables: Vec<&str> = if args.tables.is_empty() {
+        TABLES.to_vec()
+    } else {
+        args.tables.iter().map(String::as_str).collect()
+    };
+
+    // Read config from wrangler.toml
+    let config = read_catalog_config(&args.config)?;
+
+    eprintln!("==> Setting table compaction config");
+    eprintln!("    Account: {}", config.account_id);
+    eprintln!("    Bucket: {}", config.bucket);
+    eprintln!("    Target file size: {} MB", args.target_size_mb);
+    eprintln!();
+
+    let client = IcebergClient::new(args.r2_token, config.account_id, config.bucket)?;
+
+    let mut configured_count = 0;
+    let mut skip_count = 0;
+    let mut error_count = 0;
+
+    for table in tables {
+        eprint!("  {} ... ", table);
+        match client
+            .set_table_compaction(table, args.target_size_mb)
+            .await
+        {
+            Ok(CompactionConfigResult::Configured) => {
+                eprintln!("compaction config set");
+                configured_count += 1;
+            }
+            Ok(CompactionConfigResult::TableNotFound) => {
+                eprintln!("table not found");
+                skip_count += 1;
+            }
+            Err(e) => {
+                eprintln!("error: {}", e);
+                error_count += 1;
+            }
+        }
+    }
+
+    eprintln!();
+    eprintln!(
+        "Compaction config: {} set, {} skipped, {} errors",
+        configured_count, skip_count, error_count
+    );
+
+    if error_count > 0 {
+        bail!("Some tables failed to update compaction config");
+    }
+
+    Ok(())
+}
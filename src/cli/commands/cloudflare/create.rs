@@ -1,12 +1,17 @@
 use anyhow::{Context, Result};
 use std::io::Write;
+use std::path::Path;
 use std::process::{Command, Stdio};
 
 use crate::cli::auth;
 use crate::cli::commands::naming::{bucket_name, normalize, pipeline_name, sink_name, stream_name};
-use crate::cli::config::{generate_auth_token, Config};
+use crate::cli::config::{generate_auth_token, Config, CONFIG_FILENAME};
 use crate::cli::CreateArgs;
-use crate::cloudflare::{CloudflareClient, CorsAllowed, CorsRule, SchemaField};
+use crate::cloudflare::{CloudflareClient, CorsAllowed, CorsRule};
+
+use super::validate::{
+    preflight_config, preflight_schemas, rolling_interval_warning, validate_create_flags,
+};
 
 /// Signal configuration
 struct SignalConfig {
@@ -36,6 +41,16 @@ const SIGNALS: &[SignalConfig] = &[
         schema_file: "schemas/sum.schema.json",
         table: "sum",
     },
+    SignalConfig {
+        name: "span_links",
+        schema_file: "schemas/span_links.schema.json",
+        table: "span_links",
+    },
+    SignalConfig {
+        name: "summary",
+        schema_file: "schemas/summary.schema.json",
+        table: "summary",
+    },
 ];
 
 fn enabled_signals(args: &CreateArgs) -> Vec<&'static SignalConfig> {
@@ -45,12 +60,34 @@ fn enabled_signals(args: &CreateArgs) -> Vec<&'static SignalConfig> {
             "logs" => args.logs,
             "traces" => args.traces,
             "gauge" | "sum" => args.metrics,
+            "span_links" => args.traces && args.span_links,
+            "summary" => args.metrics && args.summaries,
             _ => false,
         })
         .collect()
 }
 
 pub async fn execute_create(args: CreateArgs) -> Result<()> {
+    validate_create_flags(args.retention, args.rolling_interval)?;
+    if let Some(warning) = rolling_interval_warning(args.rolling_interval) {
+        eprintln!("Warning: {}", warning);
+    }
+
+    // Pre-flight: a bad schema or config file must fail before anything is created
+    let config = preflight_config(Path::new(CONFIG_FILENAME))?;
+    let signals = enabled_signals(&args);
+    let schemas = preflight_schemas(
+        signals.iter().map(|s| (s.name, s.schema_file)),
+        Path::new("."),
+    )?;
+    if args.validate {
+        eprintln!(
+            "==> Pre-flight passed: {} schema file(s) and config OK",
+            schemas.len()
+        );
+        return Ok(());
+    }
+
     // Validate Cloudflare-specific requirements
     let r2_token = args.r2_token.as_ref().ok_or_else(|| {
         anyhow::anyhow!(
@@ -62,7 +99,7 @@ pub async fn execute_create(args: CreateArgs) -> Result<()> {
     let env_name = args
         .env
         .clone()
-        .or_else(|| Config::load().ok().map(|c| c.environment))
+        .or_else(|| config.map(|c| c.environment))
         .ok_or_else(|| {
             anyhow::anyhow!(
                 "No environment specified. Either:\n  \
@@ -87,7 +124,6 @@ pub async fn execute_create(args: CreateArgs) -> Result<()> {
     eprintln!("    Account ID: {}", client.account_id());
 
     let bucket = bucket_name(&env_name);
-    let signals = enabled_signals(&args);
     if auth_token.is_none() {
         eprintln!("    Auth: DISABLED (--no-auth)");
     } else {
@@ -146,8 +182,7 @@ pub async fn execute_create(args: CreateArgs) -> Result<()> {
         let name = stream_name(&env_name, signal.name);
         eprintln!("    Creating: {}", name);
 
-        let schema = load_schema(signal.schema_file)?;
-        match client.create_stream(&name, &schema).await? {
+        match client.create_stream(&name, &schemas[signal.name]).await? {
             Some(_) => eprintln!("      Created"),
             None => eprintln!("      Already exists"),
         }
@@ -296,14 +331,6 @@ fn set_wrangler_secret(token: &str) -> Result<()> {
     Ok(())
 }
 
-fn load_schema(path: &str) -> Result<Vec<SchemaField>> {
-    let content = std::fs::read_to_string(path)?;
-    let schema: serde_json::Value = serde_json::from_str(&content)?;
-    let fields: Vec<SchemaField> =
-        serde_json::from_value(schema.get("fields").cloned().unwrap_or_default())?;
-    Ok(fields)
-}
-
 const GITHUB_REPO: &str = "smithclay/otlp2pipeline";
 
 fn generate_wrangler_toml(
@@ -351,6 +378,12 @@ command = "{}"
     // R2 Catalog configuration for Iceberg queries
     toml.push_str(&format!("R2_CATALOG_ACCOUNT_ID = \"{}\"\n", account_id));
     toml.push_str(&format!("R2_CATALOG_BUCKET = \"{}\"\n", bucket));
+    if args.traces && args.span_links {
+        toml.push_str("SPAN_LINKS_TABLE = \"true\"\n");
+    }
+    if args.metrics && args.summaries {
+        toml.push_str("SUMMARY_TABLE = \"true\"\n");
+    }
 
     toml.push_str(&format!(
         r#"AGGREGATOR_ENABLED = "{}"
@@ -9,7 +9,7 @@ use crate::cli::config::Config;
 use crate::cli::DestroyArgs;
 use crate::cloudflare::CloudflareClient;
 
-const SIGNAL_NAMES: &[&str] = &["logs", "traces", "gauge", "sum"];
+const SIGNAL_NAMES: &[&str] = &["logs", "traces", "gauge", "sum", "span_links", "summary"];
 
 pub async fn execute_destroy(args: DestroyArgs) -> Result<()> {
     let env_name = args
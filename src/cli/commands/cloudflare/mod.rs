@@ -5,9 +5,12 @@ mod destroy;
 mod plan;
 mod query;
 mod status;
+mod validate;
 
 pub use bucket::execute_bucket_delete;
-pub use catalog::{execute_catalog_list, execute_catalog_partition};
+pub use catalog::{
+    execute_catalog_list, execute_catalog_partition, execute_catalog_set_compaction,
+};
 pub use create::execute_create;
 pub use destroy::execute_destroy;
 pub use plan::execute_plan;
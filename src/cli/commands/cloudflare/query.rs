@@ -1,9 +1,11 @@
 use anyhow::{bail, Result};
 use std::env;
 use std::io::{self, Write};
-use std::process::Command;
 
 use crate::cli::auth;
+use crate::cli::commands::duckdb::resolve_duckdb;
+use crate::cli::commands::query_follow::run_session;
+use crate::cli::commands::query_window::{window_init_sql, window_view_names, QueryWindow};
 use crate::cli::config::Config;
 use crate::cli::QueryArgs;
 use crate::cloudflare::CloudflareClient;
@@ -26,20 +28,22 @@ pub async fn execute_query(args: QueryArgs) -> Result<()> {
         })?;
 
     let bucket = bucket_name(&env_name);
+    let window = QueryWindow::from_args(
+        args.since.as_deref(),
+        args.until.as_deref(),
+        chrono::Utc::now(),
+    )?;
 
     println!("==> Starting DuckDB session for environment: {}", env_name);
     println!("    Bucket: {}", bucket);
+    if let Some(window) = &window {
+        println!("    Time window: {}", window.describe());
+        println!("    Windowed views: {}", window_view_names());
+    }
     println!();
 
-    // Check for duckdb
-    if Command::new("duckdb").arg("-version").output().is_err() {
-        bail!(
-            "duckdb not found\n\n\
-            Install DuckDB (v1.4.0+ required for Iceberg REST Catalog):\n  \
-            brew install duckdb\n  \
-            # or download from https://duckdb.org/docs/installation/"
-        );
-    }
+    // Resolve duckdb (PATH, or pinned version from the local cache)
+    let duckdb = resolve_duckdb(args.duckdb_version.as_deref())?;
 
     // Resolve auth to get account ID
     let creds = auth::resolve_credentials()?;
@@ -88,7 +92,7 @@ pub async fn execute_query(args: QueryArgs) -> Result<()> {
     println!();
 
     // Create init SQL file
-    let init_sql = format!(
+    let mut init_sql = format!(
         r#"-- DuckDB init for otlp2pipeline environment: {}
 -- Auto-generated by otlp2pipeline CLI
 
@@ -112,8 +116,19 @@ ATTACH '{}' AS r2 (
 
 -- Set default schema
 USE r2.default;
+{}
+"#,
+        env_name,
+        r2_token,
+        warehouse,
+        catalog_uri,
+        window_init_sql(window.as_ref(), "r2.default")
+    );
 
--- Show available tables
+    // Interactive sessions get a banner; --sql output stays clean
+    if args.sql.is_none() {
+        init_sql.push_str(
+            r#"-- Show available tables
 .print ''
 .print '==> Connected to R2 Data Catalog'
 .print '    Catalog: r2'
@@ -129,8 +144,8 @@ SHOW TABLES;
 .print '  DESCRIBE logs;'
 .print ''
 "#,
-        env_name, r2_token, warehouse, catalog_uri
-    );
+        );
+    }
 
     // Write to temp file
     let init_file = std::env::temp_dir().join(format!(
@@ -143,17 +158,10 @@ SHOW TABLES;
     println!();
 
     // Launch duckdb with init file
-    let status = Command::new("duckdb")
-        .arg("-init")
-        .arg(&init_file)
-        .status()?;
+    let result = run_session(&duckdb, &init_file, &args).await;
 
     // Cleanup
     let _ = std::fs::remove_file(&init_file);
 
-    if !status.success() {
-        bail!("DuckDB exited with error");
-    }
-
-    Ok(())
+    result
 }
@@ -2,9 +2,11 @@ use anyhow::Result;
 
 use crate::cli::auth;
 use crate::cli::commands::naming::{pipeline_name, sink_name, stream_name};
-use crate::cli::config::Config;
+use crate::cli::config::{try_load_config, Config};
+use crate::cli::url::resolve_worker_url;
 use crate::cli::StatusArgs;
 use crate::cloudflare::CloudflareClient;
+use crate::pipeline::health::TableHealth;
 
 const SIGNAL_NAMES: &[&str] = &["logs", "traces", "gauge", "sum"];
 
@@ -66,5 +68,34 @@ pub async fn execute_status(args: StatusArgs) -> Result<()> {
         }
     }
 
+    // Endpoint health, as seen by the worker; unreachable workers don't fail status
+    println!("\n==> Endpoint health:");
+    match fetch_endpoint_health(args.url.as_deref()).await {
+        Ok(report) if report.is_empty() => println!("    no sends in the last 5m"),
+        Ok(report) => {
+            for table in report {
+                println!("    {}", table.describe());
+            }
+        }
+        Err(e) => println!(
+            "    unavailable: {}",
+            e.to_string().lines().next().unwrap_or("")
+        ),
+    }
+
     Ok(())
 }
+
+/// Fetch per-table send health from the worker's `/v1/pipelines/health`.
+/// Counts come from whichever isolate answers, so they are a sample, not a total.
+async fn fetch_endpoint_health(url: Option<&str>) -> Result<Vec<TableHealth>> {
+    let base_url = resolve_worker_url(url).await?;
+    let mut request = reqwest::Client::new()
+        .get(format!("{}/v1/pipelines/health", base_url))
+        .timeout(std::time::Duration::from_secs(10));
+    if let Some(token) = try_load_config().and_then(|c| c.auth_token) {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await?.error_for_status()?;
+    Ok(response.json().await?)
+}
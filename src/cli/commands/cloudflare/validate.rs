@@ -0,0 +1,160 @@
+//! Pre-flight checks for `create`, run before any Cloudflare API call.
+
+use anyhow::{bail, Context, Result};
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+use crate::cli::config::{load_config_from_path, Config};
+use crate::cloudflare::SchemaField;
+
+/// Accepted aggregator retention, in minutes (up to one day)
+const RETENTION_MINUTES: RangeInclusive<u32> = 1..=1440;
+/// Accepted sink rolling interval, in seconds (up to one hour)
+const ROLLING_INTERVAL_SECS: RangeInclusive<u32> = 60..=3600;
+/// Rolling intervals below this write enough R2 objects per hour to slow queries
+const ROLLING_INTERVAL_WARN_SECS: u32 = 120;
+
+/// Reject out-of-range `--retention` and `--rolling-interval` values
+pub(super) fn validate_create_flags(retention: u32, rolling_interval: u32) -> Result<()> {
+    if !RETENTION_MINUTES.contains(&retention) {
+        bail!(
+            "--retention must be between {} and {} minutes (got {})",
+            RETENTION_MINUTES.start(),
+            RETENTION_MINUTES.end(),
+            retention
+        );
+    }
+    if !ROLLING_INTERVAL_SECS.contains(&rolling_interval) {
+        bail!(
+            "--rolling-interval must be between {} and {} seconds (got {})",
+            ROLLING_INTERVAL_SECS.start(),
+            ROLLING_INTERVAL_SECS.end(),
+            rolling_interval
+        );
+    }
+    Ok(())
+}
+
+/// Warning for rolling intervals short enough to create many small R2 objects
+pub(super) fn rolling_interval_warning(rolling_interval: u32) -> Option<String> {
+    (rolling_interval < ROLLING_INTERVAL_WARN_SECS).then(|| {
+        format!(
+            "--rolling-interval {}s writes ~{} R2 objects per signal per hour; \
+            consider {}s or more to keep compaction and queries fast",
+            rolling_interval,
+            3600 / rolling_interval.max(1),
+            ROLLING_INTERVAL_WARN_SECS
+        )
+    })
+}
+
+/// Load the config file if present; a malformed one aborts the run
+pub(super) fn preflight_config(path: &Path) -> Result<Option<Config>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    load_config_from_path(path).map(Some)
+}
+
+/// Load every enabled signal's schema, keyed by signal name, so a bad file
+/// aborts before any resource is created
+pub(super) fn preflight_schemas<'a>(
+    signals: impl IntoIterator<Item = (&'a str, &'a str)>,
+    root: &Path,
+) -> Result<HashMap<&'a str, Vec<SchemaField>>> {
+    signals
+        .into_iter()
+        .map(|(name, file)| {
+            let fields = load_schema(&root.join(file))
+                .with_context(|| format!("invalid schema file {} for {}", file, name))?;
+            Ok((name, fields))
+        })
+        .collect()
+}
+
+fn load_schema(path: &Path) -> Result<Vec<SchemaField>> {
+    let content = std::fs::read_to_string(path)?;
+    let schema: serde_json::Value = serde_json::from_str(&content)?;
+    let fields: Vec<SchemaField> =
+        serde_json::from_value(schema.get("fields").cloned().unwrap_or_default())?;
+    if fields.is_empty() {
+        bail!("schema has no fields");
+    }
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retention_bounds() {
+        assert!(validate_create_flags(1, 300).is_ok());
+        assert!(validate_create_flags(1440, 300).is_ok());
+        assert!(validate_create_flags(0, 300).is_err());
+        let err = validate_create_flags(1441, 300).unwrap_err();
+        assert!(err.to_string().contains("--retention"));
+    }
+
+    #[test]
+    fn test_rolling_interval_bounds() {
+        assert!(validate_create_flags(60, 60).is_ok());
+        assert!(validate_create_flags(60, 3600).is_ok());
+        assert!(validate_create_flags(60, 59).is_err());
+        let err = validate_create_flags(60, 3601).unwrap_err();
+        assert!(err.to_string().contains("--rolling-interval"));
+    }
+
+    #[test]
+    fn test_short_rolling_interval_warns() {
+        assert!(rolling_interval_warning(60)
+            .unwrap()
+            .contains("~60 R2 objects"));
+        assert!(rolling_interval_warning(120).is_none());
+        assert!(rolling_interval_warning(300).is_none());
+    }
+
+    #[test]
+    fn test_preflight_loads_repo_schemas() {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+        let schemas = preflight_schemas(
+            [
+                ("logs", "schemas/logs.schema.json"),
+                ("traces", "schemas/spans.schema.json"),
+            ],
+            root,
+        )
+        .unwrap();
+        assert!(schemas["logs"].iter().any(|f| f.name == "body"));
+        assert!(schemas["traces"].iter().any(|f| f.name == "trace_id"));
+    }
+
+    #[test]
+    fn test_malformed_schema_aborts_preflight() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("good.json"),
+            r#"{"fields":[{"name":"a","type":"string"}]}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("bad.json"), r#"{"fields": [ {"name": "#).unwrap();
+        std::fs::write(dir.path().join("empty.json"), r#"{"fields": []}"#).unwrap();
+
+        // preflight_schemas takes no client, so an error here means nothing was created
+        let err = preflight_schemas([("logs", "good.json"), ("gauge", "bad.json")], dir.path())
+            .unwrap_err();
+        assert!(err.to_string().contains("bad.json for gauge"));
+        assert!(preflight_schemas([("sum", "empty.json")], dir.path()).is_err());
+        assert!(preflight_schemas([("sum", "missing.json")], dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_malformed_config_aborts_preflight() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(".otlp2pipeline.toml");
+        assert!(preflight_config(&path).unwrap().is_none());
+        std::fs::write(&path, "provider = ").unwrap();
+        assert!(preflight_config(&path).is_err());
+    }
+}
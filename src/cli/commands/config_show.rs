@@ -0,0 +1,200 @@
+//! `config show`: print the effective configuration and where each value came from.
+
+use anyhow::Result;
+use std::path::Path;
+
+use crate::cli::config::{Config, CONFIG_FILENAME};
+use crate::cli::ConfigShowArgs;
+
+/// Where a resolved value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Flag,
+    Env(&'static str),
+    ConfigFile,
+    Default,
+    Unset,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Flag => write!(f, "flag"),
+            Source::Env(name) => write!(f, "env {}", name),
+            Source::ConfigFile => write!(f, "{}", CONFIG_FILENAME),
+            Source::Default => write!(f, "default"),
+            Source::Unset => write!(f, "unset"),
+        }
+    }
+}
+
+/// One resolved setting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Setting {
+    pub key: &'static str,
+    pub value: Option<String>,
+    pub source: Source,
+}
+
+/// First candidate with a value wins, in the order given
+fn first_of(
+    key: &'static str,
+    secret: bool,
+    candidates: impl IntoIterator<Item = (Option<String>, Source)>,
+) -> Setting {
+    let (value, source) = candidates
+        .into_iter()
+        .find(|(value, _)| value.as_deref().is_some_and(|v| !v.is_empty()))
+        .unwrap_or((None, Source::Unset));
+    let value = if secret {
+        value.as_deref().map(mask_secret)
+    } else {
+        value
+    };
+    Setting { key, value, source }
+}
+
+/// Keep only the last four characters of long secrets
+pub fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() < 12 {
+        return "****".to_string();
+    }
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("****{}", tail)
+}
+
+/// Resolve settings with the precedence commands apply: flag > env > config file > default
+pub fn resolve_settings(
+    args: &ConfigShowArgs,
+    config: Option<&Config>,
+    env: impl Fn(&str) -> Option<String>,
+) -> Vec<Setting> {
+    let file = |field: fn(&Config) -> Option<String>| (config.and_then(field), Source::ConfigFile);
+    let from_env = |name: &'static str| (env(name), Source::Env(name));
+    let flag = |value: &Option<String>| (value.clone(), Source::Flag);
+
+    vec![
+        first_of("provider", false, [file(|c| Some(c.provider.clone()))]),
+        first_of(
+            "environment",
+            false,
+            [flag(&args.env), file(|c| Some(c.environment.clone()))],
+        ),
+        first_of(
+            "worker_url",
+            false,
+            [flag(&args.url), file(|c| c.worker_url.clone())],
+        ),
+        first_of(
+            "account_id",
+            false,
+            [from_env("CF_ACCOUNT_ID"), file(|c| c.account_id.clone())],
+        ),
+        first_of(
+            "region",
+            false,
+            [
+                flag(&args.region),
+                file(|c| c.region.clone()),
+                (Some("us-east-1".to_string()), Source::Default),
+            ],
+        ),
+        first_of("stack_name", false, [file(|c| c.stack_name.clone())]),
+        first_of("namespace", false, [file(|c| c.namespace.clone())]),
+        first_of("auth_token", true, [file(|c| c.auth_token.clone())]),
+        first_of("cf_api_token", true, [from_env("CF_API_TOKEN")]),
+        first_of("r2_api_token", true, [from_env("R2_API_TOKEN")]),
+    ]
+}
+
+pub fn execute_config_show(args: ConfigShowArgs) -> Result<()> {
+    // A missing file is fine here; a malformed one is what the user is debugging
+    let config = if Path::new(CONFIG_FILENAME).exists() {
+        Some(Config::load()?)
+    } else {
+        eprintln!(
+            "==> No {} found; showing flags and env only",
+            CONFIG_FILENAME
+        );
+        None
+    };
+
+    println!("Precedence: flag > env > {} > default", CONFIG_FILENAME);
+    println!();
+    for setting in resolve_settings(&args, config.as_ref(), |name| std::env::var(name).ok()) {
+        println!(
+            "  {:<14} {:<40} ({})",
+            setting.key,
+            setting.value.as_deref().unwrap_or("-"),
+            setting.source
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        toml::from_str(
+            r#"
+provider = "aws"
+environment = "prod"
+region = "eu-west-1"
+account_id = "from-file"
+auth_token = "file-secret-token-abcd"
+"#,
+        )
+        .unwrap()
+    }
+
+    fn setting<'a>(settings: &'a [Setting], key: &str) -> &'a Setting {
+        settings.iter().find(|s| s.key == key).unwrap()
+    }
+
+    #[test]
+    fn test_secrets_are_masked() {
+        let settings = resolve_settings(&ConfigShowArgs::default(), Some(&config()), |name| {
+            (name == "CF_API_TOKEN").then(|| "short".to_string())
+        });
+        assert_eq!(
+            setting(&settings, "auth_token").value.as_deref(),
+            Some("****abcd")
+        );
+        assert_eq!(
+            setting(&settings, "cf_api_token").value.as_deref(),
+            Some("****")
+        );
+        assert_eq!(setting(&settings, "r2_api_token").source, Source::Unset);
+    }
+
+    #[test]
+    fn test_precedence() {
+        let args = ConfigShowArgs {
+            env: Some("staging".to_string()),
+            ..Default::default()
+        };
+        let settings = resolve_settings(&args, Some(&config()), |name| {
+            (name == "CF_ACCOUNT_ID").then(|| "from-env".to_string())
+        });
+
+        let env = setting(&settings, "environment");
+        assert_eq!(
+            (env.value.as_deref(), &env.source),
+            (Some("staging"), &Source::Flag)
+        );
+        let account = setting(&settings, "account_id");
+        assert_eq!(
+            (account.value.as_deref(), &account.source),
+            (Some("from-env"), &Source::Env("CF_ACCOUNT_ID"))
+        );
+        assert_eq!(setting(&settings, "region").source, Source::ConfigFile);
+
+        // Without a config file, region falls back to its default
+        let settings = resolve_settings(&ConfigShowArgs::default(), None, |_| None);
+        assert_eq!(setting(&settings, "region").source, Source::Default);
+        assert_eq!(setting(&settings, "environment").source, Source::Unset);
+    }
+}
@@ -1,8 +1,57 @@
-use anyhow::Result;
+use anyhow::{bail, Result};
+use std::time::Duration;
 
 use crate::cli::config::try_load_config;
 use crate::cli::url::resolve_worker_url;
-use crate::cli::{ConnectClaudeCodeArgs, ConnectCodexArgs, ConnectOtelCollectorArgs};
+
+#[derive(clap::Args)]
+pub struct ConnectArgs {
+    #[command(subcommand)]
+    pub command: ConnectCommands,
+}
+
+#[derive(clap::Subcommand)]
+pub enum ConnectCommands {
+    /// Generate OpenTelemetry Collector config (otel-collector-config.yaml)
+    OtelCollector(ConnectOtelCollectorArgs),
+    /// Generate shell exports for Claude Code integration
+    ClaudeCode(ConnectClaudeCodeArgs),
+    /// Generate TOML config for OpenAI Codex CLI
+    Codex(ConnectCodexArgs),
+    /// Generate an OpenTelemetryCollector resource for the Kubernetes OTel operator
+    K8sOtelOperator(super::ConnectK8sOtelOperatorArgs),
+}
+
+#[derive(clap::Args)]
+pub struct ConnectOtelCollectorArgs {
+    /// Worker URL (falls back to wrangler.toml)
+    #[arg(long)]
+    pub url: Option<String>,
+    /// Send a synthetic OTLP log to the URL and report whether it is accepted
+    #[arg(long)]
+    pub verify: bool,
+}
+
+#[derive(clap::Args)]
+pub struct ConnectClaudeCodeArgs {
+    /// Worker URL (falls back to wrangler.toml)
+    #[arg(long)]
+    pub url: Option<String>,
+
+    /// Output format
+    #[arg(long, default_value = "shell")]
+    pub format: String,
+}
+
+#[derive(clap::Args)]
+pub struct ConnectCodexArgs {
+    /// Worker URL (falls back to wrangler.toml)
+    #[arg(long)]
+    pub url: Option<String>,
+}
+
+/// Timeout for the `--verify` request
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Get auth token from config if present
 fn get_auth_token() -> Option<String> {
@@ -17,9 +66,68 @@ pub async fn execute_connect_otel_collector(args: ConnectOtelCollectorArgs) -> R
     let config = generate_collector_config(&url, auth_token.as_deref());
     println!("{}", config);
 
+    if args.verify {
+        eprintln!("==> Verifying {} accepts OTLP logs...", url);
+        let records = verify_endpoint(&url, auth_token.as_deref()).await?;
+        eprintln!("    Accepted ({} record(s))", records);
+    }
+
     Ok(())
 }
 
+/// Minimal OTLP/JSON logs request with one record
+fn synthetic_logs_payload() -> serde_json::Value {
+    let now_nanos = chrono::Utc::now()
+        .timestamp_nanos_opt()
+        .unwrap_or_default()
+        .to_string();
+    serde_json::json!({
+        "resourceLogs": [{
+            "resource": {"attributes": [{
+                "key": "service.name",
+                "value": {"stringValue": "otlp2pipeline-connect-verify"}
+            }]},
+            "scopeLogs": [{
+                "scope": {"name": "otlp2pipeline"},
+                "logRecords": [{
+                    "timeUnixNano": now_nanos,
+                    "severityNumber": 9,
+                    "severityText": "INFO",
+                    "body": {"stringValue": "connect otel-collector verification"}
+                }]
+            }]
+        }]
+    })
+}
+
+/// POST a synthetic log to `{url}/v1/logs`, returning the accepted record count
+async fn verify_endpoint(url: &str, auth_token: Option<&str>) -> Result<usize> {
+    let client = reqwest::Client::builder().timeout(VERIFY_TIMEOUT).build()?;
+    let mut request = client
+        .post(format!("{}/v1/logs", url.trim_end_matches('/')))
+        .json(&synthetic_logs_payload());
+    if let Some(token) = auth_token {
+        request = request.bearer_auth(token);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    if !status.is_success() {
+        bail!("Verification failed: HTTP {}: {}", status, body);
+    }
+
+    let records = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| {
+            json["records"]
+                .as_object()
+                .map(|r| r.values().filter_map(|v| v.as_u64()).sum::<u64>())
+        })
+        .unwrap_or_default();
+    Ok(records as usize)
+}
+
 /// Generate Claude Code shell exports
 pub async fn execute_connect_claude_code(args: ConnectClaudeCodeArgs) -> Result<()> {
     let url = resolve_worker_url(args.url.as_deref()).await?;
@@ -296,4 +404,32 @@ mod tests {
         assert!(config.contains("[otel.exporter.\"otlp-http\".headers]"));
         assert!(config.contains("\"Authorization\" = \"Bearer test-token-123\""));
     }
+
+    #[tokio::test]
+    async fn test_verify_endpoint_reports_success() {
+        use axum::{http::HeaderMap, routing::post, Json, Router};
+
+        let app = Router::new().route(
+            "/v1/logs",
+            post(
+                |headers: HeaderMap, Json(body): Json<serde_json::Value>| async move {
+                    assert_eq!(headers["authorization"], "Bearer secret");
+                    let count = body["resourceLogs"][0]["scopeLogs"][0]["logRecords"]
+                        .as_array()
+                        .map_or(0, Vec::len);
+                    Json(serde_json::json!({"status": "ok", "records": {"logs": count}}))
+                },
+            ),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        assert_eq!(verify_endpoint(&url, Some("secret")).await.unwrap(), 1);
+        assert!(verify_endpoint(&format!("{}missing", url), None)
+            .await
+            .is_err());
+    }
 }
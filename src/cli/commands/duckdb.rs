@@ -0,0 +1,172 @@
+//! DuckDB binary resolution for `query`.
+//!
+//! `--duckdb-version` pins a specific release, downloaded once and cached under
+//! `~/.otlp2pipeline/duckdb/v{version}/`. Without it, a `duckdb` on PATH is used,
+//! falling back to the pinned default release.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Known-good DuckDB release (Iceberg REST Catalog + S3 Tables support)
+pub const DEFAULT_DUCKDB_VERSION: &str = "1.4.1";
+
+#[cfg(windows)]
+const BINARY_NAME: &str = "duckdb.exe";
+#[cfg(not(windows))]
+const BINARY_NAME: &str = "duckdb";
+
+/// Cache directory for a DuckDB version under the given home directory
+pub fn cache_dir(home: &Path, version: &str) -> PathBuf {
+    home.join(".otlp2pipeline")
+        .join("duckdb")
+        .join(format!("v{}", version.trim_start_matches('v')))
+}
+
+/// Cached binary path for a DuckDB version
+pub fn cached_binary(home: &Path, version: &str) -> PathBuf {
+    cache_dir(home, version).join(BINARY_NAME)
+}
+
+/// Release download URL for the current platform
+fn download_url(version: &str) -> Result<String> {
+    let platform = match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("linux", "x86_64") => "linux-amd64",
+        ("linux", "aarch64") => "linux-aarch64",
+        ("macos", _) => "osx-universal",
+        ("windows", "x86_64") => "windows-amd64",
+        (os, arch) => bail!("No DuckDB release available for {}/{}", os, arch),
+    };
+    Ok(format!(
+        "https://github.com/duckdb/duckdb/releases/download/v{}/duckdb_cli-{}.zip",
+        version.trim_start_matches('v'),
+        platform
+    ))
+}
+
+/// Resolve the DuckDB binary to launch.
+///
+/// An explicit version always uses the cache; otherwise a `duckdb` on PATH wins.
+pub fn resolve_duckdb(version: Option<&str>) -> Result<PathBuf> {
+    if version.is_none() && Command::new("duckdb").arg("-version").output().is_ok() {
+        return Ok(PathBuf::from("duckdb"));
+    }
+
+    let home = dirs::home_dir().context("Could not determine home directory")?;
+    let version = version.unwrap_or(DEFAULT_DUCKDB_VERSION);
+    ensure_cached(&home, version, download_duckdb)
+}
+
+/// Return the cached binary, downloading it first if not present
+fn ensure_cached(
+    home: &Path,
+    version: &str,
+    download: impl FnOnce(&str, &Path) -> Result<()>,
+) -> Result<PathBuf> {
+    let binary = cached_binary(home, version);
+    if binary.exists() {
+        return Ok(binary);
+    }
+
+    let dir = cache_dir(home, version);
+    std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    download(version, &dir)?;
+
+    if !binary.exists() {
+        bail!(
+            "DuckDB download did not produce {}; remove {} and retry",
+            binary.display(),
+            dir.display()
+        );
+    }
+    Ok(binary)
+}
+
+/// Download and unpack a DuckDB release into `dir` using curl and unzip
+fn download_duckdb(version: &str, dir: &Path) -> Result<()> {
+    let url = download_url(version)?;
+    let archive = dir.join("duckdb_cli.zip");
+
+    eprintln!(
+        "==> Downloading DuckDB v{}",
+        version.trim_start_matches('v')
+    );
+    eprintln!("    {}", url);
+
+    let status = Command::new("curl")
+        .args(["-fsSL", "-o"])
+        .arg(&archive)
+        .arg(&url)
+        .status()
+        .context("Failed to run curl (is it installed?)")?;
+    if !status.success() {
+        bail!("Failed to download DuckDB from {}", url);
+    }
+
+    let status = Command::new("unzip")
+        .args(["-o", "-q"])
+        .arg(&archive)
+        .arg("-d")
+        .arg(dir)
+        .status()
+        .context("Failed to run unzip (is it installed?)")?;
+    let _ = std::fs::remove_file(&archive);
+    if !status.success() {
+        bail!("Failed to unpack DuckDB archive");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_path_resolution() {
+        let home = Path::new("/home/user");
+        assert_eq!(
+            cache_dir(home, "1.4.1"),
+            PathBuf::from("/home/user/.otlp2pipeline/duckdb/v1.4.1")
+        );
+        // A leading "v" is accepted and normalized
+        assert_eq!(cached_binary(home, "v1.4.1"), cached_binary(home, "1.4.1"));
+        assert!(cached_binary(home, "1.4.1").ends_with(BINARY_NAME));
+    }
+
+    #[test]
+    fn test_skips_download_when_cached() {
+        let home = tempfile::tempdir().unwrap();
+        let binary = cached_binary(home.path(), "1.4.1");
+        std::fs::create_dir_all(binary.parent().unwrap()).unwrap();
+        std::fs::write(&binary, b"").unwrap();
+
+        let resolved = ensure_cached(home.path(), "1.4.1", |_, _| {
+            panic!("download should be skipped when already cached")
+        })
+        .unwrap();
+        assert_eq!(resolved, binary);
+    }
+
+    #[test]
+    fn test_downloads_when_missing() {
+        let home = tempfile::tempdir().unwrap();
+        let mut downloaded = None;
+
+        let resolved = ensure_cached(home.path(), "1.3.0", |version, dir| {
+            downloaded = Some(version.to_string());
+            std::fs::write(dir.join(BINARY_NAME), b"").unwrap();
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(downloaded.as_deref(), Some("1.3.0"));
+        assert_eq!(resolved, cached_binary(home.path(), "1.3.0"));
+    }
+
+    #[test]
+    fn test_errors_when_download_produces_no_binary() {
+        let home = tempfile::tempdir().unwrap();
+        assert!(ensure_cached(home.path(), "1.3.0", |_, _| Ok(())).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! `inspect`: decode a captured OTLP payload locally, without deploying.
+
+use anyhow::{bail, Context, Result};
+use otlp2records::convert::vrl_value_to_json_lossy;
+use otlp2records::{decode_logs, decode_metrics, decode_traces};
+use serde_json::{json, Value as JsonValue};
+
+use crate::cli::InspectArgs;
+use crate::{
+    Bytes, HandlerConfig, InputFormat, LogsHandler, MetricsHandler, SignalHandler, TracesHandler,
+};
+
+pub fn execute_inspect(args: InspectArgs) -> Result<()> {
+    let format = parse_format(&args.format)?;
+    let ndjson = match args.output_format.to_ascii_lowercase().as_str() {
+        "pretty" => false,
+        "ndjson" => true,
+        other => bail!(
+            "Unknown output format '{}' (expected pretty or ndjson)",
+            other
+        ),
+    };
+    let body = std::fs::read(&args.input)
+        .with_context(|| format!("Failed to read {}", args.input.display()))?;
+
+    let output = inspect(&args.signal, &body, format, args.transform)?;
+    if ndjson {
+        print!("{}", to_ndjson(&output)?);
+    } else {
+        println!("{}", serde_json::to_string_pretty(&output)?);
+    }
+    Ok(())
+}
+
+/// One record per line: transformed records tagged with `_table` when present,
+/// otherwise the decoded records
+fn to_ndjson(output: &JsonValue) -> Result<String> {
+    let mut records = Vec::new();
+    match output.get("transformed").and_then(JsonValue::as_object) {
+        Some(tables) => {
+            let mut names: Vec<_> = tables.keys().collect();
+            names.sort();
+            for name in names {
+                for record in tables[name].as_array().into_iter().flatten() {
+                    let mut record = record.clone();
+                    if let Some(obj) = record.as_object_mut() {
+                        obj.insert("_table".to_string(), JsonValue::from(name.as_str()));
+                    }
+                    records.push(record);
+                }
+            }
+        }
+        None => records.extend(output["decoded"].as_array().into_iter().flatten().cloned()),
+    }
+
+    let mut out = String::new();
+    for record in records {
+        out.push_str(&serde_json::to_string(&record)?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+fn parse_format(format: &str) -> Result<InputFormat> {
+    match format.to_ascii_lowercase().as_str() {
+        "protobuf" | "pb" => Ok(InputFormat::Protobuf),
+        "json" => Ok(InputFormat::Json),
+        "jsonl" | "ndjson" => Ok(InputFormat::Jsonl),
+        "auto" => Ok(InputFormat::Auto),
+        other => bail!(
+            "Unknown format '{}' (expected protobuf, json, jsonl, or auto)",
+            other
+        ),
+    }
+}
+
+/// Decode a payload to JSON, optionally adding the transformed table-grouped records
+fn inspect(signal: &str, body: &[u8], format: InputFormat, transform: bool) -> Result<JsonValue> {
+    let values = match signal {
+        "logs" => decode_logs(body, format)?,
+        "traces" => decode_traces(body, format)?,
+        "metrics" => {
+            let result = decode_metrics(body, format)?;
+            if result.skipped.has_skipped() {
+                eprintln!(
+                    "==> Skipped {} unsupported or invalid metric data points",
+                    result.skipped.total()
+                );
+            }
+            result.values
+        }
+        other => bail!(
+            "Unknown signal '{}' (expected logs, traces, or metrics)",
+            other
+        ),
+    };
+    let decoded: Vec<JsonValue> = values.iter().map(vrl_value_to_json_lossy).collect();
+
+    let mut output = json!({ "decoded": decoded });
+    if transform {
+        let body = Bytes::copy_from_slice(body);
+        let config = HandlerConfig::from_env();
+        let result = match signal {
+            "logs" => LogsHandler::transform(body, format, &config),
+            "traces" => TracesHandler::transform(body, format, &config),
+            _ => MetricsHandler::transform(body, format, &config),
+        }
+        .context("Transform failed")?;
+        output["transformed"] = serde_json::to_value(result.grouped)?;
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_format() {
+        assert_eq!(parse_format("protobuf").unwrap(), InputFormat::Protobuf);
+        assert_eq!(parse_format("JSON").unwrap(), InputFormat::Json);
+        assert_eq!(parse_format("auto").unwrap(), InputFormat::Auto);
+        assert!(parse_format("xml").is_err());
+    }
+
+    #[test]
+    fn test_ndjson_output_is_line_delimited() {
+        let body = json!({
+            "resourceLogs": [{
+                "scopeLogs": [{
+                    "logRecords": [
+                        {"timeUnixNano": "1703265600000000000", "body": {"stringValue": "a"}},
+                        {"timeUnixNano": "1703265600000000000", "body": {"stringValue": "b"}}
+                    ]
+                }]
+            }]
+        })
+        .to_string();
+
+        for transform in [false, true] {
+            let output = inspect("logs", body.as_bytes(), InputFormat::Json, transform).unwrap();
+            let ndjson = to_ndjson(&output).unwrap();
+            let lines: Vec<JsonValue> = ndjson
+                .lines()
+                .map(|line| serde_json::from_str(line).expect("each line is JSON"))
+                .collect();
+            assert_eq!(lines.len(), 2);
+            assert!(ndjson.ends_with('\n'));
+            if transform {
+                assert_eq!(lines[1]["_table"], "logs");
+                assert_eq!(lines[1]["body"], "b");
+            }
+        }
+    }
+
+    #[test]
+    fn test_inspect_rejects_unknown_signal() {
+        assert!(inspect("profiles", b"{}", InputFormat::Json, false).is_err());
+    }
+}
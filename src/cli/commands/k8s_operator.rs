@@ -0,0 +1,143 @@
+//! `connect k8s-otel-operator`: an `OpenTelemetryCollector` custom resource
+//! for the OpenTelemetry Operator, exporting to the worker over OTLP/HTTP.
+
+use anyhow::Result;
+
+use crate::cli::config::try_load_config;
+use crate::cli::url::resolve_worker_url;
+
+#[derive(clap::Args)]
+pub struct ConnectK8sOtelOperatorArgs {
+    /// Worker URL (falls back to wrangler.toml)
+    #[arg(long)]
+    pub url: Option<String>,
+
+    /// Name of the OpenTelemetryCollector resource
+    #[arg(long, default_value = "otlp2pipeline")]
+    pub name: String,
+
+    /// Namespace of the OpenTelemetryCollector resource
+    #[arg(long, default_value = "default")]
+    pub namespace: String,
+}
+
+/// Print the OpenTelemetryCollector CR YAML
+pub async fn execute_connect_k8s_otel_operator(args: ConnectK8sOtelOperatorArgs) -> Result<()> {
+    let url = resolve_worker_url(args.url.as_deref()).await?;
+    let auth_token = try_load_config().and_then(|c| c.auth_token);
+
+    let cr = generate_operator_cr(&args.name, &args.namespace, &url, auth_token.as_deref());
+    println!("{}", cr);
+
+    Ok(())
+}
+
+fn generate_operator_cr(
+    name: &str,
+    namespace: &str,
+    endpoint: &str,
+    auth_token: Option<&str>,
+) -> String {
+    let headers = match auth_token {
+        Some(token) => format!(
+            r#"
+        headers:
+          Authorization: "Bearer {}""#,
+            token
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        r#"# OpenTelemetry Operator collector for otlp2pipeline
+# Apply with:
+#   kubectl apply -f otlp2pipeline-collector.yaml
+
+apiVersion: opentelemetry.io/v1beta1
+kind: OpenTelemetryCollector
+metadata:
+  name: {name}
+  namespace: {namespace}
+spec:
+  mode: deployment
+  config:
+    receivers:
+      otlp:
+        protocols:
+          grpc:
+            endpoint: 0.0.0.0:4317
+          http:
+            endpoint: 0.0.0.0:4318
+
+    processors:
+      batch:
+        send_batch_size: 1000
+        send_batch_max_size: 2000
+        timeout: 5s
+
+    exporters:
+      otlphttp:
+        endpoint: {endpoint}
+        compression: gzip{headers}
+
+    service:
+      pipelines:
+        logs:
+          receivers: [otlp]
+          processors: [batch]
+          exporters: [otlphttp]
+        traces:
+          receivers: [otlp]
+          processors: [batch]
+          exporters: [otlphttp]
+        metrics:
+          receivers: [otlp]
+          processors: [batch]
+          exporters: [otlphttp]
+"#,
+        name = name,
+        namespace = namespace,
+        endpoint = endpoint,
+        headers = headers
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_yaml::Value;
+
+    #[test]
+    fn test_generated_cr_parses_with_exporter_endpoint() {
+        let cr = generate_operator_cr(
+            "otlp2pipeline",
+            "observability",
+            "https://my-worker.workers.dev",
+            None,
+        );
+        let doc: Value = serde_yaml::from_str(&cr).expect("CR should be valid YAML");
+
+        assert_eq!(doc["kind"].as_str(), Some("OpenTelemetryCollector"));
+        assert_eq!(doc["metadata"]["namespace"].as_str(), Some("observability"));
+        let exporter = &doc["spec"]["config"]["exporters"]["otlphttp"];
+        assert_eq!(
+            exporter["endpoint"].as_str(),
+            Some("https://my-worker.workers.dev")
+        );
+        assert!(exporter.get("headers").is_none());
+        for signal in ["logs", "traces", "metrics"] {
+            let pipeline = &doc["spec"]["config"]["service"]["pipelines"][signal];
+            assert_eq!(pipeline["exporters"][0].as_str(), Some("otlphttp"));
+        }
+    }
+
+    #[test]
+    fn test_generated_cr_with_auth() {
+        let cr = generate_operator_cr("c", "default", "https://w.dev", Some("test-token-123"));
+        let doc: Value = serde_yaml::from_str(&cr).expect("CR should be valid YAML");
+        assert_eq!(
+            doc["spec"]["config"]["exporters"]["otlphttp"]["headers"]["Authorization"].as_str(),
+            Some("Bearer test-token-123")
+        );
+    }
+}
@@ -1,21 +1,37 @@
 pub mod aws;
 pub mod azure;
 pub mod cloudflare;
+mod config_show;
 mod connect;
+mod duckdb;
 mod init;
+mod inspect;
+mod k8s_operator;
 mod naming;
+mod query_follow;
+mod query_window;
+mod schema;
+mod schema_diff;
 mod services;
 mod tail;
 
+pub use config_show::execute_config_show;
 pub use connect::{
     execute_connect_claude_code, execute_connect_codex, execute_connect_otel_collector,
+    ConnectArgs, ConnectClaudeCodeArgs, ConnectCodexArgs, ConnectCommands,
+    ConnectOtelCollectorArgs,
 };
 pub use init::{execute_init, InitArgs};
-pub use services::execute_services;
+pub use inspect::execute_inspect;
+pub use k8s_operator::{execute_connect_k8s_otel_operator, ConnectK8sOtelOperatorArgs};
+pub use schema::execute_schema_export;
+pub use schema_diff::execute_diff_schema;
+pub use services::{execute_services, ServicesArgs, ServicesCommands, ServicesPruneArgs};
 pub use tail::execute_tail;
 
 // Re-export cloudflare commands for convenience
 pub use cloudflare::{
-    execute_bucket_delete, execute_catalog_list, execute_catalog_partition, execute_create,
-    execute_destroy, execute_plan, execute_query, execute_status,
+    execute_bucket_delete, execute_catalog_list, execute_catalog_partition,
+    execute_catalog_set_compaction, execute_create, execute_destroy, execute_plan, execute_query,
+    execute_status,
 };
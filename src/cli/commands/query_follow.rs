@@ -0,0 +1,202 @@
+//! Non-interactive `query --sql`, optionally re-run with `--follow`.
+//!
+//! Each run starts a fresh DuckDB process from the init file, so the catalog
+//! is re-attached and every run reads the latest table snapshot. With
+//! `--output-format ndjson`, DuckDB's JSON output is re-emitted one row per line.
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value as JsonValue;
+use std::future::Future;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+use crate::cli::QueryArgs;
+
+/// Whether the follow loop should keep going after a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Continue,
+    Stop,
+}
+
+/// Launch DuckDB for a query session: interactive, one `--sql` run, or `--follow`
+pub async fn run_session(duckdb: &Path, init_file: &Path, args: &QueryArgs) -> Result<()> {
+    let Some(sql) = args.sql.as_deref() else {
+        let status = Command::new(duckdb).arg("-init").arg(init_file).status()?;
+        if !status.success() {
+            bail!(
+                "DuckDB exited with error (exit code: {}). Check the output above for details.",
+                status
+                    .code()
+                    .map_or("unknown".to_string(), |c| c.to_string())
+            );
+        }
+        return Ok(());
+    };
+
+    let ndjson = args.output_format == "ndjson";
+    if !args.follow {
+        run_sql(duckdb, init_file, sql, ndjson)?;
+        return Ok(());
+    }
+
+    let interval = Duration::from_secs(args.interval);
+    eprintln!(
+        "==> Following every {}s (Ctrl-C to stop)",
+        interval.as_secs()
+    );
+    let runs = follow(interval, tokio::signal::ctrl_c(), |run| {
+        eprintln!(
+            "\n==> Run {} at {}",
+            run,
+            chrono::Utc::now().format("%H:%M:%S")
+        );
+        run_sql(duckdb, init_file, sql, ndjson)
+    })
+    .await;
+    eprintln!("\n==> Stopped after {} runs", runs);
+    Ok(())
+}
+
+/// Run `sql` once. A run killed by a signal (Ctrl-C reaches DuckDB too) stops following.
+fn run_sql(duckdb: &Path, init_file: &Path, sql: &str, ndjson: bool) -> Result<Step> {
+    let mut command = Command::new(duckdb);
+    command.arg("-init").arg(init_file);
+    let status = if ndjson {
+        let output = command
+            .arg("-json")
+            .arg("-c")
+            .arg(sql)
+            .stderr(Stdio::inherit())
+            .output()?;
+        if output.status.success() {
+            print!("{}", rows_to_ndjson(&output.stdout)?);
+        }
+        output.status
+    } else {
+        command.arg("-c").arg(sql).status()?
+    };
+    match status.code() {
+        None => Ok(Step::Stop),
+        Some(0) => Ok(Step::Continue),
+        Some(code) => bail!("DuckDB exited with code {}", code),
+    }
+}
+
+/// Flatten DuckDB `-json` output (one array per statement) to one row per line
+fn rows_to_ndjson(stdout: &[u8]) -> Result<String> {
+    let mut out = String::new();
+    for value in serde_json::Deserializer::from_slice(stdout).into_iter::<JsonValue>() {
+        let rows = match value.context("DuckDB returned invalid JSON")? {
+            JsonValue::Array(rows) => rows,
+            row => vec![row],
+        };
+        for row in rows {
+            out.push_str(&serde_json::to_string(&row)?);
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+/// Call `run` now and then every `interval` until it returns `Step::Stop` or
+/// `shutdown` resolves. Failed runs are reported and retried on the next tick,
+/// since a query can race a catalog commit. Returns the number of runs.
+pub async fn follow<F>(
+    interval: Duration,
+    shutdown: F,
+    mut run: impl FnMut(u64) -> Result<Step>,
+) -> u64
+where
+    F: Future,
+{
+    tokio::pin!(shutdown);
+    let mut runs = 0;
+    loop {
+        runs += 1;
+        match run(runs) {
+            Ok(Step::Continue) => {}
+            Ok(Step::Stop) => return runs,
+            Err(e) => eprintln!("    Run {} failed: {:#} (retrying)", runs, e),
+        }
+        tokio::select! {
+            _ = &mut shutdown => return runs,
+            _ = tokio::time::sleep(interval) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::time::Instant;
+
+    const INTERVAL: Duration = Duration::from_secs(10);
+
+    #[tokio::test(start_paused = true)]
+    async fn test_follow_runs_on_interval_until_shutdown() {
+        let start = Instant::now();
+        let mut run_times = Vec::new();
+        let shutdown = tokio::time::sleep(INTERVAL * 5 / 2);
+
+        let runs = follow(INTERVAL, shutdown, |_| {
+            run_times.push(start.elapsed());
+            Ok(Step::Continue)
+        })
+        .await;
+
+        assert_eq!(runs, 3);
+        assert_eq!(run_times, vec![Duration::ZERO, INTERVAL, INTERVAL * 2]);
+        assert_eq!(start.elapsed(), INTERVAL * 5 / 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_follow_stops_when_run_is_interrupted() {
+        let start = Instant::now();
+        let runs = follow(INTERVAL, std::future::pending::<()>(), |run| {
+            Ok(if run == 2 { Step::Stop } else { Step::Continue })
+        })
+        .await;
+
+        assert_eq!(runs, 2);
+        assert_eq!(start.elapsed(), INTERVAL);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_follow_retries_after_failed_run() {
+        let shutdown = tokio::time::sleep(INTERVAL * 3 / 2);
+        let mut attempts = 0;
+        let runs = follow(INTERVAL, shutdown, |_| {
+            attempts += 1;
+            if attempts == 1 {
+                bail!("snapshot changed");
+            }
+            Ok(Step::Continue)
+        })
+        .await;
+
+        assert_eq!(runs, 2);
+    }
+
+    #[test]
+    fn test_rows_to_ndjson_emits_one_object_per_row() {
+        let stdout = b"[{\"service_name\":\"api\",\"n\":2},\n{\"service_name\":\"web\",\"n\":1}]\n[{\"total\":3}]\n";
+        let ndjson = rows_to_ndjson(stdout).unwrap();
+
+        let rows: Vec<JsonValue> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                serde_json::json!({"service_name": "api", "n": 2}),
+                serde_json::json!({"service_name": "web", "n": 1}),
+                serde_json::json!({"total": 3}),
+            ]
+        );
+        assert_eq!(rows_to_ndjson(b"").unwrap(), "");
+        assert!(rows_to_ndjson(b"[{\"a\":").is_err());
+    }
+}
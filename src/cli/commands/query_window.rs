@@ -0,0 +1,275 @@
+//! Time-window scoping for DuckDB query sessions.
+//!
+//! `--since`/`--until` pre-create `windowed.<table>` views over the catalog
+//! tables so ad-hoc queries can be bounded to the window. The session's
+//! default schema stays the catalog's, so unqualified names (including tables
+//! without a window view, like `histogram`) still resolve to the full tables.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Duration, Utc};
+
+/// Database holding the time-scoped views
+const WINDOW_DATABASE: &str = "windowed";
+
+/// Catalog tables that get a time-scoped view
+const WINDOW_TABLES: &[&str] = &["logs", "traces", "gauge", "sum"];
+
+/// Format used for DuckDB TIMESTAMP literals
+const SQL_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f";
+
+/// Time window for a query session
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryWindow {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl QueryWindow {
+    /// Build a window from `--since` and `--until` args.
+    /// Returns `None` when neither is set.
+    pub fn from_args(
+        since: Option<&str>,
+        until: Option<&str>,
+        now: DateTime<Utc>,
+    ) -> Result<Option<Self>> {
+        if since.is_none() && until.is_none() {
+            return Ok(None);
+        }
+
+        let start = since
+            .map(|s| {
+                parse_duration(s).and_then(|duration| {
+                    now.checked_sub_signed(duration)
+                        .with_context(|| format!("Duration '{}' is out of range", s))
+                })
+            })
+            .transpose()?;
+        let end = until
+            .map(|ts| {
+                DateTime::parse_from_rfc3339(ts)
+                    .map(|t| t.with_timezone(&Utc))
+                    .with_context(|| {
+                        format!(
+                            "Invalid --until '{}': expected RFC 3339 (e.g. 2024-01-01T12:00:00Z)",
+                            ts
+                        )
+                    })
+            })
+            .transpose()?;
+
+        if let (Some(start), Some(end)) = (start, end) {
+            if start >= end {
+                bail!(
+                    "Empty time window: --since resolves to {} which is not before --until {}",
+                    start.to_rfc3339(),
+                    end.to_rfc3339()
+                );
+            }
+        }
+
+        Ok(Some(Self { start, end }))
+    }
+
+    /// SQL WHERE predicate on the `timestamp` column
+    fn predicate(&self) -> String {
+        let mut clauses = Vec::new();
+        if let Some(start) = self.start {
+            clauses.push(format!(
+                "\"timestamp\" >= TIMESTAMP '{}'",
+                start.format(SQL_TIMESTAMP_FORMAT)
+            ));
+        }
+        if let Some(end) = self.end {
+            clauses.push(format!(
+                "\"timestamp\" < TIMESTAMP '{}'",
+                end.format(SQL_TIMESTAMP_FORMAT)
+            ));
+        }
+        clauses.join(" AND ")
+    }
+
+    /// Human-readable description for session output
+    pub fn describe(&self) -> String {
+        let start = self
+            .start
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_else(|| "(unbounded)".to_string());
+        let end = self
+            .end
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_else(|| "now".to_string());
+        format!("{} -> {}", start, end)
+    }
+
+    /// Build DuckDB init SQL creating time-scoped views over the catalog schema.
+    ///
+    /// Views are created under the same table names in a separate in-memory
+    /// `windowed` database, leaving the session's default schema unchanged.
+    pub fn views_sql(&self, catalog_schema: &str) -> String {
+        let predicate = self.predicate();
+        let mut sql = format!("-- Time window: {}\n", self.describe());
+        sql.push_str(&format!(
+            "ATTACH IF NOT EXISTS ':memory:' AS {WINDOW_DATABASE};\n"
+        ));
+        for table in WINDOW_TABLES {
+            sql.push_str(&format!(
+                "CREATE OR REPLACE VIEW {WINDOW_DATABASE}.{table} AS SELECT * FROM {catalog_schema}.{table} WHERE {predicate};\n"
+            ));
+        }
+        sql
+    }
+}
+
+/// Init-file SQL for an optional window, empty when no window is set
+pub fn window_init_sql(window: Option<&QueryWindow>, catalog_schema: &str) -> String {
+    window
+        .map(|w| format!("\n{}", w.views_sql(catalog_schema)))
+        .unwrap_or_default()
+}
+
+/// Qualified names of the windowed views, for session output
+pub fn window_view_names() -> String {
+    WINDOW_TABLES
+        .iter()
+        .map(|table| format!("{WINDOW_DATABASE}.{table}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse a duration like `30s`, `15m`, `2h`, `7d`
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    let (value, unit) = input.split_at(split);
+
+    let value: i64 = value
+        .parse()
+        .with_context(|| format!("Invalid duration '{}': expected e.g. 15m, 2h, 1d", input))?;
+    if value <= 0 {
+        bail!("Invalid duration '{}': must be greater than zero", input);
+    }
+
+    let duration = match unit {
+        "s" => Duration::try_seconds(value),
+        "m" => Duration::try_minutes(value),
+        "h" => Duration::try_hours(value),
+        "d" => Duration::try_days(value),
+        _ => bail!(
+            "Invalid duration unit in '{}': use s, m, h, or d (e.g. 15m)",
+            input
+        ),
+    };
+    duration.with_context(|| format!("Duration '{}' is out of range", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("30s").unwrap(), Duration::seconds(30));
+        assert_eq!(parse_duration("15m").unwrap(), Duration::minutes(15));
+        assert_eq!(parse_duration("2h").unwrap(), Duration::hours(2));
+        assert_eq!(parse_duration("7d").unwrap(), Duration::days(7));
+    }
+
+    #[test]
+    fn test_parse_duration_rejects_invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("15").is_err());
+        assert!(parse_duration("0m").is_err());
+        assert!(parse_duration("5w").is_err());
+        assert!(parse_duration("m").is_err());
+    }
+
+    #[test]
+    fn test_no_window_when_no_args() {
+        assert_eq!(
+            QueryWindow::from_args(None, None, fixed_now()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_views_sql_with_since_and_until() {
+        let window = QueryWindow::from_args(Some("1h"), Some("2024-06-01T11:30:00Z"), fixed_now())
+            .unwrap()
+            .unwrap();
+        let sql = window.views_sql("r2.default");
+
+        assert!(sql.contains(
+            "CREATE OR REPLACE VIEW windowed.logs AS SELECT * FROM r2.default.logs \
+             WHERE \"timestamp\" >= TIMESTAMP '2024-06-01 11:00:00.000000' \
+             AND \"timestamp\" < TIMESTAMP '2024-06-01 11:30:00.000000';"
+        ));
+        for table in WINDOW_TABLES {
+            assert!(sql.contains(&format!("VIEW windowed.{} AS", table)));
+        }
+    }
+
+    #[test]
+    fn test_non_windowed_tables_still_resolve() {
+        let window = QueryWindow::from_args(Some("1h"), None, fixed_now())
+            .unwrap()
+            .unwrap();
+        let init = format!(
+            "USE r2.default;\n{}",
+            window_init_sql(Some(&window), "r2.default")
+        );
+
+        // The catalog stays the default schema, so unqualified `histogram`
+        // (or any extra table) still resolves to r2.default
+        let last_use = init
+            .lines()
+            .rfind(|line| line.trim_start().to_ascii_uppercase().starts_with("USE "));
+        assert_eq!(last_use, Some("USE r2.default;"));
+        for table in ["histogram", "exp_histogram", "spans_links"] {
+            assert!(!init.contains(&format!(".{} AS", table)), "{}", table);
+        }
+    }
+
+    #[test]
+    fn test_views_sql_with_since_only() {
+        let window = QueryWindow::from_args(Some("15m"), None, fixed_now())
+            .unwrap()
+            .unwrap();
+        let sql = window.views_sql("s3_tables.default");
+
+        assert!(sql.contains(
+            "FROM s3_tables.default.traces WHERE \"timestamp\" >= TIMESTAMP '2024-06-01 11:45:00.000000';"
+        ));
+        assert!(!sql.contains("\"timestamp\" <"));
+    }
+
+    #[test]
+    fn test_views_sql_with_until_only() {
+        let window = QueryWindow::from_args(None, Some("2024-06-01T00:00:00+02:00"), fixed_now())
+            .unwrap()
+            .unwrap();
+        let sql = window.views_sql("r2.default");
+
+        // Offsets are normalized to UTC
+        assert!(sql.contains("WHERE \"timestamp\" < TIMESTAMP '2024-05-31 22:00:00.000000';"));
+        assert!(!sql.contains(">="));
+    }
+
+    #[test]
+    fn test_rejects_invalid_until() {
+        assert!(QueryWindow::from_args(None, Some("yesterday"), fixed_now()).is_err());
+    }
+
+    #[test]
+    fn test_rejects_empty_window() {
+        let result = QueryWindow::from_args(Some("1h"), Some("2024-06-01T10:00:00Z"), fixed_now());
+        assert!(result.is_err());
+    }
+}
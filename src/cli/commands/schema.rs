@@ -0,0 +1,115 @@
+//! `schema export`: write every pipeline schema to files.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+use crate::cli::SchemaExportArgs;
+use crate::schema_json::{extra_fields, generate_cloudflare_schema, LOCAL_SCHEMAS};
+
+pub fn execute_schema_export(args: SchemaExportArgs) -> Result<()> {
+    fs::create_dir_all(&args.dir)
+        .with_context(|| format!("Failed to create {}", args.dir.display()))?;
+
+    let schemas: Vec<_> = otlp2records::schema_defs()
+        .iter()
+        .chain(LOCAL_SCHEMAS)
+        .collect();
+    for schema in &schemas {
+        write(
+            &args.dir,
+            &format!("{}.schema.json", schema.name),
+            &generate_cloudflare_schema(schema),
+        )?;
+        write(
+            &args.dir,
+            &format!("{}.schema.txt", schema.name),
+            &render_table(schema),
+        )?;
+    }
+
+    eprintln!(
+        "==> Exported {} schemas to {}",
+        schemas.len(),
+        args.dir.display()
+    );
+    Ok(())
+}
+
+fn write(dir: &Path, file: &str, contents: &str) -> Result<()> {
+    let path = dir.join(file);
+    fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Render a schema (plus extra columns) as an aligned name/type/required table
+fn render_table(schema: &otlp2records::SchemaDef) -> String {
+    let rows: Vec<(&str, &str, bool)> = schema
+        .fields
+        .iter()
+        .map(|f| (f.name, f.field_type, f.required))
+        .chain(
+            extra_fields(schema.name)
+                .iter()
+                .map(|(name, field_type)| (*name, *field_type, false)),
+        )
+        .collect();
+    let name_width = rows
+        .iter()
+        .map(|(name, _, _)| name.len())
+        .chain(["FIELD".len()])
+        .max()
+        .unwrap_or_default();
+    let type_width = rows
+        .iter()
+        .map(|(_, field_type, _)| field_type.len())
+        .chain(["TYPE".len()])
+        .max()
+        .unwrap_or_default();
+
+    let mut out = format!("{}\n\n", schema.name);
+    out.push_str(&format!(
+        "{:name_width$}  {:type_width$}  REQUIRED\n",
+        "FIELD", "TYPE"
+    ));
+    for (name, field_type, required) in rows {
+        out.push_str(&format!(
+            "{:name_width$}  {:type_width$}  {}\n",
+            name,
+            field_type,
+            if required { "yes" } else { "no" }
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_table_lists_every_field() {
+        let schema = otlp2records::schema_def("logs").expect("logs schema");
+        let table = render_table(schema);
+
+        assert!(table.starts_with("logs\n"));
+        let extra = crate::schema_json::extra_fields("logs").len();
+        assert_eq!(table.lines().count(), schema.fields.len() + extra + 3);
+        assert!(table.lines().any(|line| line.starts_with("log_id ")));
+        assert!(table.lines().any(|line| line.starts_with("log_uid ")));
+        assert!(table
+            .lines()
+            .any(|line| line.starts_with("timestamp ") && line.ends_with("yes")));
+    }
+
+    #[test]
+    fn test_metric_tables_include_series_id() {
+        for name in ["gauge", "sum"] {
+            let schema = otlp2records::schema_def(name).expect("metric schema");
+            assert!(render_table(schema)
+                .lines()
+                .any(|line| line.starts_with("series_id ") && line.ends_with("no")));
+            assert!(generate_cloudflare_schema(schema)
+                .contains(r#"{ "name": "series_id", "type": "string", "required": false }"#));
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! `diff-schema`: compare two Cloudflare schema JSON files field by field.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+use crate::cli::DiffSchemaArgs;
+use crate::cloudflare::SchemaField;
+
+/// One field-level difference between two schemas
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FieldChange {
+    Added {
+        name: String,
+        field_type: String,
+        required: bool,
+    },
+    Removed {
+        name: String,
+        field_type: String,
+        required: bool,
+    },
+    Changed {
+        name: String,
+        /// (old, new) type, when it changed
+        field_type: Option<(String, String)>,
+        /// (old, new) required flag, when it changed
+        required: Option<(bool, bool)>,
+    },
+}
+
+impl std::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let required = |r: bool| if r { "required" } else { "optional" };
+        match self {
+            FieldChange::Added {
+                name,
+                field_type,
+                required: r,
+            } => write!(f, "+ {} {} ({})", name, field_type, required(*r)),
+            FieldChange::Removed {
+                name,
+                field_type,
+                required: r,
+            } => write!(f, "- {} {} ({})", name, field_type, required(*r)),
+            FieldChange::Changed {
+                name,
+                field_type,
+                required: r,
+            } => {
+                write!(f, "~ {}", name)?;
+                if let Some((old, new)) = field_type {
+                    write!(f, " type {} -> {}", old, new)?;
+                }
+                if let Some((old, new)) = r {
+                    write!(f, " {} -> {}", required(*old), required(*new))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Field changes from `old` to `new`: changed and removed fields in `old`'s
+/// order, then added fields in `new`'s order
+pub fn diff_schemas(old: &[SchemaField], new: &[SchemaField]) -> Vec<FieldChange> {
+    let find = |fields: &[SchemaField], name: &str| fields.iter().find(|f| f.name == name).cloned();
+    let mut changes: Vec<FieldChange> = old
+        .iter()
+        .filter_map(|before| match find(new, &before.name) {
+            None => Some(FieldChange::Removed {
+                name: before.name.clone(),
+                field_type: before.field_type.clone(),
+                required: before.required,
+            }),
+            Some(after) => {
+                let field_type = (before.field_type != after.field_type)
+                    .then(|| (before.field_type.clone(), after.field_type.clone()));
+                let required = (before.required != after.required)
+                    .then_some((before.required, after.required));
+                (field_type.is_some() || required.is_some()).then(|| FieldChange::Changed {
+                    name: before.name.clone(),
+                    field_type,
+                    required,
+                })
+            }
+        })
+        .collect();
+    changes.extend(
+        new.iter()
+            .filter(|after| find(old, &after.name).is_none())
+            .map(|after| FieldChange::Added {
+                name: after.name.clone(),
+                field_type: after.field_type.clone(),
+                required: after.required,
+            }),
+    );
+    changes
+}
+
+fn load_fields(path: &Path) -> Result<Vec<SchemaField>> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let schema: serde_json::Value = serde_json::from_str(&content)
+        .with_context(|| format!("{} is not valid JSON", path.display()))?;
+    serde_json::from_value(schema.get("fields").cloned().unwrap_or_default())
+        .with_context(|| format!("{} has no valid \"fields\" array", path.display()))
+}
+
+/// Print field differences; fails (nonzero exit) when the schemas differ, for CI gating
+pub fn execute_diff_schema(args: DiffSchemaArgs) -> Result<()> {
+    let changes = diff_schemas(&load_fields(&args.old)?, &load_fields(&args.new)?);
+    if changes.is_empty() {
+        eprintln!("==> Schemas match");
+        return Ok(());
+    }
+    println!("{} -> {}", args.old.display(), args.new.display());
+    for change in &changes {
+        println!("  {}", change);
+    }
+    bail!("schemas differ ({} field change(s))", changes.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn field(name: &str, field_type: &str, required: bool) -> SchemaField {
+        SchemaField {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            required,
+        }
+    }
+
+    fn base() -> Vec<SchemaField> {
+        vec![
+            field("timestamp", "timestamp", true),
+            field("service_name", "string", true),
+            field("body", "string", false),
+        ]
+    }
+
+    #[test]
+    fn test_identical_schemas_have_no_changes() {
+        assert!(diff_schemas(&base(), &base()).is_empty());
+    }
+
+    #[test]
+    fn test_field_addition() {
+        let mut new = base();
+        new.push(field("log_id", "string", false));
+        let changes = diff_schemas(&base(), &new);
+        assert_eq!(
+            changes,
+            vec![FieldChange::Added {
+                name: "log_id".to_string(),
+                field_type: "string".to_string(),
+                required: false,
+            }]
+        );
+        assert_eq!(changes[0].to_string(), "+ log_id string (optional)");
+    }
+
+    #[test]
+    fn test_field_removal() {
+        let new: Vec<_> = base().into_iter().filter(|f| f.name != "body").collect();
+        let changes = diff_schemas(&base(), &new);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].to_string(), "- body string (optional)");
+    }
+
+    #[test]
+    fn test_type_and_required_changes() {
+        let mut new = base();
+        new[1] = field("service_name", "json", true);
+        new[2] = field("body", "string", true);
+        let changes = diff_schemas(&base(), &new);
+        assert_eq!(
+            changes[0],
+            FieldChange::Changed {
+                name: "service_name".to_string(),
+                field_type: Some(("string".to_string(), "json".to_string())),
+                required: None,
+            }
+        );
+        assert_eq!(changes[1].to_string(), "~ body optional -> required");
+    }
+
+    #[test]
+    fn test_differences_fail_the_command() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, json: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, json).unwrap();
+            path
+        };
+        let old = write("old.json", r#"{"fields":[{"name":"a","type":"string"}]}"#);
+        let same = write(
+            "same.json",
+            r#"{"fields":[{"name":"a","type":"string","required":false}]}"#,
+        );
+        let new = write("new.json", r#"{"fields":[{"name":"a","type":"int64"}]}"#);
+
+        let args = |new: &PathBuf| DiffSchemaArgs {
+            old: old.clone(),
+            new: new.clone(),
+        };
+        assert!(execute_diff_schema(args(&same)).is_ok());
+        assert!(execute_diff_schema(args(&new)).is_err());
+        assert!(execute_diff_schema(args(&dir.path().join("missing.json"))).is_err());
+    }
+}
@@ -2,13 +2,37 @@ use std::time::Duration;
 
 use anyhow::{bail, Result};
 
+use super::query_window::parse_duration;
 use crate::cli::config::try_load_config;
 use crate::cli::url::resolve_worker_url;
-use crate::cli::ServicesArgs;
+use crate::registry::prune::{PruneRequest, PruneResponse};
 
 /// Default timeout for HTTP requests (30 seconds)
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
+#[derive(clap::Args)]
+pub struct ServicesArgs {
+    #[command(subcommand)]
+    pub command: Option<ServicesCommands>,
+
+    /// Worker URL (falls back to wrangler.toml)
+    #[arg(long, global = true)]
+    pub url: Option<String>,
+}
+
+#[derive(clap::Subcommand)]
+pub enum ServicesCommands {
+    /// Remove services that have not sent data recently
+    Prune(ServicesPruneArgs),
+}
+
+#[derive(clap::Args)]
+pub struct ServicesPruneArgs {
+    /// Remove services not seen within this duration (e.g. 12h, 7d)
+    #[arg(long)]
+    pub older_than: String,
+}
+
 pub async fn execute_services(args: ServicesArgs) -> Result<()> {
     // Check if provider is AWS - services command is Cloudflare-only
     if let Some(config) = try_load_config() {
@@ -22,11 +46,15 @@ pub async fn execute_services(args: ServicesArgs) -> Result<()> {
     }
 
     let base_url = resolve_worker_url(args.url.as_deref()).await?;
-    let url = format!("{}/v1/services", base_url);
-
     let client = reqwest::Client::builder()
         .timeout(REQUEST_TIMEOUT)
         .build()?;
+
+    if let Some(ServicesCommands::Prune(prune)) = args.command {
+        return execute_prune(&client, &base_url, &prune).await;
+    }
+
+    let url = format!("{}/v1/services", base_url);
     let response = client.get(&url).send().await?;
 
     if !response.status().is_success() {
@@ -40,3 +68,34 @@ pub async fn execute_services(args: ServicesArgs) -> Result<()> {
 
     Ok(())
 }
+
+async fn execute_prune(
+    client: &reqwest::Client,
+    base_url: &str,
+    args: &ServicesPruneArgs,
+) -> Result<()> {
+    let older_than = parse_duration(&args.older_than)?;
+
+    let mut request = client
+        .post(format!("{}/v1/services/prune", base_url))
+        .json(&PruneRequest {
+            older_than_ms: older_than.num_milliseconds(),
+        });
+    if let Some(token) = try_load_config().and_then(|c| c.auth_token) {
+        request = request.bearer_auth(token);
+    }
+    let response = request.send().await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        bail!("Failed to prune services: {} - {}", status, body);
+    }
+
+    let pruned: PruneResponse = response.json().await?;
+    eprintln!(
+        "==> Pruned {} services not seen in {}",
+        pruned.pruned, args.older_than
+    );
+    Ok(())
+}
@@ -28,6 +28,7 @@ pub async fn execute_tail(args: TailArgs) -> Result<()> {
     if args.signal != "logs" && args.signal != "traces" {
         bail!("Signal must be 'logs' or 'traces', got: {}", args.signal);
     }
+    let trace_id = args.trace_id.as_deref().map(parse_trace_id).transpose()?;
 
     let base_url = resolve_worker_url(args.url.as_deref()).await?;
 
@@ -54,11 +55,19 @@ pub async fn execute_tail(args: TailArgs) -> Result<()> {
         "Connected. Streaming {} for service '{}'...",
         args.signal, args.service
     );
+    if let Some(trace_id) = &trace_id {
+        eprintln!("Filtering to trace {}", trace_id);
+    }
     eprintln!("Press Ctrl+C to stop.\n");
 
     while let Some(msg) = read.next().await {
         match msg {
-            Ok(Message::Text(text)) => {
+            // Records outside the trace filter fall through to the catch-all
+            Ok(Message::Text(text))
+                if trace_id
+                    .as_deref()
+                    .is_none_or(|id| matches_trace(&text, id)) =>
+            {
                 println!("{}", text);
             }
             Ok(Message::Close(_)) => {
@@ -75,3 +84,69 @@ pub async fn execute_tail(args: TailArgs) -> Result<()> {
 
     Ok(())
 }
+
+/// Validate a trace ID (32 hex characters, not all zeros), returned lowercased
+fn parse_trace_id(value: &str) -> Result<String> {
+    let trace_id = value.trim().to_ascii_lowercase();
+    if trace_id.len() != 32 || !trace_id.chars().all(|c| c.is_ascii_hexdigit()) {
+        bail!(
+            "Invalid trace ID '{}': expected 32 hex characters",
+            value.trim()
+        );
+    }
+    if trace_id.chars().all(|c| c == '0') {
+        bail!("Invalid trace ID: all zeros is not a valid trace ID");
+    }
+    Ok(trace_id)
+}
+
+/// Whether a tail message should be shown under a trace filter.
+/// Records must carry `trace_id`; status messages (connected, dropped) always pass.
+fn matches_trace(message: &str, trace_id: &str) -> bool {
+    let Ok(message) = serde_json::from_str::<serde_json::Value>(message) else {
+        return true;
+    };
+    if message.get("type").and_then(|t| t.as_str()) != Some("record") {
+        return true;
+    }
+    message["data"]
+        .get("trace_id")
+        .and_then(|t| t.as_str())
+        .is_some_and(|t| t.eq_ignore_ascii_case(trace_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACE: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+
+    #[test]
+    fn test_parse_trace_id() {
+        assert_eq!(
+            parse_trace_id(" 4BF92F3577B34DA6A3CE929D0E0E4736 ").unwrap(),
+            TRACE
+        );
+        assert!(parse_trace_id("4bf92f35").is_err());
+        assert!(parse_trace_id("zzf92f3577b34da6a3ce929d0e0e4736").is_err());
+        assert!(parse_trace_id(&"0".repeat(32)).is_err());
+    }
+
+    #[test]
+    fn test_matches_trace() {
+        let record = |trace_id: &str| {
+            serde_json::json!({"type": "record", "data": {"trace_id": trace_id, "span_name": "GET"}})
+                .to_string()
+        };
+        assert!(matches_trace(&record(TRACE), TRACE));
+        assert!(!matches_trace(
+            &record("00f067aa0ba902b7a3ce929d0e0e4736"),
+            TRACE
+        ));
+        assert!(!matches_trace(
+            r#"{"type":"record","data":{"span_name":"GET"}}"#,
+            TRACE
+        ));
+        assert!(matches_trace(r#"{"type":"dropped","count":3}"#, TRACE));
+    }
+}
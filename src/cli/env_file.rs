@@ -0,0 +1,170 @@
+//! Dotenv-style `--env-file` loading.
+//!
+//! The file is applied before clap parses arguments so that `env = "..."`
+//! argument defaults (e.g. `R2_API_TOKEN`) see the loaded values.
+
+use anyhow::{bail, Context, Result};
+use std::path::Path;
+
+/// Parse dotenv content into ordered `(key, value)` pairs.
+///
+/// Supports blank lines, `#` comments, an optional `export ` prefix, and
+/// single- or double-quoted values.
+pub fn parse_env_file(content: &str) -> Result<Vec<(String, String)>> {
+    let mut entries = Vec::new();
+
+    for (idx, raw) in content.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        let Some((key, value)) = line.split_once('=') else {
+            bail!("line {}: expected KEY=VALUE", idx + 1);
+        };
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            bail!("line {}: invalid variable name '{}'", idx + 1, key);
+        }
+
+        entries.push((key.to_string(), unquote(value.trim()).to_string()));
+    }
+
+    Ok(entries)
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if value.len() >= 2 && value.starts_with(quote) && value.ends_with(quote) {
+            return &value[1..value.len() - 1];
+        }
+    }
+    value
+}
+
+/// Select the entries to apply: already-set variables win unless `override_existing`.
+pub fn entries_to_apply(
+    entries: Vec<(String, String)>,
+    is_set: impl Fn(&str) -> bool,
+    override_existing: bool,
+) -> Vec<(String, String)> {
+    entries
+        .into_iter()
+        .filter(|(key, _)| override_existing || !is_set(key))
+        .collect()
+}
+
+/// Load an env file into the process environment
+pub fn load_env_file(path: &Path, override_existing: bool) -> Result<()> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read env file {}", path.display()))?;
+    let entries = parse_env_file(&content)
+        .with_context(|| format!("Failed to parse env file {}", path.display()))?;
+
+    let to_apply = entries_to_apply(
+        entries,
+        |key| std::env::var_os(key).is_some(),
+        override_existing,
+    );
+    for (key, value) in to_apply {
+        std::env::set_var(key, value);
+    }
+    Ok(())
+}
+
+/// Find `--env-file` / `--env-file-override` in raw args (before clap parsing).
+pub fn env_file_from_args(args: &[String]) -> (Option<String>, bool) {
+    let mut path = None;
+    let mut override_existing = false;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "--env-file" {
+            path = iter.next().cloned();
+        } else if let Some(value) = arg.strip_prefix("--env-file=") {
+            path = Some(value.to_string());
+        } else if arg == "--env-file-override" {
+            override_existing = true;
+        }
+    }
+
+    (path, override_existing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let content = r#"
+# Cloudflare credentials
+CLOUDFLARE_ACCOUNT_ID=abc123
+export R2_API_TOKEN="token with spaces"
+AWS_REGION='us-east-1'
+EMPTY=
+URL=https://example.com/?a=b
+"#;
+        let entries = parse_env_file(content).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                ("CLOUDFLARE_ACCOUNT_ID".into(), "abc123".into()),
+                ("R2_API_TOKEN".into(), "token with spaces".into()),
+                ("AWS_REGION".into(), "us-east-1".into()),
+                ("EMPTY".into(), "".into()),
+                ("URL".into(), "https://example.com/?a=b".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_env_file_rejects_invalid_lines() {
+        assert!(parse_env_file("NOT_AN_ASSIGNMENT").is_err());
+        assert!(parse_env_file("BAD-NAME=1").is_err());
+    }
+
+    #[test]
+    fn test_existing_vars_win_without_override() {
+        let entries = vec![
+            ("SET".to_string(), "from-file".to_string()),
+            ("UNSET".to_string(), "from-file".to_string()),
+        ];
+        let applied = entries_to_apply(entries.clone(), |k| k == "SET", false);
+        assert_eq!(applied, vec![("UNSET".into(), "from-file".into())]);
+
+        let applied = entries_to_apply(entries, |k| k == "SET", true);
+        assert_eq!(applied.len(), 2);
+    }
+
+    #[test]
+    fn test_env_file_from_args() {
+        let args = |v: &[&str]| v.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            env_file_from_args(&args(&[
+                "otlp2pipeline",
+                "--env-file",
+                ".env.prod",
+                "status"
+            ])),
+            (Some(".env.prod".to_string()), false)
+        );
+        assert_eq!(
+            env_file_from_args(&args(&[
+                "otlp2pipeline",
+                "status",
+                "--env-file=.env",
+                "--env-file-override"
+            ])),
+            (Some(".env".to_string()), true)
+        );
+        assert_eq!(
+            env_file_from_args(&args(&["otlp2pipeline", "status"])),
+            (None, false)
+        );
+    }
+}
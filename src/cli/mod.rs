@@ -1,8 +1,22 @@
 pub mod auth;
 pub mod commands;
 pub mod config;
+pub mod env_file;
+mod provider_args;
 pub mod url;
 
+pub use commands::{
+    ConnectArgs, ConnectClaudeCodeArgs, ConnectCodexArgs, ConnectCommands,
+    ConnectOtelCollectorArgs, ServicesArgs, ServicesCommands, ServicesPruneArgs,
+};
+
+pub use provider_args::{
+    AwsArgs, AwsCatalogArgs, AwsCatalogCommands, AwsCatalogListArgs, AwsCommands, AzureArgs,
+    AzureCommands, BucketArgs, BucketCommands, BucketDeleteArgs, CatalogArgs, CatalogCommands,
+    CatalogListArgs, CatalogPartitionArgs, CatalogSetCompactionArgs, CloudflareArgs,
+    CloudflareCommands,
+};
+
 use clap::{Parser, Subcommand};
 
 #[derive(Parser)]
@@ -12,6 +26,14 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Load environment variables from a dotenv-style file
+    #[arg(long, global = true)]
+    pub env_file: Option<String>,
+
+    /// Let --env-file values override variables already set in the environment
+    #[arg(long, global = true, requires = "env_file")]
+    pub env_file_override: bool,
 }
 
 #[derive(Subcommand)]
@@ -49,6 +71,14 @@ pub enum Commands {
     Tail(TailArgs),
     /// Generate OpenTelemetry Collector config
     Connect(ConnectArgs),
+    /// Decode a captured OTLP payload to JSON locally
+    Inspect(InspectArgs),
+    /// Export pipeline schemas to files
+    Schema(SchemaArgs),
+    /// Compare two schema JSON files; exits nonzero if they differ
+    DiffSchema(DiffSchemaArgs),
+    /// Inspect the resolved CLI configuration
+    Config(ConfigArgs),
 }
 
 #[derive(clap::Args)]
@@ -74,167 +104,6 @@ pub struct InitArgs {
     pub force: bool,
 }
 
-#[derive(clap::Args)]
-pub struct CloudflareArgs {
-    #[command(subcommand)]
-    pub command: CloudflareCommands,
-}
-
-#[derive(Subcommand)]
-pub enum CloudflareCommands {
-    /// Create a new pipeline environment
-    Create(CreateArgs),
-    /// Destroy a pipeline environment
-    Destroy(DestroyArgs),
-    /// Show environment status
-    Status(StatusArgs),
-    /// Dry-run: show what would be created
-    Plan(PlanArgs),
-    /// Start a DuckDB query session
-    Query(QueryArgs),
-    /// Manage Iceberg catalog
-    Catalog(CatalogArgs),
-    /// Manage R2 bucket data
-    Bucket(BucketArgs),
-}
-
-#[derive(clap::Args)]
-pub struct AwsArgs {
-    #[command(subcommand)]
-    pub command: AwsCommands,
-}
-
-#[derive(Subcommand)]
-pub enum AwsCommands {
-    /// Generate CloudFormation template for S3 Tables + Firehose
-    Create(CreateArgs),
-    /// Show CloudFormation stack status
-    Status(StatusArgs),
-    /// Delete CloudFormation stack
-    Destroy(DestroyArgs),
-    /// Show what would be generated
-    Plan(PlanArgs),
-    /// Start a DuckDB query session connected to S3 Tables
-    Query(QueryArgs),
-    /// Manage S3 Tables catalog
-    Catalog(AwsCatalogArgs),
-}
-
-#[derive(clap::Args)]
-pub struct AzureArgs {
-    #[command(subcommand)]
-    pub command: AzureCommands,
-}
-
-#[derive(Subcommand)]
-pub enum AzureCommands {
-    /// Create Azure infrastructure (Event Hub + Stream Analytics)
-    Create(CreateArgs),
-    /// Show Azure deployment status
-    Status(StatusArgs),
-    /// Delete Azure infrastructure
-    Destroy(DestroyArgs),
-    /// Show what would be created
-    Plan(PlanArgs),
-}
-
-#[derive(clap::Args)]
-pub struct AwsCatalogArgs {
-    #[command(subcommand)]
-    pub command: AwsCatalogCommands,
-}
-
-#[derive(Subcommand)]
-pub enum AwsCatalogCommands {
-    /// List S3 Tables metadata
-    List(AwsCatalogListArgs),
-}
-
-#[derive(clap::Args)]
-pub struct AwsCatalogListArgs {
-    /// Environment name (overrides .otlp2pipeline.toml)
-    #[arg(long)]
-    pub env: Option<String>,
-
-    /// AWS region (overrides .otlp2pipeline.toml)
-    #[arg(long)]
-    pub region: Option<String>,
-}
-
-#[derive(clap::Args)]
-pub struct CatalogArgs {
-    #[command(subcommand)]
-    pub command: CatalogCommands,
-}
-
-#[derive(clap::Args)]
-pub struct BucketArgs {
-    #[command(subcommand)]
-    pub command: BucketCommands,
-}
-
-#[derive(Subcommand)]
-pub enum BucketCommands {
-    /// Delete all objects in the bucket using AWS CLI
-    Delete(BucketDeleteArgs),
-}
-
-#[derive(clap::Args)]
-pub struct BucketDeleteArgs {
-    /// Environment name (bucket will be otlp2pipeline-{name})
-    pub name: String,
-
-    /// Override bucket name (use exact name instead of otlp2pipeline-{name})
-    #[arg(long)]
-    pub bucket: Option<String>,
-
-    /// AWS Access Key ID for R2 S3 API
-    #[arg(long, env = "AWS_ACCESS_KEY_ID")]
-    pub access_key_id: String,
-
-    /// AWS Secret Access Key for R2 S3 API
-    #[arg(long, env = "AWS_SECRET_ACCESS_KEY")]
-    pub secret_access_key: String,
-
-    /// Skip confirmation prompt
-    #[arg(long)]
-    pub force: bool,
-}
-
-#[derive(Subcommand)]
-pub enum CatalogCommands {
-    /// List table metadata including partition specs
-    List(CatalogListArgs),
-    /// Add service_name identity partition to all tables
-    Partition(CatalogPartitionArgs),
-}
-
-#[derive(clap::Args)]
-pub struct CatalogListArgs {
-    /// R2 API token (create at dash.cloudflare.com > R2 > Manage R2 API Tokens)
-    #[arg(long = "r2-token", env = "R2_API_TOKEN")]
-    pub r2_token: String,
-
-    /// Path to wrangler.toml config file
-    #[arg(long, default_value = "wrangler.toml")]
-    pub config: String,
-}
-
-#[derive(clap::Args)]
-pub struct CatalogPartitionArgs {
-    /// R2 API token (create at dash.cloudflare.com > R2 > Manage R2 API Tokens)
-    #[arg(long = "r2-token", env = "R2_API_TOKEN")]
-    pub r2_token: String,
-
-    /// Path to wrangler.toml config file
-    #[arg(long, default_value = "wrangler.toml")]
-    pub config: String,
-
-    /// Show what would change without applying
-    #[arg(long)]
-    pub dry_run: bool,
-}
-
 #[derive(clap::Args)]
 pub struct CreateArgs {
     /// Environment name (overrides .otlp2pipeline.toml)
@@ -265,6 +134,14 @@ pub struct CreateArgs {
     #[arg(long, default_value = "true")]
     pub metrics: bool,
 
+    /// Also write one row per span link to a span_links table (Cloudflare)
+    #[arg(long)]
+    pub span_links: bool,
+
+    /// Also write summary metrics, with their quantiles, to a summary table (Cloudflare)
+    #[arg(long)]
+    pub summaries: bool,
+
     /// Enable RED metrics Durable Object (Cloudflare)
     #[arg(long, default_value = "true")]
     pub aggregator: bool,
@@ -285,6 +162,10 @@ pub struct CreateArgs {
     #[arg(long)]
     pub use_local: bool,
 
+    /// Only check schema files and config, then exit without creating anything (Cloudflare)
+    #[arg(long)]
+    pub validate: bool,
+
     // --- AWS-specific options ---
     /// AWS region (overrides .otlp2pipeline.toml)
     #[arg(long)]
@@ -302,6 +183,10 @@ pub struct CreateArgs {
     #[arg(long)]
     pub local: bool,
 
+    /// Continue past per-table failures and summarize them at the end (AWS)
+    #[arg(long)]
+    pub keep_going: bool,
+
     // --- Azure-specific options ---
     /// Container image to deploy (Azure)
     #[arg(
@@ -346,6 +231,10 @@ pub struct StatusArgs {
     #[arg(long)]
     pub env: Option<String>,
 
+    /// Worker URL for endpoint health (Cloudflare; falls back to wrangler.toml)
+    #[arg(long)]
+    pub url: Option<String>,
+
     // --- AWS-specific options ---
     /// AWS region (overrides .otlp2pipeline.toml)
     #[arg(long)]
@@ -368,13 +257,34 @@ pub struct QueryArgs {
     /// Environment name (overrides .otlp2pipeline.toml)
     #[arg(long)]
     pub env: Option<String>,
-}
 
-#[derive(clap::Args)]
-pub struct ServicesArgs {
-    /// Worker URL (falls back to wrangler.toml)
+    /// Add windowed.<table> views of data newer than this duration ago (e.g. 15m, 2h, 1d)
     #[arg(long)]
-    pub url: Option<String>,
+    pub since: Option<String>,
+
+    /// Add windowed.<table> views of data older than this RFC 3339 timestamp (e.g. 2024-01-01T12:00:00Z)
+    #[arg(long)]
+    pub until: Option<String>,
+
+    /// Pin a DuckDB release (downloaded and cached under ~/.otlp2pipeline/duckdb/)
+    #[arg(long)]
+    pub duckdb_version: Option<String>,
+
+    /// Run this SQL and exit instead of starting an interactive session
+    #[arg(long)]
+    pub sql: Option<String>,
+
+    /// Re-run --sql on an interval until Ctrl-C
+    #[arg(long, requires = "sql")]
+    pub follow: bool,
+
+    /// Seconds between --follow runs
+    #[arg(long, default_value = "10", value_parser = clap::value_parser!(u64).range(1..), requires = "follow")]
+    pub interval: u64,
+
+    /// Output format for --sql: table, or ndjson for one JSON object per row
+    #[arg(long, default_value = "table", value_parser = ["table", "ndjson"], requires = "sql")]
+    pub output_format: String,
 }
 
 #[derive(clap::Args)]
@@ -388,45 +298,83 @@ pub struct TailArgs {
     /// Worker URL (falls back to wrangler.toml)
     #[arg(long)]
     pub url: Option<String>,
+
+    /// Only show records from this trace (32 hex characters)
+    #[arg(long)]
+    pub trace_id: Option<String>,
 }
 
 #[derive(clap::Args)]
-pub struct ConnectArgs {
+pub struct InspectArgs {
+    /// Signal type (logs, traces, or metrics)
+    #[arg(long)]
+    pub signal: String,
+    /// Captured payload file
+    #[arg(long)]
+    pub input: std::path::PathBuf,
+    /// Payload format (protobuf, json, jsonl, or auto)
+    #[arg(long, default_value = "auto")]
+    pub format: String,
+    /// Also run the transform and print table-grouped records
+    #[arg(long)]
+    pub transform: bool,
+    /// Output format: pretty, or ndjson for one record per line
+    #[arg(long, default_value = "pretty")]
+    pub output_format: String,
+}
+
+#[derive(clap::Args)]
+pub struct SchemaArgs {
     #[command(subcommand)]
-    pub command: ConnectCommands,
+    pub command: SchemaCommands,
 }
 
 #[derive(Subcommand)]
-pub enum ConnectCommands {
-    /// Generate OpenTelemetry Collector config (otel-collector-config.yaml)
-    OtelCollector(ConnectOtelCollectorArgs),
-    /// Generate shell exports for Claude Code integration
-    ClaudeCode(ConnectClaudeCodeArgs),
-    /// Generate TOML config for OpenAI Codex CLI
-    Codex(ConnectCodexArgs),
+pub enum SchemaCommands {
+    /// Write each schema as Cloudflare JSON and a readable table
+    Export(SchemaExportArgs),
 }
 
 #[derive(clap::Args)]
-pub struct ConnectOtelCollectorArgs {
-    /// Worker URL (falls back to wrangler.toml)
-    #[arg(long)]
-    pub url: Option<String>,
+pub struct SchemaExportArgs {
+    /// Output directory (created if missing)
+    #[arg(long, default_value = "./schemas")]
+    pub dir: std::path::PathBuf,
 }
 
 #[derive(clap::Args)]
-pub struct ConnectClaudeCodeArgs {
-    /// Worker URL (falls back to wrangler.toml)
-    #[arg(long)]
-    pub url: Option<String>,
+pub struct DiffSchemaArgs {
+    /// Schema JSON before the change (e.g. from the base branch)
+    pub old: std::path::PathBuf,
 
-    /// Output format
-    #[arg(long, default_value = "shell")]
-    pub format: String,
+    /// Schema JSON after the change
+    pub new: std::path::PathBuf,
 }
 
 #[derive(clap::Args)]
-pub struct ConnectCodexArgs {
-    /// Worker URL (falls back to wrangler.toml)
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    pub command: ConfigCommands,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the resolved configuration with secrets masked
+    Show(ConfigShowArgs),
+}
+
+/// Flags other commands accept, to preview how they would resolve
+#[derive(clap::Args, Default)]
+pub struct ConfigShowArgs {
+    /// Environment name (as passed to --env)
+    #[arg(long)]
+    pub env: Option<String>,
+
+    /// AWS region (as passed to --region)
+    #[arg(long)]
+    pub region: Option<String>,
+
+    /// Worker URL (as passed to --url)
     #[arg(long)]
     pub url: Option<String>,
 }
@@ -0,0 +1,188 @@
+//! Clap arguments for the provider-specific subcommands
+//! (`cloudflare`, `aws`, `azure`).
+
+use clap::Subcommand;
+
+use super::{CreateArgs, DestroyArgs, PlanArgs, QueryArgs, StatusArgs};
+
+#[derive(clap::Args)]
+pub struct CloudflareArgs {
+    #[command(subcommand)]
+    pub command: CloudflareCommands,
+}
+
+#[derive(Subcommand)]
+pub enum CloudflareCommands {
+    /// Create a new pipeline environment
+    Create(CreateArgs),
+    /// Destroy a pipeline environment
+    Destroy(DestroyArgs),
+    /// Show environment status
+    Status(StatusArgs),
+    /// Dry-run: show what would be created
+    Plan(PlanArgs),
+    /// Start a DuckDB query session
+    Query(QueryArgs),
+    /// Manage Iceberg catalog
+    Catalog(CatalogArgs),
+    /// Manage R2 bucket data
+    Bucket(BucketArgs),
+}
+
+#[derive(clap::Args)]
+pub struct AwsArgs {
+    #[command(subcommand)]
+    pub command: AwsCommands,
+}
+
+#[derive(Subcommand)]
+pub enum AwsCommands {
+    /// Generate CloudFormation template for S3 Tables + Firehose
+    Create(CreateArgs),
+    /// Show CloudFormation stack status
+    Status(StatusArgs),
+    /// Delete CloudFormation stack
+    Destroy(DestroyArgs),
+    /// Show what would be generated
+    Plan(PlanArgs),
+    /// Start a DuckDB query session connected to S3 Tables
+    Query(QueryArgs),
+    /// Manage S3 Tables catalog
+    Catalog(AwsCatalogArgs),
+}
+
+#[derive(clap::Args)]
+pub struct AzureArgs {
+    #[command(subcommand)]
+    pub command: AzureCommands,
+}
+
+#[derive(Subcommand)]
+pub enum AzureCommands {
+    /// Create Azure infrastructure (Event Hub + Stream Analytics)
+    Create(CreateArgs),
+    /// Show Azure deployment status
+    Status(StatusArgs),
+    /// Delete Azure infrastructure
+    Destroy(DestroyArgs),
+    /// Show what would be created
+    Plan(PlanArgs),
+}
+
+#[derive(clap::Args)]
+pub struct AwsCatalogArgs {
+    #[command(subcommand)]
+    pub command: AwsCatalogCommands,
+}
+
+#[derive(Subcommand)]
+pub enum AwsCatalogCommands {
+    /// List S3 Tables metadata
+    List(AwsCatalogListArgs),
+}
+
+#[derive(clap::Args)]
+pub struct AwsCatalogListArgs {
+    /// Environment name (overrides .otlp2pipeline.toml)
+    #[arg(long)]
+    pub env: Option<String>,
+
+    /// AWS region (overrides .otlp2pipeline.toml)
+    #[arg(long)]
+    pub region: Option<String>,
+}
+
+#[derive(clap::Args)]
+pub struct CatalogArgs {
+    #[command(subcommand)]
+    pub command: CatalogCommands,
+}
+
+#[derive(clap::Args)]
+pub struct BucketArgs {
+    #[command(subcommand)]
+    pub command: BucketCommands,
+}
+
+#[derive(Subcommand)]
+pub enum BucketCommands {
+    /// Delete all objects in the bucket using AWS CLI
+    Delete(BucketDeleteArgs),
+}
+
+#[derive(clap::Args)]
+pub struct BucketDeleteArgs {
+    /// Environment name (bucket will be otlp2pipeline-{name})
+    pub name: String,
+
+    /// Override bucket name (use exact name instead of otlp2pipeline-{name})
+    #[arg(long)]
+    pub bucket: Option<String>,
+
+    /// AWS Access Key ID for R2 S3 API
+    #[arg(long, env = "AWS_ACCESS_KEY_ID")]
+    pub access_key_id: String,
+
+    /// AWS Secret Access Key for R2 S3 API
+    #[arg(long, env = "AWS_SECRET_ACCESS_KEY")]
+    pub secret_access_key: String,
+
+    /// Skip confirmation prompt
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[derive(Subcommand)]
+pub enum CatalogCommands {
+    /// List table metadata including partition specs
+    List(CatalogListArgs),
+    /// Add service_name identity partition to all tables
+    Partition(CatalogPartitionArgs),
+    /// Enable compaction with a target file size (Cloudflare compacts on its own schedule)
+    SetCompaction(CatalogSetCompactionArgs),
+}
+
+#[derive(clap::Args)]
+pub struct CatalogListArgs {
+    /// R2 API token (create at dash.cloudflare.com > R2 > Manage R2 API Tokens)
+    #[arg(long = "r2-token", env = "R2_API_TOKEN")]
+    pub r2_token: String,
+
+    /// Path to wrangler.toml config file
+    #[arg(long, default_value = "wrangler.toml")]
+    pub config: String,
+}
+
+#[derive(clap::Args)]
+pub struct CatalogPartitionArgs {
+    /// R2 API token (create at dash.cloudflare.com > R2 > Manage R2 API Tokens)
+    #[arg(long = "r2-token", env = "R2_API_TOKEN")]
+    pub r2_token: String,
+
+    /// Path to wrangler.toml config file
+    #[arg(long, default_value = "wrangler.toml")]
+    pub config: String,
+
+    /// Show what would change without applying
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+#[derive(clap::Args)]
+pub struct CatalogSetCompactionArgs {
+    /// R2 API token (create at dash.cloudflare.com > R2 > Manage R2 API Tokens)
+    #[arg(long = "r2-token", env = "R2_API_TOKEN")]
+    pub r2_token: String,
+
+    /// Path to wrangler.toml config file
+    #[arg(long, default_value = "wrangler.toml")]
+    pub config: String,
+
+    /// Tables to configure (repeatable, defaults to all)
+    #[arg(long = "table")]
+    pub tables: Vec<String>,
+
+    /// Target file size in MB for compacted files
+    #[arg(long, default_value = "128")]
+    pub target_size_mb: u32,
+}
@@ -7,6 +7,7 @@ use std::time::Duration;
 pub use super::iceberg_types::*;
 
 const CATALOG_BASE: &str = "https://catalog.cloudflarestorage.com";
+const API_BASE: &str = "https://api.cloudflare.com/client/v4";
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Iceberg REST Catalog client for Cloudflare R2
@@ -290,6 +291,47 @@ impl IcebergClient {
             body
         )))
     }
+
+    /// Enable compaction for a table with the given target file size via the
+    /// R2 Data Catalog table maintenance config. This only sets the config:
+    /// Cloudflare compacts on its own schedule, and the API has no way to
+    /// trigger a run now.
+    pub async fn set_table_compaction(
+        &self,
+        table: &str,
+        target_size_mb: u32,
+    ) -> Result<CompactionConfigResult> {
+        let url = format!(
+            "{}/accounts/{}/r2-catalog/{}/namespaces/default/tables/{}/maintenance-configs",
+            API_BASE, self.account_id, self.bucket, table
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .bearer_auth(&self.token)
+            .json(&CompactionConfigRequest::new(target_size_mb))
+            .send()
+            .await
+            .with_context(|| format!("Failed to set compaction config for table '{}'", table))?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(CompactionConfigResult::TableNotFound);
+        }
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            bail!(
+                "Failed to set compaction config for table '{}': HTTP {} - {}",
+                table,
+                status,
+                body
+            );
+        }
+
+        Ok(CompactionConfigResult::Configured)
+    }
 }
 
 /// Internal error type for commit operations
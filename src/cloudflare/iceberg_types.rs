@@ -94,6 +94,39 @@ pub enum AddPartitionResult {
     TableNotFound,
 }
 
+/// Result of setting a table's compaction config
+#[derive(Debug)]
+pub enum CompactionConfigResult {
+    /// Compaction is enabled with the requested target file size
+    Configured,
+    /// Table does not exist
+    TableNotFound,
+}
+
+/// Table-level maintenance config enabling compaction
+#[derive(Debug, Serialize)]
+pub struct CompactionConfigRequest {
+    pub compaction: TableCompactionConfig,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableCompactionConfig {
+    pub state: &'static str,
+    pub target_size_mb: String,
+}
+
+impl CompactionConfigRequest {
+    /// Build a request enabling compaction with the given target file size
+    pub fn new(target_size_mb: u32) -> Self {
+        Self {
+            compaction: TableCompactionConfig {
+                state: "enabled",
+                target_size_mb: target_size_mb.to_string(),
+            },
+        }
+    }
+}
+
 /// Commit request for Iceberg table updates
 #[derive(Debug, Serialize)]
 pub struct CommitRequest {
@@ -241,3 +274,19 @@ impl TableMetadataInner {
             .map(|f| f.id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compaction_config_request_body() {
+        let body = serde_json::to_value(CompactionConfigRequest::new(256)).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "compaction": {"state": "enabled", "target_size_mb": "256"}
+            })
+        );
+    }
+}
@@ -6,7 +6,7 @@ pub mod r2;
 pub mod workers;
 
 pub use client::CloudflareClient;
-pub use iceberg::{AddPartitionResult, IcebergClient};
+pub use iceberg::{AddPartitionResult, CompactionConfigResult, IcebergClient};
 pub use iceberg_types::TableMetadataInner;
 pub use pipelines::{Pipeline, SchemaField, Sink, Stream};
 pub use r2::{CorsAllowed, CorsRule};
@@ -25,7 +25,7 @@ struct Schema<'a> {
     fields: &'a [SchemaField],
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SchemaField {
     pub name: String,
     #[serde(rename = "type")]
@@ -0,0 +1,122 @@
+//! Recently forwarded records, for local debugging.
+//!
+//! With `OTLP2PIPELINE_DEBUG=1` the native router keeps the last
+//! `DEBUG_EVENTS_CAPACITY` records it sent to the pipeline and serves them at
+//! `/debug/events`, like the test mock pipeline's `/events`.
+
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::pipeline::{PipelineSender, SendResult};
+
+/// Records kept for `/debug/events`; older records are evicted first
+pub const DEBUG_EVENTS_CAPACITY: usize = 1000;
+
+/// Bounded buffer of the most recently sent records
+pub struct DebugEvents {
+    events: Mutex<VecDeque<JsonValue>>,
+    capacity: usize,
+}
+
+impl DebugEvents {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    fn push_all(&self, records: impl IntoIterator<Item = JsonValue>) {
+        let mut events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        for record in records {
+            if events.len() == self.capacity {
+                events.pop_front();
+            }
+            events.push_back(record);
+        }
+    }
+
+    /// Buffered records, oldest first
+    pub fn snapshot(&self) -> Vec<JsonValue> {
+        let events = self.events.lock().unwrap_or_else(|e| e.into_inner());
+        events.iter().cloned().collect()
+    }
+}
+
+/// Parse `OTLP2PIPELINE_DEBUG`: `1` or `true` enables the buffer
+pub(crate) fn debug_enabled(value: Option<String>) -> bool {
+    matches!(value.as_deref().map(str::trim), Some("1" | "true"))
+}
+
+/// Sender that copies records of successfully sent tables into `events`
+pub(crate) struct RecordingSender<'a, S> {
+    pub inner: &'a S,
+    pub events: Option<&'a DebugEvents>,
+}
+
+#[async_trait::async_trait]
+impl<S: PipelineSender + Sync> PipelineSender for RecordingSender<'_, S> {
+    async fn send_all(&self, grouped: HashMap<String, Vec<JsonValue>>) -> SendResult {
+        let Some(events) = self.events else {
+            return self.inner.send_all(grouped).await;
+        };
+        let sent = grouped.clone();
+        let result = self.inner.send_all(grouped).await;
+        for (table, records) in sent {
+            if result.succeeded.contains_key(&table) {
+                events.push_all(records);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::{FailureReason, SendFailure};
+    use serde_json::json;
+
+    /// Accepts `logs`, fails every other table
+    struct LogsOnly;
+
+    #[async_trait::async_trait]
+    impl PipelineSender for LogsOnly {
+        async fn send_all(&self, grouped: HashMap<String, Vec<JsonValue>>) -> SendResult {
+            let mut result = SendResult::default();
+            for (table, records) in grouped {
+                if table == "logs" {
+                    result.succeeded.insert(table, records.len());
+                } else {
+                    let failure = SendFailure::new(FailureReason::Http5xx, "down");
+                    result.failed.insert(table, failure);
+                }
+            }
+            result
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_sent_records_are_kept_up_to_capacity() {
+        let events = DebugEvents::new(2);
+        let sender = RecordingSender {
+            inner: &LogsOnly,
+            events: Some(&events),
+        };
+        let grouped = HashMap::from([
+            ("logs".to_string(), vec![json!(1), json!(2), json!(3)]),
+            ("traces".to_string(), vec![json!("lost")]),
+        ]);
+        sender.send_all(grouped).await;
+        assert_eq!(events.snapshot(), vec![json!(2), json!(3)]);
+    }
+
+    #[test]
+    fn test_debug_enabled() {
+        assert!(debug_enabled(Some("1".to_string())));
+        assert!(debug_enabled(Some(" true".to_string())));
+        assert!(!debug_enabled(Some("0".to_string())));
+        assert!(!debug_enabled(None));
+    }
+}
@@ -0,0 +1,168 @@
+//! Attribute key normalization.
+//!
+//! Attributes keep their OTLP dotted keys (`http.method`), which some
+//! backends and SQL column promotion handle poorly. With
+//! `ATTRIBUTE_KEY_NORMALIZE=true`, every `.` in an attribute key is replaced
+//! with `ATTRIBUTE_KEY_REPLACEMENT` (default `_`), so `http.method` becomes
+//! `http_method`. Only attribute columns are touched, for every signal.
+
+use serde_json::{Map, Value as JsonValue};
+
+use super::stages::TransformStage;
+use super::TransformCounts;
+use crate::signal::Signal;
+
+/// Default replacement for `.` in attribute keys
+pub const DEFAULT_KEY_REPLACEMENT: char = '_';
+
+/// Dot replacement in attribute keys; off by default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttributeKeyNormalization {
+    pub enabled: bool,
+    pub replacement: char,
+}
+
+impl Default for AttributeKeyNormalization {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            replacement: DEFAULT_KEY_REPLACEMENT,
+        }
+    }
+}
+
+impl AttributeKeyNormalization {
+    /// Rewrite attribute keys in every attribute column, returning how many
+    /// columns changed
+    pub fn apply(&self, records: &mut [JsonValue]) -> usize {
+        if !self.enabled {
+            return 0;
+        }
+        let mut changed = 0;
+        for record in records {
+            let Some(columns) = record.as_object_mut() else {
+                continue;
+            };
+            for (_, value) in columns
+                .iter_mut()
+                .filter(|(name, _)| is_attribute_column(name))
+            {
+                if self.normalize_column(value) {
+                    changed += 1;
+                }
+            }
+        }
+        changed
+    }
+
+    /// Attribute columns are usually JSON strings, but may be objects before serialization
+    fn normalize_column(&self, value: &mut JsonValue) -> bool {
+        match value {
+            JsonValue::Object(map) => self.normalize_keys(map),
+            JsonValue::String(blob) => {
+                let Ok(JsonValue::Object(mut map)) = serde_json::from_str(blob) else {
+                    return false;
+                };
+                let changed = self.normalize_keys(&mut map);
+                if changed {
+                    *blob = JsonValue::Object(map).to_string();
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    fn normalize_keys(&self, map: &mut Map<String, JsonValue>) -> bool {
+        if !map.keys().any(|key| key.contains('.')) {
+            return false;
+        }
+        let replacement = self.replacement.to_string();
+        *map = std::mem::take(map)
+            .into_iter()
+            .map(|(key, value)| (key.replace('.', &replacement), value))
+            .collect();
+        true
+    }
+}
+
+/// `attributes` (span links) or `*_attributes` (resource, scope, log, span, metric)
+fn is_attribute_column(name: &str) -> bool {
+    name == "attributes" || name.ends_with("_attributes")
+}
+
+/// Parse `ATTRIBUTE_KEY_REPLACEMENT`: exactly one character other than `.`
+pub(crate) fn parse_replacement(value: &str) -> Option<char> {
+    let mut chars = value.trim().chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c != '.' => Some(c),
+        _ => None,
+    }
+}
+
+impl TransformStage for AttributeKeyNormalization {
+    fn name(&self) -> &'static str {
+        "attribute_keys"
+    }
+
+    fn apply(&self, _signal: Signal, records: &mut Vec<JsonValue>, _: &mut TransformCounts) {
+        AttributeKeyNormalization::apply(self, records);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn enabled(replacement: char) -> AttributeKeyNormalization {
+        AttributeKeyNormalization {
+            enabled: true,
+            replacement,
+        }
+    }
+
+    fn record() -> JsonValue {
+        json!({
+            "service_name": "api.gateway",
+            "resource_attributes": r#"{"service.name":"api.gateway"}"#,
+            "span_attributes": {"http.method": "GET", "status": 200},
+        })
+    }
+
+    #[test]
+    fn test_dots_replaced_when_enabled() {
+        let mut records = vec![record()];
+        assert_eq!(enabled('_').apply(&mut records), 2);
+
+        assert_eq!(records[0]["span_attributes"]["http_method"], "GET");
+        assert_eq!(records[0]["span_attributes"]["status"], 200);
+        let resource: JsonValue =
+            serde_json::from_str(records[0]["resource_attributes"].as_str().unwrap()).unwrap();
+        assert_eq!(resource, json!({"service_name": "api.gateway"}));
+        // Values and non-attribute columns are untouched
+        assert_eq!(records[0]["service_name"], "api.gateway");
+    }
+
+    #[test]
+    fn test_unchanged_when_disabled() {
+        let mut records = vec![record()];
+        assert_eq!(AttributeKeyNormalization::default().apply(&mut records), 0);
+        assert_eq!(records[0], record());
+    }
+
+    #[test]
+    fn test_custom_replacement_and_span_link_attributes() {
+        let mut records = vec![json!({"attributes": r#"{"link.reason":"retry"}"#})];
+        enabled('-').apply(&mut records);
+        assert_eq!(records[0]["attributes"], r#"{"link-reason":"retry"}"#);
+    }
+
+    #[test]
+    fn test_parse_replacement() {
+        assert_eq!(parse_replacement(" - "), Some('-'));
+        assert_eq!(parse_replacement("."), None);
+        assert_eq!(parse_replacement("__"), None);
+        assert_eq!(parse_replacement(""), None);
+    }
+}
@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use super::attribute_keys::{parse_replacement, AttributeKeyNormalization};
+use super::counter_resets::DEFAULT_MAX_TRACKED_SERIES;
+use super::float_precision::parse_precision;
+use super::log_id::{parse_log_id_fields, DEFAULT_LOG_ID_FIELDS};
+use super::log_uid::{parse_log_uid_attribute, DEFAULT_LOG_UID_ATTRIBUTE};
+use super::multiline::MultilineJoin;
+use super::projection::{parse_projection, ColumnProjection};
+use super::redaction::RedactionConfig;
+use super::scope_routing::routed_table_names;
+use super::service_fallback::ServiceNameFallback;
+use super::service_rate_limit::ServiceRateLimit;
+use super::severity_filter::SeverityFilter;
+use super::span_links::SPAN_LINKS_TABLE;
+use super::stages::{parse_stages, StageKind, DEFAULT_STAGES};
+use super::timestamp_bounds::TimestampBounds;
+use crate::parse_or;
+
+/// Default cap on span events kept per span
+pub const DEFAULT_MAX_SPAN_EVENTS: usize = 1000;
+/// Default cap on span links kept per span
+pub const DEFAULT_MAX_SPAN_LINKS: usize = 1000;
+/// Default cap on the raw (still compressed) request body, in bytes
+pub const DEFAULT_MAX_REQUEST_SIZE: usize = 2 * 1024 * 1024;
+/// Default cap on request body size after decompression, in bytes
+pub const DEFAULT_MAX_DECOMPRESSED_SIZE: usize = 10 * 1024 * 1024;
+/// Default decompression buffer size, as a multiple of the compressed size
+pub const DEFAULT_DECOMPRESS_PREALLOC_FACTOR: usize = 2;
+/// Default cap on decompressed size relative to the gzipped body
+pub const DEFAULT_MAX_COMPRESSION_RATIO: usize = 100;
+/// Default cap on distinct tables a single request may fan out to
+pub const DEFAULT_MAX_TABLES_PER_REQUEST: usize = 64;
+
+/// Runtime configuration for signal handling
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandlerConfig {
+    /// Maximum span events kept per span; extras are dropped and counted
+    pub max_span_events: usize,
+    /// Maximum span links kept per span; extras are dropped and counted
+    pub max_span_links: usize,
+    /// Metric scope name -> table suffix (e.g. `runtime` routes gauge to `gauge_runtime`)
+    pub metric_scope_routes: HashMap<String, String>,
+    /// Maximum distinct tables per request; larger requests are rejected
+    pub max_tables_per_request: usize,
+    /// PII redaction rules for logs and traces
+    pub redaction: RedactionConfig,
+    /// Accepted record timestamp window and what to do outside it
+    pub timestamp_bounds: TimestampBounds,
+    /// Minimum log severity, globally and per service
+    pub severity_filter: SeverityFilter,
+    /// Resource attributes naming the service when `service.name` is missing
+    pub service_name_fallback: ServiceNameFallback,
+    /// Records allowed per service per window; over-limit records are dropped
+    pub service_rate_limit: ServiceRateLimit,
+    /// Join continuation log records (e.g. split stack traces) into the record before them
+    pub multiline_join: MultilineJoin,
+    /// Also emit one `span_links` row per span link
+    pub span_links_table: bool,
+    /// Decode summary metrics into a `summary` table instead of skipping them
+    pub summary_table: bool,
+    /// Maximum raw request body size in bytes, enforced by the native router before decompression
+    pub max_request_size: usize,
+    /// Maximum request body size in bytes, after decompression
+    pub max_decompressed_size: usize,
+    /// Decompression buffer preallocated as this multiple of the compressed size, capped
+    /// by the size limits; match it to typical traffic's compression ratio to avoid regrowth
+    pub decompress_prealloc_factor: usize,
+    /// Maximum decompressed:compressed size ratio for gzip bodies; 0 disables
+    pub max_compression_ratio: usize,
+    /// Mark cumulative counter points that follow a reset (needs a long-lived process)
+    pub counter_reset_detection: bool,
+    /// Maximum series remembered for counter reset detection, and metric names for
+    /// temporality validation
+    pub max_tracked_series: usize,
+    /// Significant digits float metric values are rounded to; `None` keeps them exact
+    pub metric_value_precision: Option<u32>,
+    /// Warn when a metric name switches between delta and cumulative (needs a long-lived process)
+    pub temporality_validation: bool,
+    /// HTTP status for requests that decode to zero records: 200 (default) or 204
+    pub empty_response_status: u16,
+    /// Record stages run between decode and send, in order; omitted stages are disabled
+    pub stages: Vec<StageKind>,
+    /// Log columns hashed into `log_id`; empty disables the column
+    pub log_id_fields: Vec<String>,
+    /// Log attribute promoted into `log_uid`; empty disables the column
+    pub log_uid_attribute: String,
+    /// Optional columns dropped from output records before send
+    pub projection: ColumnProjection,
+    /// Reject JSON requests with unknown top-level fields instead of ignoring them
+    pub strict_json: bool,
+    /// Replace `.` in attribute keys (e.g. `http.method` -> `http_method`)
+    pub attribute_keys: AttributeKeyNormalization,
+}
+
+impl Default for HandlerConfig {
+    fn default() -> Self {
+        Self {
+            max_span_events: DEFAULT_MAX_SPAN_EVENTS,
+            max_span_links: DEFAULT_MAX_SPAN_LINKS,
+            metric_scope_routes: HashMap::new(),
+            max_tables_per_request: DEFAULT_MAX_TABLES_PER_REQUEST,
+            redaction: RedactionConfig::default(),
+            timestamp_bounds: TimestampBounds::default(),
+            severity_filter: SeverityFilter::default(),
+            service_name_fallback: ServiceNameFallback::default(),
+            service_rate_limit: ServiceRateLimit::default(),
+            multiline_join: MultilineJoin::default(),
+            span_links_table: false,
+            summary_table: false,
+            max_request_size: DEFAULT_MAX_REQUEST_SIZE,
+            max_decompressed_size: DEFAULT_MAX_DECOMPRESSED_SIZE,
+            decompress_prealloc_factor: DEFAULT_DECOMPRESS_PREALLOC_FACTOR,
+            max_compression_ratio: DEFAULT_MAX_COMPRESSION_RATIO,
+            counter_reset_detection: false,
+            max_tracked_series: DEFAULT_MAX_TRACKED_SERIES,
+            metric_value_precision: None,
+            temporality_validation: false,
+            empty_response_status: 200,
+            stages: DEFAULT_STAGES.to_vec(),
+            log_id_fields: DEFAULT_LOG_ID_FIELDS
+                .iter()
+                .map(|f| f.to_string())
+                .collect(),
+            log_uid_attribute: DEFAULT_LOG_UID_ATTRIBUTE.to_string(),
+            projection: ColumnProjection::default(),
+            strict_json: false,
+            attribute_keys: AttributeKeyNormalization::default(),
+        }
+    }
+}
+
+impl HandlerConfig {
+    /// Build config from a variable lookup (worker env or process env).
+    /// Missing or unparseable values fall back to defaults.
+    pub fn from_lookup(mut var: impl FnMut(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        Self {
+            max_span_events: parse_or(var("MAX_SPAN_EVENTS"), defaults.max_span_events),
+            max_span_links: parse_or(var("MAX_SPAN_LINKS"), defaults.max_span_links),
+            metric_scope_routes: var("METRIC_SCOPE_ROUTES")
+                .map(|v| parse_scope_routes(&v))
+                .unwrap_or_default(),
+            max_tables_per_request: parse_or(
+                var("MAX_TABLES_PER_REQUEST"),
+                defaults.max_tables_per_request,
+            ),
+            timestamp_bounds: TimestampBounds {
+                min_unix_secs: parse_or(
+                    var("TIMESTAMP_MIN_UNIX_SECS"),
+                    defaults.timestamp_bounds.min_unix_secs,
+                ),
+                max_future_secs: parse_or(
+                    var("TIMESTAMP_MAX_FUTURE_SECS"),
+                    defaults.timestamp_bounds.max_future_secs,
+                ),
+                action: parse_or(
+                    var("TIMESTAMP_OUT_OF_BOUNDS"),
+                    defaults.timestamp_bounds.action,
+                ),
+            },
+            span_links_table: parse_or(var("SPAN_LINKS_TABLE"), defaults.span_links_table),
+            summary_table: parse_or(var("SUMMARY_TABLE"), defaults.summary_table),
+            strict_json: parse_or(var("STRICT_JSON"), defaults.strict_json),
+            multiline_join: MultilineJoin {
+                enabled: parse_or(var("LOG_MULTILINE_JOIN"), defaults.multiline_join.enabled),
+            },
+            max_request_size: parse_or(var("MAX_REQUEST_BYTES"), defaults.max_request_size),
+            max_decompressed_size: parse_or(
+                var("MAX_DECOMPRESSED_BYTES"),
+                defaults.max_decompressed_size,
+            ),
+            decompress_prealloc_factor: parse_or(
+                var("DECOMPRESS_PREALLOC_FACTOR"),
+                defaults.decompress_prealloc_factor,
+            ),
+            max_compression_ratio: parse_or(
+                var("MAX_COMPRESSION_RATIO"),
+                defaults.max_compression_ratio,
+            ),
+            counter_reset_detection: parse_or(
+                var("COUNTER_RESET_DETECTION"),
+                defaults.counter_reset_detection,
+            ),
+            max_tracked_series: parse_or(var("MAX_TRACKED_SERIES"), defaults.max_tracked_series),
+            metric_value_precision: var("METRIC_VALUE_PRECISION").and_then(|v| parse_precision(&v)),
+            temporality_validation: parse_or(
+                var("TEMPORALITY_VALIDATION"),
+                defaults.temporality_validation,
+            ),
+            empty_response_status: match parse_or(var("EMPTY_RESPONSE_STATUS"), 0u16) {
+                204 => 204,
+                _ => defaults.empty_response_status,
+            },
+            stages: var("TRANSFORM_STAGES")
+                .map(|v| parse_stages(&v))
+                .unwrap_or(defaults.stages),
+            log_id_fields: var("LOG_ID_FIELDS")
+                .map(|v| parse_log_id_fields(&v))
+                .unwrap_or(defaults.log_id_fields),
+            log_uid_attribute: var("LOG_UID_ATTRIBUTE")
+                .map(|v| parse_log_uid_attribute(&v))
+                .unwrap_or(defaults.log_uid_attribute),
+            projection: match var("DROP_COLUMNS").map(|v| parse_projection(&v)) {
+                Some(Ok(projection)) => projection,
+                Some(Err(e)) => {
+                    tracing::warn!(error = %e, "ignoring DROP_COLUMNS");
+                    defaults.projection
+                }
+                None => defaults.projection,
+            },
+            attribute_keys: AttributeKeyNormalization {
+                enabled: parse_or(
+                    var("ATTRIBUTE_KEY_NORMALIZE"),
+                    defaults.attribute_keys.enabled,
+                ),
+                replacement: match var("ATTRIBUTE_KEY_REPLACEMENT") {
+                    Some(v) => parse_replacement(&v).unwrap_or_else(|| {
+                        tracing::warn!(value = %v, "ignoring ATTRIBUTE_KEY_REPLACEMENT");
+                        defaults.attribute_keys.replacement
+                    }),
+                    None => defaults.attribute_keys.replacement,
+                },
+            },
+            severity_filter: SeverityFilter::from_lookup(&mut var),
+            service_name_fallback: var("SERVICE_NAME_FALLBACK_ATTRIBUTES")
+                .map(|v| ServiceNameFallback::parse(&v))
+                .unwrap_or_default(),
+            service_rate_limit: ServiceRateLimit::from_lookup(&mut var),
+            redaction: RedactionConfig::from_lookup(var),
+        }
+    }
+
+    /// Tables beyond the per-signal ones, each sent to its own `PIPELINE_{TABLE}` endpoint
+    pub fn extra_table_names(&self) -> Vec<String> {
+        let mut names = routed_table_names(&self.metric_scope_routes);
+        if self.span_links_table {
+            names.push(SPAN_LINKS_TABLE.to_string());
+        }
+        names
+    }
+
+    /// Build config from process environment variables
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+}
+
+/// Parse `scope=suffix` pairs separated by commas.
+/// Entries with an empty scope or a suffix that isn't `[a-z0-9_]` are ignored.
+fn parse_scope_routes(value: &str) -> HashMap<String, String> {
+    value
+        .split(',')
+        .filter_map(|entry| {
+            let (scope, suffix) = entry.split_once('=')?;
+            let (scope, suffix) = (scope.trim(), suffix.trim());
+            let valid_suffix = !suffix.is_empty()
+                && suffix
+                    .chars()
+                    .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+            (!scope.is_empty() && valid_suffix).then(|| (scope.to_string(), suffix.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::timestamp_bounds::OutOfBoundsAction;
+
+    #[test]
+    fn test_defaults_when_unset() {
+        assert_eq!(
+            HandlerConfig::from_lookup(|_| None),
+            HandlerConfig::default()
+        );
+    }
+
+    #[test]
+    fn test_from_lookup_parses_values() {
+        let config = HandlerConfig::from_lookup(|name| match name {
+            "MAX_SPAN_EVENTS" => Some("5".to_string()),
+            "MAX_SPAN_LINKS" => Some("not-a-number".to_string()),
+            "MAX_TABLES_PER_REQUEST" => Some("8".to_string()),
+            "TIMESTAMP_OUT_OF_BOUNDS" => Some("drop".to_string()),
+            "EMPTY_RESPONSE_STATUS" => Some("204".to_string()),
+            "MAX_DECOMPRESSED_BYTES" => Some("52428800".to_string()),
+            "MAX_REQUEST_BYTES" => Some("1048576".to_string()),
+            "ATTRIBUTE_KEY_NORMALIZE" => Some("true".to_string()),
+            "ATTRIBUTE_KEY_REPLACEMENT" => Some("..".to_string()),
+            "SUMMARY_TABLE" => Some("true".to_string()),
+            _ => None,
+        });
+        assert!(config.summary_table);
+        assert!(config.attribute_keys.enabled);
+        assert_eq!(config.attribute_keys.replacement, '_');
+        assert_eq!(config.max_decompressed_size, 50 * 1024 * 1024);
+        assert_eq!(config.max_request_size, 1024 * 1024);
+        assert_eq!(config.empty_response_status, 204);
+        assert_eq!(config.timestamp_bounds.action, OutOfBoundsAction::Drop);
+        assert_eq!(config.max_tables_per_request, 8);
+        assert_eq!(config.max_span_events, 5);
+        assert_eq!(config.max_span_links, DEFAULT_MAX_SPAN_LINKS);
+    }
+
+    #[test]
+    fn test_parse_scope_routes() {
+        let config = HandlerConfig::from_lookup(|name| {
+            (name == "METRIC_SCOPE_ROUTES")
+                .then(|| "runtime=runtime, io.opentelemetry.jvm = jvm,bad=Has-Dash,=x".to_string())
+        });
+        assert_eq!(config.metric_scope_routes.len(), 2);
+        assert_eq!(config.metric_scope_routes["runtime"], "runtime");
+        assert_eq!(config.metric_scope_routes["io.opentelemetry.jvm"], "jvm");
+    }
+}
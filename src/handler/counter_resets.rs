@@ -0,0 +1,203 @@
+//! Counter reset detection for cumulative monotonic sums.
+//!
+//! A cumulative counter whose value drops, or whose start time moves forward,
+//! was restarted. Long-lived processes (native server, warm Lambda) remember
+//! the last point of each series and mark such points with `reset: true` so
+//! rate queries can treat them as a fresh baseline.
+
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use tracing::debug;
+
+use super::series_id::SERIES_ID_FIELD;
+
+/// Default cap on series remembered for reset detection (~100 bytes each)
+pub const DEFAULT_MAX_TRACKED_SERIES: usize = 50_000;
+
+/// OTLP `AGGREGATION_TEMPORALITY_CUMULATIVE`
+const CUMULATIVE: i64 = 2;
+
+/// Column marking a point that follows a counter reset
+const RESET_FIELD: &str = "reset";
+
+/// Last point seen for a series
+#[derive(Debug, Clone, Copy)]
+struct LastPoint {
+    timestamp: i64,
+    start_timestamp: i64,
+    value: f64,
+}
+
+/// Per-series last points, evicting the oldest series past `max_series`
+#[derive(Debug, Default)]
+pub(crate) struct ResetTracker {
+    series: HashMap<String, LastPoint>,
+    order: VecDeque<String>,
+}
+
+impl ResetTracker {
+    /// Mark each sum record with `reset`, returning how many resets were found.
+    /// Records that are not cumulative monotonic sums are marked `false`.
+    pub(crate) fn observe(&mut self, records: &mut [JsonValue], max_series: usize) -> usize {
+        let mut resets = 0;
+        for record in records.iter_mut() {
+            let reset = self.observe_point(record, max_series);
+            resets += usize::from(reset);
+            if let Some(obj) = record.as_object_mut() {
+                obj.insert(RESET_FIELD.to_string(), JsonValue::Bool(reset));
+            }
+        }
+        if resets > 0 {
+            debug!(
+                resets,
+                tracked = self.series.len(),
+                "counter resets detected"
+            );
+        }
+        resets
+    }
+
+    fn observe_point(&mut self, record: &JsonValue, max_series: usize) -> bool {
+        let is_cumulative_counter = record.get("is_monotonic").and_then(JsonValue::as_bool)
+            == Some(true)
+            && record
+                .get("aggregation_temporality")
+                .and_then(JsonValue::as_i64)
+                == Some(CUMULATIVE);
+        let (Some(series_id), Some(value)) = (
+            record.get(SERIES_ID_FIELD).and_then(JsonValue::as_str),
+            record.get("value").and_then(JsonValue::as_f64),
+        ) else {
+            return false;
+        };
+        if !is_cumulative_counter || max_series == 0 {
+            return false;
+        }
+
+        let point = LastPoint {
+            timestamp: int_field(record, "timestamp"),
+            start_timestamp: int_field(record, "start_timestamp"),
+            value,
+        };
+        match self.series.get_mut(series_id) {
+            Some(last) => {
+                // Late or duplicate points neither update state nor count as resets
+                if point.timestamp <= last.timestamp {
+                    return false;
+                }
+                let reset = point.value < last.value
+                    || (point.start_timestamp > 0 && point.start_timestamp > last.start_timestamp);
+                *last = point;
+                reset
+            }
+            None => {
+                while self.series.len() >= max_series {
+                    let Some(oldest) = self.order.pop_front() else {
+                        break;
+                    };
+                    self.series.remove(&oldest);
+                }
+                self.series.insert(series_id.to_string(), point);
+                self.order.push_back(series_id.to_string());
+                false
+            }
+        }
+    }
+}
+
+fn int_field(record: &JsonValue, field: &str) -> i64 {
+    record.get(field).and_then(JsonValue::as_i64).unwrap_or(0)
+}
+
+/// Run `records` through the process-wide tracker
+pub(crate) fn detect_resets(records: &mut [JsonValue], max_series: usize) -> usize {
+    static TRACKER: OnceLock<Mutex<ResetTracker>> = OnceLock::new();
+    let mut tracker = TRACKER
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    tracker.observe(records, max_series)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn point(series: &str, timestamp: i64, start: i64, value: f64) -> JsonValue {
+        json!({
+            "series_id": series,
+            "timestamp": timestamp,
+            "start_timestamp": start,
+            "value": value,
+            "is_monotonic": true,
+            "aggregation_temporality": CUMULATIVE
+        })
+    }
+
+    fn resets(records: &[JsonValue]) -> Vec<bool> {
+        records
+            .iter()
+            .map(|r| r[RESET_FIELD].as_bool().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_value_drop_is_a_reset() {
+        let mut tracker = ResetTracker::default();
+        let mut records = vec![
+            point("a", 1, 0, 10.0),
+            point("a", 2, 0, 15.0),
+            point("a", 3, 0, 3.0),
+            point("a", 4, 0, 8.0),
+        ];
+
+        assert_eq!(tracker.observe(&mut records, 10), 1);
+        assert_eq!(resets(&records), vec![false, false, true, false]);
+
+        // State carries across batches
+        let mut next = vec![point("a", 5, 0, 1.0)];
+        assert_eq!(tracker.observe(&mut next, 10), 1);
+    }
+
+    #[test]
+    fn test_new_start_timestamp_is_a_reset() {
+        let mut tracker = ResetTracker::default();
+        let mut records = vec![point("a", 1, 100, 10.0), point("a", 2, 200, 12.0)];
+        assert_eq!(tracker.observe(&mut records, 10), 1);
+        assert_eq!(resets(&records), vec![false, true]);
+    }
+
+    #[test]
+    fn test_late_points_and_other_sums_are_not_resets() {
+        let mut tracker = ResetTracker::default();
+        let mut delta = point("d", 2, 0, 1.0);
+        delta["aggregation_temporality"] = json!(1);
+        let mut records = vec![
+            point("a", 2, 0, 10.0),
+            point("a", 1, 0, 5.0),
+            delta.clone(),
+            delta,
+        ];
+        assert_eq!(tracker.observe(&mut records, 10), 0);
+        assert_eq!(resets(&records), vec![false; 4]);
+    }
+
+    #[test]
+    fn test_tracked_series_are_bounded() {
+        let mut tracker = ResetTracker::default();
+        let mut records = vec![
+            point("a", 1, 0, 10.0),
+            point("b", 1, 0, 10.0),
+            point("c", 1, 0, 10.0),
+        ];
+        tracker.observe(&mut records, 2);
+        assert_eq!(tracker.series.len(), 2);
+        assert!(!tracker.series.contains_key("a"));
+
+        // Evicted series start over, so a drop is not detected
+        let mut records = vec![point("a", 2, 0, 1.0)];
+        assert_eq!(tracker.observe(&mut records, 2), 0);
+    }
+}
@@ -0,0 +1,103 @@
+//! Diagnostics for protobuf payloads that fail to decode.
+//!
+//! prost rejects a malformed request wholesale without saying where, so this
+//! walks the top-level wire format to locate the first bad field.
+
+use otlp2records::decode::looks_like_json;
+
+/// Protobuf wire type is the low 3 bits of a field key
+const WIRE_TYPE_MASK: u64 = 0x7;
+
+/// Explain why a body failed protobuf decoding
+pub(crate) fn protobuf_diagnostic(body: &[u8]) -> String {
+    if looks_like_json(body) {
+        return "body looks like JSON; send it with Content-Type: application/json".to_string();
+    }
+    match first_invalid_offset(body) {
+        Some(offset) => format!(
+            "invalid protobuf field at byte {} of {} (truncated body or trailing bytes?)",
+            offset,
+            body.len()
+        ),
+        None => format!(
+            "top-level protobuf framing is valid across {} bytes; a nested message is malformed",
+            body.len()
+        ),
+    }
+}
+
+/// Byte offset of the first top-level field whose key or length is invalid
+pub(crate) fn first_invalid_offset(body: &[u8]) -> Option<usize> {
+    let mut pos = 0;
+    while pos < body.len() {
+        let field_start = pos;
+        let Some(key) = read_varint(body, &mut pos) else {
+            return Some(field_start);
+        };
+        if key >> 3 == 0 {
+            return Some(field_start);
+        }
+
+        let skip = match key & WIRE_TYPE_MASK {
+            0 => read_varint(body, &mut pos).map(|_| 0),
+            1 => Some(8),
+            2 => read_varint(body, &mut pos).and_then(|len| usize::try_from(len).ok()),
+            5 => Some(4),
+            _ => None,
+        };
+        match skip.and_then(|skip| pos.checked_add(skip)) {
+            Some(end) if end <= body.len() => pos = end,
+            _ => return Some(field_start),
+        }
+    }
+    None
+}
+
+fn read_varint(body: &[u8], pos: &mut usize) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *body.get(*pos)?;
+        *pos += 1;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// ExportLogsServiceRequest with one empty ResourceLogs (field 1, length 0)
+    const VALID: &[u8] = &[0x0a, 0x00];
+
+    #[test]
+    fn test_valid_framing_has_no_offset() {
+        assert_eq!(first_invalid_offset(VALID), None);
+        assert_eq!(first_invalid_offset(&[]), None);
+    }
+
+    #[test]
+    fn test_trailing_garbage_reports_offset() {
+        let body = [VALID, &[0xff, 0xff, 0xff]].concat();
+        assert_eq!(first_invalid_offset(&body), Some(2));
+
+        let diagnostic = protobuf_diagnostic(&body);
+        assert!(diagnostic.contains("byte 2 of 5"), "{}", diagnostic);
+    }
+
+    #[test]
+    fn test_truncated_length_reports_offset() {
+        // Field 1 claims 16 bytes but only 2 follow
+        assert_eq!(first_invalid_offset(&[0x0a, 0x10, 0x01, 0x02]), Some(0));
+    }
+
+    #[test]
+    fn test_json_sent_as_protobuf_is_distinct() {
+        let diagnostic = protobuf_diagnostic(br#"{"resourceLogs":[]}"#);
+        assert!(diagnostic.contains("looks like JSON"), "{}", diagnostic);
+        assert!(!protobuf_diagnostic(&[0x0a, 0x00, 0xff]).contains("JSON"));
+    }
+}
@@ -0,0 +1,266 @@
+use bytes::Bytes;
+use flate2::read::{GzDecoder, ZlibDecoder};
+use ruzstd::decoding::StreamingDecoder;
+use std::io::Read;
+use tracing::{debug, error};
+
+use super::{HandleError, HandlerConfig};
+
+/// Request body compression, from the `Content-Encoding` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    /// Pure-Rust decoder, so it works in the wasm32 worker too
+    Zstd,
+    /// zlib-wrapped deflate, as HTTP defines it
+    Deflate,
+}
+
+impl Compression {
+    /// Unrecognized encodings are treated as uncompressed
+    pub fn from_content_encoding(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            Some("gzip" | "x-gzip") => Compression::Gzip,
+            Some("zstd") => Compression::Zstd,
+            Some("deflate") => Compression::Deflate,
+            _ => Compression::None,
+        }
+    }
+
+    fn decoder<'a>(self, body: &'a [u8]) -> Result<Box<dyn Read + 'a>, String> {
+        Ok(match self {
+            Compression::None => Box::new(body),
+            Compression::Gzip => Box::new(GzDecoder::new(body)),
+            Compression::Deflate => Box::new(ZlibDecoder::new(body)),
+            Compression::Zstd => Box::new(StreamingDecoder::new(body).map_err(|e| e.to_string())?),
+        })
+    }
+}
+
+/// Decompress a body, bounded by `max_decompressed_size` bytes and by
+/// `max_compression_ratio` times the compressed size (0 disables the ratio check).
+pub fn decompress_body(
+    body: Bytes,
+    compression: Compression,
+    config: &HandlerConfig,
+) -> Result<Bytes, HandleError> {
+    let max_size = config.max_decompressed_size;
+    let max_ratio = config.max_compression_ratio;
+    if compression == Compression::None {
+        if body.len() > max_size {
+            error!(
+                bytes_read = body.len(),
+                max = max_size,
+                "uncompressed body exceeds limit"
+            );
+            return Err(size_limit_error(max_size));
+        }
+        return Ok(body);
+    }
+
+    debug!(
+        compressed_size = body.len(),
+        ?compression,
+        "decompressing body"
+    );
+    let ratio_limit = match max_ratio {
+        0 => usize::MAX,
+        ratio => body.len().saturating_mul(ratio),
+    };
+    let limit = max_size.min(ratio_limit);
+    let decoder = compression.decoder(body.as_ref()).map_err(|e| {
+        error!(error = %e, ?compression, "decompression failed");
+        HandleError::Decompress(e)
+    })?;
+    // Preallocate for the expected ratio; the limit caps what a lying header can reserve
+    let capacity = body
+        .len()
+        .saturating_mul(config.decompress_prealloc_factor)
+        .min(limit.saturating_add(1));
+    let mut decompressed = Vec::with_capacity(capacity);
+    // Reading stops one byte past the tighter limit, so bombs abort early
+    let bytes_read = decoder
+        .take((limit + 1) as u64)
+        .read_to_end(&mut decompressed)
+        .map_err(|e| {
+            error!(error = %e, ?compression, "decompression failed");
+            HandleError::Decompress(e.to_string())
+        })?;
+    if bytes_read > max_size {
+        error!(
+            bytes_read,
+            max = max_size,
+            "decompressed size exceeds limit"
+        );
+        return Err(size_limit_error(max_size));
+    }
+    if bytes_read > limit {
+        error!(
+            compressed_size = body.len(),
+            max_ratio, "compression ratio exceeds limit"
+        );
+        return Err(HandleError::Decompress(format!(
+            "compression ratio exceeds {}:1 limit",
+            max_ratio
+        )));
+    }
+    debug!(decompressed_size = bytes_read, "decompression complete");
+    Ok(Bytes::from(decompressed))
+}
+
+/// "exceeds 10MB limit" for whole megabytes, otherwise the byte count
+fn size_limit_error(max_size: usize) -> HandleError {
+    const MB: usize = 1024 * 1024;
+    let limit = if max_size >= MB && max_size.is_multiple_of(MB) {
+        format!("{}MB", max_size / MB)
+    } else {
+        format!("{} byte", max_size)
+    };
+    HandleError::Decompress(format!("exceeds {} limit", limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::config::DEFAULT_MAX_DECOMPRESSED_SIZE;
+    use flate2::write::{GzEncoder, ZlibEncoder};
+    use ruzstd::encoding::{compress_to_vec, CompressionLevel};
+    use std::io::Write;
+
+    fn gzip(data: &[u8]) -> Bytes {
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::best());
+        encoder.write_all(data).unwrap();
+        Bytes::from(encoder.finish().unwrap())
+    }
+
+    fn compress(data: &[u8], compression: Compression) -> Bytes {
+        match compression {
+            Compression::None => Bytes::copy_from_slice(data),
+            Compression::Gzip => gzip(data),
+            Compression::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::best());
+                encoder.write_all(data).unwrap();
+                Bytes::from(encoder.finish().unwrap())
+            }
+            Compression::Zstd => Bytes::from(compress_to_vec(data, CompressionLevel::Fastest)),
+        }
+    }
+
+    fn limits(max_decompressed_size: usize, max_compression_ratio: usize) -> HandlerConfig {
+        HandlerConfig {
+            max_decompressed_size,
+            max_compression_ratio,
+            ..HandlerConfig::default()
+        }
+    }
+
+    const ALL: [Compression; 4] = [
+        Compression::None,
+        Compression::Gzip,
+        Compression::Zstd,
+        Compression::Deflate,
+    ];
+
+    #[test]
+    fn test_high_ratio_body_is_rejected_under_size_cap() {
+        // 1MB of zeros compresses to ~1KB, far beyond 100:1 but under 10MB
+        let bomb = gzip(&vec![0u8; 1024 * 1024]);
+        let err = decompress_body(
+            bomb.clone(),
+            Compression::Gzip,
+            &limits(DEFAULT_MAX_DECOMPRESSED_SIZE, 100),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(&err, HandleError::Decompress(msg) if msg.contains("100:1")),
+            "got {}",
+            err
+        );
+
+        // Disabling the ratio check falls back to the absolute cap
+        assert!(decompress_body(
+            bomb,
+            Compression::Gzip,
+            &limits(DEFAULT_MAX_DECOMPRESSED_SIZE, 0)
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_normal_body_passes() {
+        let payload = include_bytes!("../../tests/fixtures/sample_otlp.json");
+        for compression in ALL {
+            let body = decompress_body(
+                compress(payload, compression),
+                compression,
+                &limits(DEFAULT_MAX_DECOMPRESSED_SIZE, 100),
+            )
+            .unwrap();
+            assert_eq!(body.as_ref(), payload, "{:?}", compression);
+        }
+    }
+
+    #[test]
+    fn test_from_content_encoding() {
+        let parse = Compression::from_content_encoding;
+        assert_eq!(parse(Some("GZIP")), Compression::Gzip);
+        assert_eq!(parse(Some("zstd")), Compression::Zstd);
+        assert_eq!(parse(Some(" deflate ")), Compression::Deflate);
+        assert_eq!(parse(Some("br")), Compression::None);
+        assert_eq!(parse(None), Compression::None);
+    }
+
+    #[test]
+    fn test_corrupt_body_is_decompress_error() {
+        for compression in [Compression::Gzip, Compression::Zstd, Compression::Deflate] {
+            let err = decompress_body(
+                Bytes::from_static(b"not compressed"),
+                compression,
+                &limits(DEFAULT_MAX_DECOMPRESSED_SIZE, 100),
+            )
+            .unwrap_err();
+            assert!(
+                matches!(err, HandleError::Decompress(_)),
+                "{:?}",
+                compression
+            );
+        }
+    }
+
+    #[test]
+    fn test_custom_size_limit() {
+        let limit = 64 * 1024;
+        let under = vec![b'a'; limit];
+        let over = vec![b'a'; limit + 1];
+
+        for compression in ALL {
+            assert_eq!(
+                decompress_body(
+                    compress(&under, compression),
+                    compression,
+                    &limits(limit, 0)
+                )
+                .unwrap()
+                .len(),
+                limit
+            );
+            let err = decompress_body(compress(&over, compression), compression, &limits(limit, 0))
+                .unwrap_err();
+            assert!(
+                matches!(&err, HandleError::Decompress(msg) if msg == "exceeds 65536 byte limit"),
+                "got {}",
+                err
+            );
+        }
+
+        let err = decompress_body(
+            Bytes::from(vec![0u8; 3 * 1024 * 1024]),
+            Compression::None,
+            &limits(2 * 1024 * 1024, 0),
+        )
+        .unwrap_err();
+        assert!(matches!(&err, HandleError::Decompress(msg) if msg == "exceeds 2MB limit"));
+    }
+}
@@ -0,0 +1,98 @@
+//! Rounding metric values to a fixed number of significant digits.
+//!
+//! Float arithmetic in SDKs leaves values like `0.30000000000000004`, which
+//! bloat NDJSON bodies. With `METRIC_VALUE_PRECISION=6`, float metric values
+//! are rounded to 6 significant digits before serialization (`0.3`). Off by
+//! default so values stay exact.
+
+use serde_json::Value as JsonValue;
+
+/// Float columns rounded on metric records
+const VALUE_FIELDS: &[&str] = &["value", "sum", "min", "max"];
+
+/// Most significant digits an f64 can carry; more never changes a value
+const MAX_PRECISION: u32 = 17;
+
+/// Parse `METRIC_VALUE_PRECISION` as significant digits in 1..=17; anything else disables rounding
+pub(crate) fn parse_precision(value: &str) -> Option<u32> {
+    match value.trim().parse::<u32>() {
+        Ok(digits @ 1..=MAX_PRECISION) => Some(digits),
+        _ => {
+            tracing::warn!(value, "ignoring METRIC_VALUE_PRECISION (expected 1-17)");
+            None
+        }
+    }
+}
+
+/// Round float value columns to `digits` significant digits; no-op when `None`
+pub(crate) fn round_metric_values(records: &mut [JsonValue], digits: Option<u32>) {
+    let Some(digits) = digits else {
+        return;
+    };
+    for record in records {
+        for field in VALUE_FIELDS {
+            let Some(value) = record.get_mut(*field) else {
+                continue;
+            };
+            // Integers are already exact
+            if let Some(rounded) = value
+                .as_f64()
+                .filter(|_| value.is_f64())
+                .map(|v| round(v, digits))
+            {
+                if let Some(number) = serde_json::Number::from_f64(rounded) {
+                    *value = JsonValue::Number(number);
+                }
+            }
+        }
+    }
+}
+
+/// Round through scientific notation, so the digit count is relative to the value's magnitude
+fn round(value: f64, digits: u32) -> f64 {
+    if !value.is_finite() {
+        return value;
+    }
+    format!("{:.*e}", digits as usize - 1, value)
+        .parse()
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_noisy_float_rounds_cleanly() {
+        let mut records = vec![json!({"value": 0.1 + 0.2, "sum": 1234.56789, "count": 3})];
+        round_metric_values(&mut records, Some(6));
+        assert_eq!(
+            records[0].to_string(),
+            r#"{"count":3,"sum":1234.57,"value":0.3}"#
+        );
+    }
+
+    #[test]
+    fn test_disabled_preserves_full_value() {
+        let mut records = vec![json!({"value": 0.1 + 0.2})];
+        round_metric_values(&mut records, None);
+        assert_eq!(records[0]["value"].as_f64(), Some(0.30000000000000004));
+    }
+
+    #[test]
+    fn test_small_values_keep_significant_digits() {
+        let mut records = vec![json!({"value": 0.000123456789, "min": 9})];
+        round_metric_values(&mut records, Some(3));
+        assert_eq!(records[0]["value"].as_f64(), Some(0.000123));
+        assert_eq!(records[0]["min"], 9);
+    }
+
+    #[test]
+    fn test_parse_precision() {
+        assert_eq!(parse_precision(" 6 "), Some(6));
+        assert_eq!(parse_precision("0"), None);
+        assert_eq!(parse_precision("18"), None);
+        assert_eq!(parse_precision("six"), None);
+    }
+}
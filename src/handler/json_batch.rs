@@ -0,0 +1,143 @@
+//! Support for JSON bodies holding an array of export requests.
+//!
+//! Some batchers concatenate several `Export*ServiceRequest` objects into a
+//! top-level JSON array. The decoders expect one request, so each element is
+//! transformed separately and the records concatenated.
+
+use otlp2records::decode::DecodeError;
+use otlp2records::{InputFormat, JsonMetricBatches};
+use serde_json::Value as JsonValue;
+
+/// Split a top-level JSON array into one body per request.
+/// Returns `None` for anything else, including protobuf and JSONL bodies.
+fn split_array(body: &[u8], format: InputFormat) -> Option<Result<Vec<Vec<u8>>, DecodeError>> {
+    if !matches!(format, InputFormat::Json | InputFormat::Auto) {
+        return None;
+    }
+    if body.iter().find(|b| !b.is_ascii_whitespace()) != Some(&b'[') {
+        return None;
+    }
+    Some(
+        serde_json::from_slice::<Vec<JsonValue>>(body)
+            .and_then(|elements| elements.iter().map(serde_json::to_vec).collect())
+            .map_err(DecodeError::Json),
+    )
+}
+
+/// Transform a body with `transform`, concatenating records across array elements
+pub(crate) fn transform_each<T>(
+    body: &[u8],
+    format: InputFormat,
+    transform: impl Fn(&[u8], InputFormat) -> Result<Vec<T>, otlp2records::Error>,
+) -> Result<Vec<T>, otlp2records::Error> {
+    let Some(elements) = split_array(body, format) else {
+        return transform(body, format);
+    };
+    let mut records = Vec::new();
+    for element in elements? {
+        records.extend(transform(&element, InputFormat::Json)?);
+    }
+    Ok(records)
+}
+
+/// Metrics variant of [`transform_each`], merging per-type batches and skip counts
+pub(crate) fn transform_metrics_each(
+    body: &[u8],
+    format: InputFormat,
+    transform: impl Fn(&[u8], InputFormat) -> Result<JsonMetricBatches, otlp2records::Error>,
+) -> Result<JsonMetricBatches, otlp2records::Error> {
+    let Some(elements) = split_array(body, format) else {
+        return transform(body, format);
+    };
+    let mut merged = JsonMetricBatches {
+        gauge: Vec::new(),
+        sum: Vec::new(),
+        histogram: Vec::new(),
+        exp_histogram: Vec::new(),
+        skipped: Default::default(),
+    };
+    for element in elements? {
+        let batch = transform(&element, InputFormat::Json)?;
+        merged.gauge.extend(batch.gauge);
+        merged.sum.extend(batch.sum);
+        merged.histogram.extend(batch.histogram);
+        merged.exp_histogram.extend(batch.exp_histogram);
+        merged.skipped.summaries += batch.skipped.summaries;
+        merged.skipped.nan_values += batch.skipped.nan_values;
+        merged.skipped.infinity_values += batch.skipped.infinity_values;
+        merged.skipped.missing_values += batch.skipped.missing_values;
+    }
+    Ok(merged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use otlp2records::{transform_logs_json, transform_metrics_json};
+    use serde_json::json;
+
+    fn logs_request(body: &str) -> JsonValue {
+        json!({
+            "resourceLogs": [{
+                "scopeLogs": [{
+                    "logRecords": [{
+                        "timeUnixNano": "1703265600000000000",
+                        "body": {"stringValue": body}
+                    }]
+                }]
+            }]
+        })
+    }
+
+    fn gauge_request(value: f64) -> JsonValue {
+        json!({
+            "resourceMetrics": [{
+                "scopeMetrics": [{
+                    "metrics": [{
+                        "name": "cpu",
+                        "gauge": {"dataPoints": [{"timeUnixNano": "1703265600000000000", "asDouble": value}]}
+                    }]
+                }]
+            }]
+        })
+    }
+
+    #[test]
+    fn test_array_of_logs_requests_is_concatenated() {
+        let body = json!([logs_request("first"), logs_request("second")]).to_string();
+
+        for format in [InputFormat::Json, InputFormat::Auto] {
+            let records = transform_each(body.as_bytes(), format, transform_logs_json).unwrap();
+            assert_eq!(records.len(), 2);
+            assert_eq!(records[0]["body"], "first");
+            assert_eq!(records[1]["body"], "second");
+        }
+    }
+
+    #[test]
+    fn test_single_object_is_unchanged() {
+        let body = logs_request("only").to_string();
+        let records =
+            transform_each(body.as_bytes(), InputFormat::Json, transform_logs_json).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["body"], "only");
+    }
+
+    #[test]
+    fn test_array_of_metrics_requests_is_merged() {
+        let body = json!([gauge_request(1.0), gauge_request(2.0)]).to_string();
+        let batches =
+            transform_metrics_each(body.as_bytes(), InputFormat::Json, transform_metrics_json)
+                .unwrap();
+        assert_eq!(batches.gauge.len(), 2);
+    }
+
+    #[test]
+    fn test_malformed_array_is_a_decode_error() {
+        let err = transform_each(b"[{}, ", InputFormat::Json, transform_logs_json).unwrap_err();
+        assert!(matches!(
+            err,
+            otlp2records::Error::Decode(DecodeError::Json(_))
+        ));
+    }
+}
@@ -0,0 +1,96 @@
+//! Stable `log_id` for deduplicating retried log exports.
+//!
+//! The ID hashes a configurable set of columns (by default trace/span IDs,
+//! observed timestamp and body), so a log re-sent by a retrying exporter gets
+//! the same `log_id` and downstream stores can upsert on it.
+
+use serde_json::Value as JsonValue;
+
+use super::series_id::fingerprint;
+
+/// Column added to log records
+pub(crate) const LOG_ID_FIELD: &str = "log_id";
+
+/// Columns hashed into `log_id` when `LOG_ID_FIELDS` is unset
+pub const DEFAULT_LOG_ID_FIELDS: &[&str] = &["trace_id", "span_id", "observed_timestamp", "body"];
+
+/// Parse `LOG_ID_FIELDS` as comma-separated column names; `none` disables `log_id`
+pub(crate) fn parse_log_id_fields(value: &str) -> Vec<String> {
+    if value.trim().eq_ignore_ascii_case("none") {
+        return Vec::new();
+    }
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty() && *field != LOG_ID_FIELD)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Set `log_id` on each record from `fields`; no-op when `fields` is empty
+pub(crate) fn assign_log_ids(records: &mut [JsonValue], fields: &[String]) {
+    if fields.is_empty() {
+        return;
+    }
+    for record in records.iter_mut() {
+        let id = fingerprint(record, fields);
+        if let Some(obj) = record.as_object_mut() {
+            obj.insert(LOG_ID_FIELD.to_string(), JsonValue::String(id));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn default_fields() -> Vec<String> {
+        DEFAULT_LOG_ID_FIELDS
+            .iter()
+            .map(|f| f.to_string())
+            .collect()
+    }
+
+    fn log(body: &str, observed_timestamp: i64) -> JsonValue {
+        json!({
+            "timestamp": 1703265600000000_i64,
+            "observed_timestamp": observed_timestamp,
+            "trace_id": "0af7651916cd43dd8448eb211c80319c",
+            "span_id": "b7ad6b7169203331",
+            "body": body,
+            "severity_number": 9
+        })
+    }
+
+    #[test]
+    fn test_identical_logs_share_log_id() {
+        let mut records = vec![log("hello", 1), log("hello", 1)];
+        assign_log_ids(&mut records, &default_fields());
+
+        let id = records[0][LOG_ID_FIELD].as_str().unwrap();
+        assert_eq!(id.len(), 16);
+        assert_eq!(records[1][LOG_ID_FIELD], id);
+    }
+
+    #[test]
+    fn test_differing_logs_get_different_log_ids() {
+        let mut records = vec![log("hello", 1), log("goodbye", 1), log("hello", 2)];
+        assign_log_ids(&mut records, &default_fields());
+
+        assert_ne!(records[0][LOG_ID_FIELD], records[1][LOG_ID_FIELD]);
+        assert_ne!(records[0][LOG_ID_FIELD], records[2][LOG_ID_FIELD]);
+    }
+
+    #[test]
+    fn test_configured_fields() {
+        // Only the body counts, so a different observed timestamp is a duplicate
+        let mut records = vec![log("hello", 1), log("hello", 2)];
+        assign_log_ids(&mut records, &parse_log_id_fields(" body ,log_id"));
+        assert_eq!(records[0][LOG_ID_FIELD], records[1][LOG_ID_FIELD]);
+
+        let mut records = vec![log("hello", 1)];
+        assign_log_ids(&mut records, &parse_log_id_fields("none"));
+        assert!(records[0].get(LOG_ID_FIELD).is_none());
+    }
+}
@@ -0,0 +1,87 @@
+//! Promoting an SDK-provided log record UID into its own column.
+//!
+//! OTLP has no universal log record ID, but some SDKs set one as an
+//! attribute (`log.record.uid` by default). Its value is copied into
+//! `log_uid` so stores can key idempotent writes on a real UID; records
+//! without the attribute get an empty `log_uid`.
+
+use serde_json::Value as JsonValue;
+
+use super::series_id::attributes;
+
+/// Column added to log records
+pub(crate) const LOG_UID_FIELD: &str = "log_uid";
+
+/// Attribute promoted when `LOG_UID_ATTRIBUTE` is unset
+pub const DEFAULT_LOG_UID_ATTRIBUTE: &str = "log.record.uid";
+
+/// Parse `LOG_UID_ATTRIBUTE`; `none` (or empty) disables `log_uid`
+pub(crate) fn parse_log_uid_attribute(value: &str) -> String {
+    let value = value.trim();
+    if value.eq_ignore_ascii_case("none") {
+        return String::new();
+    }
+    value.to_string()
+}
+
+/// Set `log_uid` on each record from `attribute` in `log_attributes`;
+/// no-op when `attribute` is empty
+pub(crate) fn assign_log_uids(records: &mut [JsonValue], attribute: &str) {
+    if attribute.is_empty() {
+        return;
+    }
+    for record in records.iter_mut() {
+        let uid = match attributes(record.get("log_attributes")).get(attribute) {
+            Some(JsonValue::String(uid)) => uid.clone(),
+            Some(JsonValue::Null) | None => String::new(),
+            Some(other) => other.to_string(),
+        };
+        if let Some(obj) = record.as_object_mut() {
+            obj.insert(LOG_UID_FIELD.to_string(), JsonValue::String(uid));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_promotes_attribute_when_present() {
+        let mut records = vec![
+            json!({"log_attributes": r#"{"log.record.uid":"01HV6Z","http.method":"GET"}"#}),
+            json!({"log_attributes": {"log.record.uid": 42}}),
+        ];
+        assign_log_uids(&mut records, DEFAULT_LOG_UID_ATTRIBUTE);
+        assert_eq!(records[0][LOG_UID_FIELD], "01HV6Z");
+        assert_eq!(records[1][LOG_UID_FIELD], "42");
+        // The attribute itself stays in place
+        assert!(records[0]["log_attributes"]
+            .as_str()
+            .unwrap()
+            .contains("log.record.uid"));
+    }
+
+    #[test]
+    fn test_empty_when_attribute_absent() {
+        let mut records = vec![
+            json!({"log_attributes": r#"{"http.method":"GET"}"#}),
+            json!({"body": "no attributes"}),
+        ];
+        assign_log_uids(&mut records, DEFAULT_LOG_UID_ATTRIBUTE);
+        assert_eq!(records[0][LOG_UID_FIELD], "");
+        assert_eq!(records[1][LOG_UID_FIELD], "");
+    }
+
+    #[test]
+    fn test_configured_attribute() {
+        let mut records = vec![json!({"log_attributes": r#"{"event.id":"e-1"}"#})];
+        assign_log_uids(&mut records, &parse_log_uid_attribute(" event.id "));
+        assert_eq!(records[0][LOG_UID_FIELD], "e-1");
+
+        let mut records = vec![json!({"log_attributes": "{}"})];
+        assign_log_uids(&mut records, &parse_log_uid_attribute("none"));
+        assert!(records[0].get(LOG_UID_FIELD).is_none());
+    }
+}
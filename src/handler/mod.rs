@@ -1,29 +1,65 @@
 #[cfg(target_arch = "wasm32")]
 use crate::livetail::LiveTailSender;
 use bytes::Bytes;
-use flate2::read::GzDecoder;
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 #[cfg(target_arch = "wasm32")]
 use std::collections::HashSet;
-use std::io::Read;
 use tracing::{debug, error, info, warn, Span};
 
 use crate::pipeline::PipelineSender;
 use crate::signal::Signal;
 use crate::InputFormat;
-
+use otlp2records::decode::DecodeError;
+
+mod attribute_keys;
+mod config;
+mod counter_resets;
+mod decode_diagnostics;
+mod decompress;
+mod float_precision;
+mod json_batch;
+mod log_id;
+mod log_uid;
+mod multiline;
+mod plain_text;
+mod projection;
+mod redaction;
+mod response;
+mod root_span;
+mod scope_routing;
+mod series_id;
+mod service_fallback;
+mod service_rate_limit;
+mod severity_filter;
 mod signal_handlers;
-
+mod span_limits;
+mod span_links;
+mod stages;
+mod strict_json;
+mod summary;
+mod table_limit;
+mod temporality;
+mod timestamp_bounds;
+
+pub use config::HandlerConfig;
+pub use decompress::{decompress_body, Compression};
+pub use plain_text::BodyFormat;
+pub use redaction::RedactionConfig;
+pub use response::{
+    verbose_requested, HandleResponse, ResponseWarning, SkippedMetricsWarning, TransformCounts,
+};
+pub use severity_filter::SeverityFilter;
 pub use signal_handlers::{LogsHandler, MetricsHandler, TracesHandler};
-
-const MAX_DECOMPRESSED_SIZE: usize = 10 * 1024 * 1024;
+pub use stages::{run_stages, StageKind, TransformStage};
+pub use timestamp_bounds::{OutOfBoundsAction, TimestampBounds};
 
 #[derive(Debug)]
 pub enum HandleError {
     Decompress(String),
     Decode(String),
     Transform(String),
+    TooManyTables(String),
     SendFailed(String),
 }
 
@@ -33,99 +69,17 @@ impl std::fmt::Display for HandleError {
             HandleError::Decompress(e) => write!(f, "decompress error: {}", e),
             HandleError::Decode(e) => write!(f, "decode error: {}", e),
             HandleError::Transform(e) => write!(f, "transform error: {}", e),
+            HandleError::TooManyTables(e) => write!(f, "too many tables: {}", e),
             HandleError::SendFailed(e) => write!(f, "send failed: {}", e),
         }
     }
 }
 
-#[derive(Debug, serde::Serialize)]
-pub struct HandleResponse {
-    pub status: &'static str,
-    pub records: HashMap<String, usize>,
-    #[serde(skip_serializing_if = "HashMap::is_empty")]
-    pub errors: HashMap<String, String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub warnings: Option<SkippedMetricsWarning>,
-    #[serde(skip)]
-    pub service_names: Vec<String>,
-    #[serde(skip)]
-    pub metric_names: Vec<(String, String)>,
-}
-
-/// Warning info for skipped metrics, surfaced to users in the response
-#[derive(Debug, Clone, serde::Serialize)]
-pub struct SkippedMetricsWarning {
-    pub message: &'static str,
-    pub skipped_total: usize,
-    #[serde(skip_serializing_if = "is_zero")]
-    pub histograms: usize,
-    #[serde(skip_serializing_if = "is_zero")]
-    pub exponential_histograms: usize,
-    #[serde(skip_serializing_if = "is_zero")]
-    pub summaries: usize,
-    #[serde(skip_serializing_if = "is_zero")]
-    pub nan_values: usize,
-    #[serde(skip_serializing_if = "is_zero")]
-    pub infinity_values: usize,
-    #[serde(skip_serializing_if = "is_zero")]
-    pub missing_values: usize,
-}
-
-fn is_zero(n: &usize) -> bool {
-    *n == 0
-}
-
-impl HandleResponse {
-    pub fn empty() -> Self {
-        Self {
-            status: "ok",
-            records: HashMap::new(),
-            errors: HashMap::new(),
-            warnings: None,
-            service_names: Vec::new(),
-            metric_names: Vec::new(),
-        }
-    }
-
-    pub fn from_result(result: crate::pipeline::SendResult) -> Self {
-        let status = if result.failed.is_empty() {
-            "ok"
-        } else if result.succeeded.is_empty() {
-            "error"
-        } else {
-            "partial"
-        };
-
-        Self {
-            status,
-            records: result.succeeded,
-            errors: result.failed,
-            warnings: None,
-            service_names: Vec::new(),
-            metric_names: Vec::new(),
-        }
-    }
-
-    pub fn with_service_names(mut self, service_names: Vec<String>) -> Self {
-        self.service_names = service_names;
-        self
-    }
-
-    pub fn with_metric_names(mut self, metric_names: Vec<(String, String)>) -> Self {
-        self.metric_names = metric_names;
-        self
-    }
-
-    pub fn with_warnings(mut self, warnings: Option<SkippedMetricsWarning>) -> Self {
-        self.warnings = warnings;
-        self
-    }
-}
-
 /// Result of transforming a signal payload
 pub struct TransformResult {
     pub grouped: HashMap<String, Vec<JsonValue>>,
     pub skipped: Option<SkippedMetricsWarning>,
+    pub counts: TransformCounts,
 }
 
 /// Trait for signal-specific decode and transform logic
@@ -134,7 +88,11 @@ pub trait SignalHandler {
     const SIGNAL: Signal;
 
     /// Decode and transform a payload into table-grouped JSON records.
-    fn transform(body: Bytes, format: InputFormat) -> Result<TransformResult, otlp2records::Error>;
+    fn transform(
+        body: Bytes,
+        format: InputFormat,
+        config: &HandlerConfig,
+    ) -> Result<TransformResult, otlp2records::Error>;
 }
 
 /// Extract unique service names from grouped records
@@ -179,93 +137,78 @@ fn extract_metric_names(grouped: &HashMap<String, Vec<JsonValue>>) -> Vec<(Strin
     metric_names.into_iter().collect()
 }
 
-pub(crate) fn decompress_if_gzipped(body: Bytes, is_gzipped: bool) -> Result<Bytes, HandleError> {
-    if !is_gzipped && body.len() > MAX_DECOMPRESSED_SIZE {
-        error!(
-            bytes_read = body.len(),
-            max = MAX_DECOMPRESSED_SIZE,
-            "uncompressed body exceeds limit"
-        );
-        return Err(HandleError::Decompress(format!(
-            "exceeds {}MB limit",
-            MAX_DECOMPRESSED_SIZE / 1024 / 1024
-        )));
-    }
-
-    if is_gzipped {
-        debug!(compressed_size = body.len(), "decompressing gzipped body");
-        let decoder = GzDecoder::new(body.as_ref());
-        let mut decompressed = Vec::with_capacity(body.len().saturating_mul(2));
-        let bytes_read = decoder
-            .take((MAX_DECOMPRESSED_SIZE + 1) as u64)
-            .read_to_end(&mut decompressed)
-            .map_err(|e| {
-                error!(error = %e, "gzip decompression failed");
-                HandleError::Decompress(e.to_string())
-            })?;
-        if bytes_read > MAX_DECOMPRESSED_SIZE {
+/// Map a transform failure to a handler error, diagnosing protobuf decode failures
+fn transform_error(err: otlp2records::Error, body: &[u8]) -> HandleError {
+    match err {
+        otlp2records::Error::Decode(DecodeError::Protobuf(err)) => {
+            let diagnostic = decode_diagnostics::protobuf_diagnostic(body);
             error!(
-                bytes_read,
-                max = MAX_DECOMPRESSED_SIZE,
-                "decompressed size exceeds limit"
+                error = %err,
+                body_len = body.len(),
+                invalid_offset = ?decode_diagnostics::first_invalid_offset(body),
+                %diagnostic,
+                "failed to decode protobuf payload"
             );
-            return Err(HandleError::Decompress(format!(
-                "exceeds {}MB limit",
-                MAX_DECOMPRESSED_SIZE / 1024 / 1024
-            )));
+            HandleError::Decode(format!("{}: {}", err, diagnostic))
+        }
+        otlp2records::Error::Decode(err) => {
+            error!(error = %err, "failed to decode payload");
+            HandleError::Decode(err.to_string())
+        }
+        err => {
+            error!(error = %err, "transform failed");
+            HandleError::Transform(err.to_string())
         }
-        debug!(decompressed_size = bytes_read, "decompression complete");
-        Ok(Bytes::from(decompressed))
-    } else {
-        Ok(body)
     }
 }
 
 /// Generic handler for any signal type
 #[tracing::instrument(
     name = "ingest",
-    skip(body, sender),
+    skip(body, config, request_id, sender),
     fields(
+        request_id = %request_id,
         signal = ?H::SIGNAL,
         format = ?format,
-        gzipped = is_gzipped,
+        compression = ?compression,
         records = tracing::field::Empty,
         tables = tracing::field::Empty,
     )
 )]
 pub async fn handle_signal<H: SignalHandler, S: PipelineSender>(
     body: Bytes,
-    is_gzipped: bool,
-    format: InputFormat,
+    compression: Compression,
+    format: BodyFormat,
+    config: &HandlerConfig,
+    request_id: &str,
     sender: &S,
 ) -> Result<HandleResponse, HandleError> {
     debug!(
         body_size = body.len(),
-        is_gzipped,
+        ?compression,
         signal = ?H::SIGNAL,
         "handling signal request"
     );
 
-    let body = decompress_if_gzipped(body, is_gzipped)?;
+    let body = decompress_body(body, compression, config)?;
+    let (body, format) = plain_text::preprocess(body, format, H::SIGNAL)?;
+    if config.strict_json {
+        strict_json::check_top_level(&body, format, H::SIGNAL)?;
+    }
 
-    let transform_result = H::transform(body, format).map_err(|e| match e {
-        otlp2records::Error::Decode(err) => {
-            error!(error = %err, "failed to decode payload");
-            HandleError::Decode(err.to_string())
-        }
-        err => {
-            error!(error = %err, "transform failed");
-            HandleError::Transform(err.to_string())
-        }
-    })?;
+    let mut transform_result =
+        H::transform(body.clone(), format, config).map_err(|e| transform_error(e, &body))?;
+    stages::run_configured(config, H::SIGNAL, &mut transform_result)?;
 
     let grouped = transform_result.grouped;
-    let skipped = transform_result.skipped;
+    let warnings =
+        response::collect_warnings(transform_result.skipped.as_ref(), transform_result.counts);
 
     if grouped.is_empty() {
         debug!("no records to send");
-        return Ok(HandleResponse::empty().with_warnings(skipped));
+        return Ok(HandleResponse::empty().with_warnings(warnings));
     }
+    table_limit::check_table_limit(&grouped, config.max_tables_per_request)?;
 
     let table_counts: Vec<_> = grouped.iter().map(|(k, v)| (k.as_str(), v.len())).collect();
     debug!(?table_counts, "sending records to pipelines");
@@ -277,14 +220,15 @@ pub async fn handle_signal<H: SignalHandler, S: PipelineSender>(
     let result = sender.send_all(grouped).await;
 
     if !result.failed.is_empty() {
-        for (table, err) in &result.failed {
-            warn!(table, error = %err, "pipeline send failed");
+        for (table, failure) in &result.failed {
+            warn!(table, reason = %failure.reason, error = %failure, "pipeline send failed");
         }
     }
 
     info!(
         succeeded = result.succeeded.len(),
         failed = result.failed.len(),
+        failure_reasons = ?result.failure_counts(),
         signal = ?H::SIGNAL,
         "request complete"
     );
@@ -292,7 +236,18 @@ pub async fn handle_signal<H: SignalHandler, S: PipelineSender>(
     Span::current().record("records", total_records);
     Span::current().record("tables", &table_names);
 
-    Ok(HandleResponse::from_result(result).with_warnings(skipped))
+    Ok(HandleResponse::from_result(result).with_warnings(warnings))
+}
+
+/// Per-request context for [`handle_signal_with_cache`]
+#[cfg(target_arch = "wasm32")]
+pub struct RequestContext<'a, C, L> {
+    pub config: &'a HandlerConfig,
+    pub request_id: &'a str,
+    /// Aggregator for best-effort dual-write
+    pub cache: Option<&'a C>,
+    /// LiveTail for best-effort triple-write
+    pub livetail: Option<&'a L>,
 }
 
 /// Handle signal with optional aggregator dual-write and livetail.
@@ -304,24 +259,24 @@ pub async fn handle_signal<H: SignalHandler, S: PipelineSender>(
 #[cfg(target_arch = "wasm32")]
 #[tracing::instrument(
     name = "ingest_triple",
-    skip(body, sender, cache, livetail),
+    skip(body, sender, ctx),
     fields(
+        request_id = %ctx.request_id,
         signal = ?H::SIGNAL,
         format = ?format,
-        gzipped = is_gzipped,
+        compression = ?compression,
         records = tracing::field::Empty,
         tables = tracing::field::Empty,
-        cache_enabled = cache.is_some(),
-        livetail_enabled = livetail.is_some(),
+        cache_enabled = ctx.cache.is_some(),
+        livetail_enabled = ctx.livetail.is_some(),
     )
 )]
 pub async fn handle_signal_with_cache<H, S, C, L>(
     body: Bytes,
-    is_gzipped: bool,
-    format: InputFormat,
+    compression: Compression,
+    format: BodyFormat,
     sender: &S,
-    cache: Option<&C>,
-    livetail: Option<&L>,
+    ctx: RequestContext<'_, C, L>,
 ) -> Result<HandleResponse, HandleError>
 where
     H: SignalHandler,
@@ -329,9 +284,15 @@ where
     C: crate::aggregator::AggregatorSender,
     L: LiveTailSender,
 {
+    let RequestContext {
+        config,
+        cache,
+        livetail,
+        ..
+    } = ctx;
     debug!(
         body_size = body.len(),
-        is_gzipped,
+        ?compression,
         signal = ?H::SIGNAL,
         cache_enabled = cache.is_some(),
         livetail_enabled = livetail.is_some(),
@@ -339,27 +300,26 @@ where
     );
 
     // Decompress
-    let body = decompress_if_gzipped(body, is_gzipped)?;
+    let body = decompress_body(body, compression, config)?;
+    let (body, format) = plain_text::preprocess(body, format, H::SIGNAL)?;
+    if config.strict_json {
+        strict_json::check_top_level(&body, format, H::SIGNAL)?;
+    }
 
     // Transform
-    let transform_result = H::transform(body, format).map_err(|e| match e {
-        otlp2records::Error::Decode(err) => {
-            error!(error = %err, "failed to decode payload");
-            HandleError::Decode(err.to_string())
-        }
-        err => {
-            error!(error = %err, "transform failed");
-            HandleError::Transform(err.to_string())
-        }
-    })?;
+    let mut transform_result =
+        H::transform(body.clone(), format, config).map_err(|e| transform_error(e, &body))?;
+    stages::run_configured(config, H::SIGNAL, &mut transform_result)?;
 
     let grouped = transform_result.grouped;
-    let skipped = transform_result.skipped;
+    let warnings =
+        response::collect_warnings(transform_result.skipped.as_ref(), transform_result.counts);
 
     if grouped.is_empty() {
         debug!("no records to send");
-        return Ok(HandleResponse::empty().with_warnings(skipped));
+        return Ok(HandleResponse::empty().with_warnings(warnings));
     }
+    table_limit::check_table_limit(&grouped, config.max_tables_per_request)?;
 
     let table_counts: Vec<_> = grouped.iter().map(|(k, v)| (k.as_str(), v.len())).collect();
     debug!(?table_counts, "sending records to pipelines");
@@ -462,10 +422,15 @@ where
 
     // Pipeline failure = request failure
     if !pipeline_result.failed.is_empty() {
-        for (table, err) in &pipeline_result.failed {
-            warn!(table = %table, error = %err, "pipeline send failed");
+        for (table, failure) in &pipeline_result.failed {
+            warn!(table = %table, reason = %failure.reason, error = %failure, "pipeline send failed");
         }
 
+        warn!(
+            failure_reasons = ?pipeline_result.failure_counts(),
+            signal = ?H::SIGNAL,
+            "request failed"
+        );
         let errors: Vec<String> = pipeline_result
             .failed
             .iter()
@@ -486,5 +451,5 @@ where
     Ok(HandleResponse::from_result(pipeline_result)
         .with_service_names(service_names)
         .with_metric_names(metric_names)
-        .with_warnings(skipped))
+        .with_warnings(warnings))
 }
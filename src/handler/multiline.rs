@@ -0,0 +1,132 @@
+//! Joining multi-line log bodies split across records.
+//!
+//! Naive shippers send each line of a stack trace as its own log record.
+//! With `LOG_MULTILINE_JOIN=true`, a record with no timestamp and no
+//! severity is treated as a continuation of the record before it (from the
+//! same service, resource, and scope) and its body is appended on a new
+//! line. The joined record keeps the first record's other columns.
+
+use serde_json::Value as JsonValue;
+
+use super::stages::TransformStage;
+use super::TransformCounts;
+use crate::signal::Signal;
+
+/// Columns that must match for a record to continue the previous one
+const SOURCE_FIELDS: &[&str] = &["service_name", "resource_attributes", "scope_name"];
+
+/// Continuation-line joining for logs; off by default
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MultilineJoin {
+    pub enabled: bool,
+}
+
+impl MultilineJoin {
+    /// Merge continuation records into the record before them, returning how
+    /// many records were merged away
+    pub fn apply(&self, records: &mut Vec<JsonValue>) -> usize {
+        if !self.enabled || records.len() < 2 {
+            return 0;
+        }
+        let before = records.len();
+        let mut joined: Vec<JsonValue> = Vec::with_capacity(before);
+        for record in records.drain(..) {
+            if let Some(head) = joined.last_mut().filter(|head| continues(head, &record)) {
+                if let (Some(JsonValue::String(body)), Some(line)) = (
+                    head.get_mut("body"),
+                    record.get("body").and_then(JsonValue::as_str),
+                ) {
+                    body.push('\n');
+                    body.push_str(line);
+                    continue;
+                }
+            }
+            joined.push(record);
+        }
+        *records = joined;
+        before - records.len()
+    }
+}
+
+/// Whether `record` looks like a continuation line of `head`
+fn continues(head: &JsonValue, record: &JsonValue) -> bool {
+    let unset = |field: &str| match record.get(field) {
+        None | Some(JsonValue::Null) => true,
+        Some(JsonValue::Number(n)) => n.as_i64() == Some(0),
+        Some(JsonValue::String(s)) => s.is_empty(),
+        Some(_) => false,
+    };
+    unset("timestamp")
+        && unset("severity_number")
+        && unset("severity_text")
+        && SOURCE_FIELDS
+            .iter()
+            .all(|field| head.get(*field) == record.get(*field))
+}
+
+impl TransformStage for MultilineJoin {
+    fn name(&self) -> &'static str {
+        "multiline_join"
+    }
+
+    fn apply(&self, signal: Signal, records: &mut Vec<JsonValue>, counts: &mut TransformCounts) {
+        if signal == Signal::Logs {
+            counts.multiline_joined += MultilineJoin::apply(self, records);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn log(service: &str, timestamp: i64, severity: i64, body: &str) -> JsonValue {
+        json!({
+            "service_name": service,
+            "resource_attributes": "{}",
+            "scope_name": "",
+            "timestamp": timestamp,
+            "severity_number": severity,
+            "severity_text": if severity > 0 { "ERROR" } else { "" },
+            "body": body,
+        })
+    }
+
+    const JOIN: MultilineJoin = MultilineJoin { enabled: true };
+
+    #[test]
+    fn test_split_stack_trace_is_joined() {
+        let mut records = vec![
+            log("api", 1_000, 17, "Exception in thread main"),
+            log("api", 0, 0, "  at Foo.bar(Foo.java:10)"),
+            log("api", 0, 0, "  at Foo.main(Foo.java:3)"),
+            log("api", 2_000, 9, "next request"),
+        ];
+        assert_eq!(JOIN.apply(&mut records), 2);
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0]["body"],
+            "Exception in thread main\n  at Foo.bar(Foo.java:10)\n  at Foo.main(Foo.java:3)"
+        );
+        assert_eq!(records[0]["timestamp"], 1_000);
+        assert_eq!(records[1]["body"], "next request");
+    }
+
+    #[test]
+    fn test_independent_logs_stay_separate() {
+        let mut records = vec![
+            log("api", 1_000, 9, "one"),
+            log("api", 2_000, 0, "has a timestamp"),
+            log("worker", 0, 0, "other service"),
+            log("worker", 3_000, 9, "four"),
+        ];
+        let original = records.clone();
+        assert_eq!(JOIN.apply(&mut records), 0);
+        assert_eq!(records, original);
+
+        let mut split = vec![log("api", 1_000, 17, "a"), log("api", 0, 0, "b")];
+        assert_eq!(MultilineJoin::default().apply(&mut split), 0);
+        assert_eq!(split.len(), 2);
+    }
+}
@@ -0,0 +1,153 @@
+//! Support for newline-delimited plain-text log bodies.
+//!
+//! Appliances and syslog-style shippers can POST `text/plain` to `/v1/logs`
+//! without an OTLP SDK. Each non-empty line (LF or CRLF terminated) becomes
+//! one log record with the line as its body, ingest time as its timestamps
+//! and severity INFO (9). The body is rewritten into an OTLP JSON logs
+//! request before decoding, so it runs through the regular logs transform.
+
+use bytes::Bytes;
+use serde_json::{json, Value as JsonValue};
+
+use super::timestamp_bounds::current_time_micros;
+use super::HandleError;
+use crate::signal::Signal;
+use crate::InputFormat;
+
+/// Severity number for plain-text lines (INFO)
+const PLAIN_TEXT_SEVERITY_NUMBER: i64 = 9;
+
+/// Request body format: an OTLP encoding or plain-text log lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyFormat {
+    Otlp(InputFormat),
+    /// `text/plain`, one log record per line (logs only)
+    PlainText,
+}
+
+impl BodyFormat {
+    /// Detect the format from a Content-Type header, ignoring parameters
+    /// such as `charset`
+    pub fn from_content_type(content_type: Option<&str>) -> Self {
+        let essence = content_type
+            .and_then(|v| v.split(';').next())
+            .map(|v| v.trim().to_ascii_lowercase());
+        match essence.as_deref() {
+            Some("text/plain") => Self::PlainText,
+            _ => Self::Otlp(InputFormat::from_content_type(content_type)),
+        }
+    }
+}
+
+impl From<InputFormat> for BodyFormat {
+    fn from(format: InputFormat) -> Self {
+        Self::Otlp(format)
+    }
+}
+
+/// Rewrite a plain-text body into an OTLP JSON logs request; OTLP bodies
+/// pass through unchanged. Plain text is rejected for non-log signals.
+pub(crate) fn preprocess(
+    body: Bytes,
+    format: BodyFormat,
+    signal: Signal,
+) -> Result<(Bytes, InputFormat), HandleError> {
+    match format {
+        BodyFormat::Otlp(format) => Ok((body, format)),
+        BodyFormat::PlainText if signal == Signal::Logs => Ok((
+            to_logs_request(&body, current_time_micros().saturating_mul(1_000)),
+            InputFormat::Json,
+        )),
+        BodyFormat::PlainText => Err(HandleError::Decode(
+            "text/plain bodies are only accepted on /v1/logs".to_string(),
+        )),
+    }
+}
+
+/// Build an `ExportLogsServiceRequest` (OTLP JSON) with one record per
+/// non-empty line, stamped with `now_nanos`
+fn to_logs_request(body: &[u8], now_nanos: i64) -> Bytes {
+    let text = String::from_utf8_lossy(body);
+    let now = now_nanos.to_string();
+    let records: Vec<JsonValue> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            json!({
+                "timeUnixNano": now,
+                "observedTimeUnixNano": now,
+                "severityNumber": PLAIN_TEXT_SEVERITY_NUMBER,
+                "severityText": "INFO",
+                "body": {"stringValue": line},
+            })
+        })
+        .collect();
+    let request = json!({
+        "resourceLogs": [{"scopeLogs": [{"logRecords": records}]}]
+    });
+    Bytes::from(request.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::{HandlerConfig, LogsHandler, SignalHandler};
+
+    /// 2024-01-15T14:10:00Z
+    const NOW_NANOS: i64 = 1_705_327_800_000_000_000;
+
+    fn transform(text: &str) -> Vec<JsonValue> {
+        let body = to_logs_request(text.as_bytes(), NOW_NANOS);
+        let mut result =
+            LogsHandler::transform(body, InputFormat::Json, &HandlerConfig::default()).unwrap();
+        result.grouped.remove("logs").unwrap_or_default()
+    }
+
+    #[test]
+    fn test_each_line_becomes_a_log_record() {
+        let records = transform("first line\nsecond line\n");
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["body"], "first line");
+        assert_eq!(records[1]["body"], "second line");
+        assert_eq!(records[0]["severity_number"], 9);
+        assert_eq!(records[0]["timestamp"], NOW_NANOS / 1_000);
+    }
+
+    #[test]
+    fn test_crlf_and_empty_lines() {
+        let records = transform("one\r\n\r\n   \ntwo\r\n");
+
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["body"], "one");
+        assert_eq!(records[1]["body"], "two");
+        assert!(transform("").is_empty());
+        assert!(transform("\r\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_format_from_content_type() {
+        assert_eq!(
+            BodyFormat::from_content_type(Some("text/plain; charset=utf-8")),
+            BodyFormat::PlainText
+        );
+        assert_eq!(
+            BodyFormat::from_content_type(Some("application/json")),
+            BodyFormat::Otlp(InputFormat::Json)
+        );
+        assert_eq!(
+            BodyFormat::from_content_type(None),
+            BodyFormat::Otlp(InputFormat::Auto)
+        );
+    }
+
+    #[test]
+    fn test_plain_text_is_logs_only() {
+        let body = Bytes::from_static(b"cpu 0.5");
+        assert!(preprocess(body.clone(), BodyFormat::PlainText, Signal::Logs).is_ok());
+        assert!(matches!(
+            preprocess(body, BodyFormat::PlainText, Signal::Traces),
+            Err(HandleError::Decode(_))
+        ));
+    }
+}
@@ -0,0 +1,142 @@
+//! Dropping optional output columns before send.
+//!
+//! `DROP_COLUMNS` lists columns to remove, comma-separated. `table.column`
+//! drops a column from one table (e.g. `logs.scope_attributes`); a bare
+//! `column` drops it from every table. Required schema columns can't be
+//! dropped, so a projection never makes records fail validation.
+
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, HashSet};
+
+/// Columns removed from output records, per table and for all tables
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnProjection {
+    /// Dropped from every table
+    pub all_tables: HashSet<String>,
+    /// Table name -> columns dropped from that table only
+    pub per_table: HashMap<String, HashSet<String>>,
+}
+
+impl ColumnProjection {
+    pub fn is_empty(&self) -> bool {
+        self.all_tables.is_empty() && self.per_table.is_empty()
+    }
+
+    /// Remove the projected-out columns from every record, in place
+    pub fn apply(&self, grouped: &mut HashMap<String, Vec<JsonValue>>) {
+        if self.is_empty() {
+            return;
+        }
+        for (table, records) in grouped.iter_mut() {
+            let table_columns = self.per_table.get(table);
+            for obj in records.iter_mut().filter_map(JsonValue::as_object_mut) {
+                obj.retain(|column, _| {
+                    !self.all_tables.contains(column)
+                        && !table_columns.is_some_and(|c| c.contains(column))
+                });
+            }
+        }
+    }
+}
+
+/// Parse a `DROP_COLUMNS` value, rejecting required columns
+pub(crate) fn parse_projection(value: &str) -> Result<ColumnProjection, String> {
+    let mut projection = ColumnProjection::default();
+    for entry in value.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        match entry.split_once('.') {
+            Some((table, column)) => {
+                let (table, column) = (table.trim(), column.trim());
+                if table.is_empty() || column.is_empty() {
+                    return Err(format!("invalid column '{}': expected table.column", entry));
+                }
+                if is_required(table, column) {
+                    return Err(format!(
+                        "column '{}' is required in table '{}' and can't be dropped",
+                        column, table
+                    ));
+                }
+                projection
+                    .per_table
+                    .entry(table.to_string())
+                    .or_default()
+                    .insert(column.to_string());
+            }
+            None => {
+                if let Some(table) = required_in_any_table(entry) {
+                    return Err(format!(
+                        "column '{}' is required in table '{}' and can't be dropped",
+                        entry, table
+                    ));
+                }
+                projection.all_tables.insert(entry.to_string());
+            }
+        }
+    }
+    Ok(projection)
+}
+
+/// Schema name for a table; traces are stored under the `spans` schema
+fn schema_name(table: &str) -> &str {
+    match table {
+        "traces" => "spans",
+        other => other,
+    }
+}
+
+fn is_required(table: &str, column: &str) -> bool {
+    let generated = otlp2records::schema_def(schema_name(table))
+        .is_some_and(|def| def.fields.iter().any(|f| f.required && f.name == column));
+    let validated = crate::schema::get_schema(table)
+        .is_some_and(|s| s.required_fields.iter().any(|f| f.name == column));
+    generated || validated
+}
+
+fn required_in_any_table(column: &str) -> Option<&'static str> {
+    let tables = otlp2records::schema_defs()
+        .iter()
+        .map(|def| match def.name {
+            "spans" => "traces",
+            other => other,
+        });
+    tables
+        .chain(["gauge", "sum", "logs", "traces"])
+        .find(|table| is_required(table, column))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_projecting_out_optional_columns() {
+        let projection = parse_projection("logs.scope_attributes, scope_version").unwrap();
+        let mut grouped = HashMap::from([
+            (
+                "logs".to_string(),
+                vec![json!({"body": "x", "scope_attributes": "{}", "scope_version": "1"})],
+            ),
+            (
+                "traces".to_string(),
+                vec![json!({"span_name": "GET", "scope_attributes": "{}", "scope_version": "1"})],
+            ),
+        ]);
+        projection.apply(&mut grouped);
+
+        assert_eq!(grouped["logs"][0], json!({"body": "x"}));
+        assert_eq!(
+            grouped["traces"][0],
+            json!({"span_name": "GET", "scope_attributes": "{}"})
+        );
+    }
+
+    #[test]
+    fn test_required_columns_cannot_be_dropped() {
+        let err = parse_projection("logs.severity_number").unwrap_err();
+        assert!(err.contains("required"), "{}", err);
+        assert!(parse_projection("traces.span_name").is_err());
+        assert!(parse_projection("timestamp").is_err());
+        assert!(parse_projection("logs.").is_err());
+        assert!(parse_projection("").unwrap().is_empty());
+    }
+}
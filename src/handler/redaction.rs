@@ -0,0 +1,317 @@
+use regex::Regex;
+use serde_json::Value as JsonValue;
+use std::borrow::Cow;
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+use super::stages::TransformStage;
+use super::TransformCounts;
+use crate::signal::Signal;
+
+/// Replacement text for redacted matches
+const REDACTED: &str = "[REDACTED]";
+
+/// Built-in rules selectable by name via `REDACT_RULES`
+const BUILTIN_RULES: &[(&str, &str)] = &[
+    ("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}"),
+    ("credit_card", r"\b(?:\d[ -]?){12,18}\d\b"),
+    ("bearer_token", r"(?i)\bbearer\s+[A-Za-z0-9._~+/-]+=*"),
+];
+
+/// Log columns that are redacted: the body plus attribute blobs
+const LOG_FIELDS: &[&str] = &[
+    "body",
+    "log_attributes",
+    "resource_attributes",
+    "scope_attributes",
+];
+
+/// Span columns that are redacted: attribute blobs
+const TRACE_FIELDS: &[&str] = &["span_attributes", "resource_attributes", "scope_attributes"];
+
+/// Raw `REDACT_*` values a `RedactionConfig` was built from
+#[derive(Debug, PartialEq)]
+struct RedactionVars {
+    rules: Option<String>,
+    patterns: Option<String>,
+    logs: Option<String>,
+    traces: Option<String>,
+}
+
+/// Regex-based PII redaction applied to transformed logs and traces
+#[derive(Debug, Clone)]
+pub struct RedactionConfig {
+    /// Patterns whose matches are replaced with `[REDACTED]`
+    pub patterns: Vec<Regex>,
+    /// Redact log bodies and attributes
+    pub logs: bool,
+    /// Redact span attributes
+    pub traces: bool,
+}
+
+impl Default for RedactionConfig {
+    fn default() -> Self {
+        Self {
+            patterns: Vec::new(),
+            logs: true,
+            traces: true,
+        }
+    }
+}
+
+impl PartialEq for RedactionConfig {
+    fn eq(&self, other: &Self) -> bool {
+        self.logs == other.logs
+            && self.traces == other.traces
+            && self
+                .patterns
+                .iter()
+                .map(Regex::as_str)
+                .eq(other.patterns.iter().map(Regex::as_str))
+    }
+}
+
+impl RedactionConfig {
+    /// Build from `REDACT_RULES` (built-in names, comma-separated),
+    /// `REDACT_PATTERNS` (JSON array of regexes), and the `REDACT_LOGS` /
+    /// `REDACT_TRACES` switches. Unknown rules and invalid patterns are skipped.
+    ///
+    /// The worker reads config on every request, so the compiled config is
+    /// cached per process (or isolate) and rebuilt only when the values change.
+    pub fn from_lookup(mut var: impl FnMut(&str) -> Option<String>) -> Self {
+        static CACHE: OnceLock<Mutex<Option<(RedactionVars, RedactionConfig)>>> = OnceLock::new();
+        let vars = RedactionVars {
+            rules: var("REDACT_RULES"),
+            patterns: var("REDACT_PATTERNS"),
+            logs: var("REDACT_LOGS"),
+            traces: var("REDACT_TRACES"),
+        };
+        let mut cache = CACHE
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match cache.as_ref() {
+            Some((cached, config)) if *cached == vars => config.clone(),
+            _ => {
+                let config = Self::build(&vars);
+                *cache = Some((vars, config.clone()));
+                config
+            }
+        }
+    }
+
+    fn build(vars: &RedactionVars) -> Self {
+        let mut patterns = Vec::new();
+
+        for name in vars.rules.as_deref().unwrap_or_default().split(',') {
+            let name = name.trim();
+            if name.is_empty() {
+                continue;
+            }
+            match BUILTIN_RULES.iter().find(|(rule, _)| *rule == name) {
+                Some((_, pattern)) => patterns.push(compile(pattern)),
+                None => warn!(rule = name, "unknown redaction rule"),
+            }
+        }
+
+        if let Some(custom) = &vars.patterns {
+            match serde_json::from_str::<Vec<String>>(custom) {
+                Ok(custom) => patterns.extend(custom.iter().filter_map(|p| {
+                    Regex::new(p)
+                        .map_err(|e| warn!(pattern = %p, error = %e, "invalid redaction pattern"))
+                        .ok()
+                })),
+                Err(e) => warn!(error = %e, "REDACT_PATTERNS must be a JSON array of strings"),
+            }
+        }
+
+        let defaults = Self::default();
+        Self {
+            patterns,
+            logs: parse_switch(vars.logs.clone(), defaults.logs),
+            traces: parse_switch(vars.traces.clone(), defaults.traces),
+        }
+    }
+
+    /// Redact configured fields for a signal in place, returning how many changed
+    pub(crate) fn apply(&self, signal: Signal, records: &mut [JsonValue]) -> usize {
+        let fields = match signal {
+            Signal::Logs if self.logs => LOG_FIELDS,
+            Signal::Traces if self.traces => TRACE_FIELDS,
+            _ => return 0,
+        };
+        if self.patterns.is_empty() {
+            return 0;
+        }
+
+        let mut redacted_fields = 0;
+        for record in records {
+            for field in fields {
+                if let Some(JsonValue::String(value)) = record.get_mut(*field) {
+                    if let Some(redacted) = self.redact_field(value) {
+                        *value = redacted;
+                        redacted_fields += 1;
+                    }
+                }
+            }
+        }
+        redacted_fields
+    }
+
+    /// Redact a column value. Attribute blobs are JSON strings, so their string
+    /// values are redacted individually to keep the blob valid JSON.
+    fn redact_field(&self, value: &str) -> Option<String> {
+        match serde_json::from_str::<JsonValue>(value) {
+            Ok(mut json @ (JsonValue::Object(_) | JsonValue::Array(_))) => {
+                self.redact_json(&mut json).then(|| json.to_string())
+            }
+            _ => self.redact_str(value),
+        }
+    }
+
+    fn redact_json(&self, value: &mut JsonValue) -> bool {
+        match value {
+            JsonValue::String(s) => match self.redact_str(s) {
+                Some(redacted) => {
+                    *s = redacted;
+                    true
+                }
+                None => false,
+            },
+            JsonValue::Array(items) => {
+                let mut changed = false;
+                for item in items {
+                    changed |= self.redact_json(item);
+                }
+                changed
+            }
+            JsonValue::Object(map) => {
+                let mut changed = false;
+                for item in map.values_mut() {
+                    changed |= self.redact_json(item);
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    fn redact_str(&self, value: &str) -> Option<String> {
+        let mut current = Cow::Borrowed(value);
+        for pattern in &self.patterns {
+            if let Cow::Owned(replaced) = pattern.replace_all(&current, REDACTED) {
+                current = Cow::Owned(replaced);
+            }
+        }
+        match current {
+            Cow::Owned(redacted) => Some(redacted),
+            Cow::Borrowed(_) => None,
+        }
+    }
+}
+
+fn compile(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("built-in redaction pattern is valid")
+}
+
+fn parse_switch(value: Option<String>, default: bool) -> bool {
+    match value.as_deref().map(str::trim) {
+        Some(v) if v.eq_ignore_ascii_case("false") || v == "0" => false,
+        Some(v) if v.eq_ignore_ascii_case("true") || v == "1" => true,
+        _ => default,
+    }
+}
+
+impl TransformStage for RedactionConfig {
+    fn name(&self) -> &'static str {
+        "redaction"
+    }
+
+    fn apply(&self, signal: Signal, records: &mut Vec<JsonValue>, counts: &mut TransformCounts) {
+        counts.redacted += RedactionConfig::apply(self, signal, records);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn config(rules: &str) -> RedactionConfig {
+        let rules = rules.to_string();
+        RedactionConfig::from_lookup(move |name| (name == "REDACT_RULES").then(|| rules.clone()))
+    }
+
+    #[test]
+    fn test_redacts_email_in_body_and_attributes() {
+        let mut records = vec![json!({
+            "body": "login failed for alice@example.com",
+            "log_attributes": r#"{"user.email":"bob@example.org","http.method":"GET"}"#,
+        })];
+        assert_eq!(config("email").apply(Signal::Logs, &mut records), 2);
+
+        assert_eq!(records[0]["body"], "login failed for [REDACTED]");
+        let attrs: JsonValue =
+            serde_json::from_str(records[0]["log_attributes"].as_str().unwrap()).unwrap();
+        assert_eq!(attrs["user.email"], REDACTED);
+        assert_eq!(attrs["http.method"], "GET");
+    }
+
+    #[test]
+    fn test_redacts_credit_card_numbers() {
+        let mut records = vec![json!({
+            "span_attributes": r#"{"card":"4111 1111 1111 1111","order":"12345"}"#,
+        })];
+        config("credit_card").apply(Signal::Traces, &mut records);
+
+        let attrs: JsonValue =
+            serde_json::from_str(records[0]["span_attributes"].as_str().unwrap()).unwrap();
+        assert_eq!(attrs["card"], REDACTED);
+        assert_eq!(attrs["order"], "12345");
+    }
+
+    #[test]
+    fn test_non_matching_text_untouched() {
+        let original = json!({
+            "body": "user logged in",
+            "log_attributes": r#"{"b":"2","a":"1"}"#,
+        });
+        let mut records = vec![original.clone()];
+        config("email,credit_card").apply(Signal::Logs, &mut records);
+        assert_eq!(records[0], original);
+    }
+
+    #[test]
+    fn test_signal_switch_disables_redaction() {
+        let mut config = config("email");
+        config.logs = false;
+        let mut records = vec![json!({"body": "alice@example.com"})];
+        config.apply(Signal::Logs, &mut records);
+        assert_eq!(records[0]["body"], "alice@example.com");
+    }
+
+    #[test]
+    fn test_cached_config_follows_changed_values() {
+        let email = config("email");
+        assert_eq!(config("email"), email);
+        assert_ne!(config("credit_card"), email);
+        assert_eq!(config("email"), email);
+    }
+
+    #[test]
+    fn test_from_lookup_parses_custom_patterns() {
+        let config = RedactionConfig::from_lookup(|name| match name {
+            "REDACT_RULES" => Some("email, unknown".to_string()),
+            "REDACT_PATTERNS" => Some(r#"["secret-\\d+", "("]"#.to_string()),
+            "REDACT_TRACES" => Some("false".to_string()),
+            _ => None,
+        });
+        assert_eq!(config.patterns.len(), 2);
+        assert!(config.logs);
+        assert!(!config.traces);
+        assert_eq!(
+            config.redact_str("key secret-42").as_deref(),
+            Some("key [REDACTED]")
+        );
+    }
+}
@@ -0,0 +1,290 @@
+use std::collections::HashMap;
+
+#[derive(Debug, serde::Serialize)]
+pub struct HandleResponse {
+    pub status: &'static str,
+    pub records: HashMap<String, usize>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub errors: HashMap<String, String>,
+    /// Transform warnings; only kept for verbose requests
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<ResponseWarning>,
+    #[serde(skip)]
+    pub service_names: Vec<String>,
+    #[serde(skip)]
+    pub metric_names: Vec<(String, String)>,
+}
+
+/// Warning info for skipped metrics
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SkippedMetricsWarning {
+    pub message: &'static str,
+    pub skipped_total: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub histograms: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub exponential_histograms: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub summaries: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub nan_values: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub infinity_values: usize,
+    #[serde(skip_serializing_if = "is_zero")]
+    pub missing_values: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+impl HandleResponse {
+    pub fn empty() -> Self {
+        Self {
+            status: "ok",
+            records: HashMap::new(),
+            errors: HashMap::new(),
+            warnings: Vec::new(),
+            service_names: Vec::new(),
+            metric_names: Vec::new(),
+        }
+    }
+
+    pub fn from_result(result: crate::pipeline::SendResult) -> Self {
+        let accepted = result.succeeded.values().any(|&count| count > 0);
+        let status = if result.failed.is_empty() && result.rejected.is_empty() {
+            "ok"
+        } else if !accepted {
+            "error"
+        } else {
+            "partial"
+        };
+
+        let mut errors: HashMap<String, String> = result
+            .failed
+            .into_iter()
+            .map(|(table, failure)| (table, failure.message))
+            .collect();
+        for (table, count) in result.rejected {
+            let reasons = result
+                .rejection_reasons
+                .get(&table)
+                .cloned()
+                .unwrap_or_default();
+            let message = format!("{} record(s) rejected: {}", count, reasons.join("; "));
+            errors
+                .entry(table)
+                .and_modify(|failure| *failure = format!("{}; {}", message, failure))
+                .or_insert(message);
+        }
+
+        Self {
+            status,
+            records: result.succeeded,
+            errors,
+            warnings: Vec::new(),
+            service_names: Vec::new(),
+            metric_names: Vec::new(),
+        }
+    }
+
+    pub fn with_service_names(mut self, service_names: Vec<String>) -> Self {
+        self.service_names = service_names;
+        self
+    }
+
+    pub fn with_metric_names(mut self, metric_names: Vec<(String, String)>) -> Self {
+        self.metric_names = metric_names;
+        self
+    }
+
+    pub fn with_warnings(mut self, warnings: Vec<ResponseWarning>) -> Self {
+        self.warnings = warnings;
+        self
+    }
+
+    /// Whether to answer `204 No Content`: nothing was accepted or rejected
+    /// and the config asks for 204 on empty requests
+    pub fn is_no_content(&self, config: &super::HandlerConfig) -> bool {
+        config.empty_response_status == 204 && self.records.is_empty() && self.errors.is_empty()
+    }
+
+    /// Drop warnings unless the caller opted in with `?verbose=1`
+    pub fn for_verbosity(mut self, verbose: bool) -> Self {
+        if !verbose {
+            self.warnings.clear();
+        }
+        self
+    }
+}
+
+/// Records altered during transform, beyond skipped metrics
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TransformCounts {
+    /// Span events and links dropped by the configured limits
+    pub truncated: usize,
+    /// Fields rewritten by PII redaction
+    pub redacted: usize,
+    /// Records with a timestamp outside the configured bounds (counted, clamped or dropped)
+    pub out_of_bounds_timestamps: usize,
+    /// Logs dropped for falling below their service's severity threshold
+    pub severity_filtered: usize,
+    /// Records dropped because their service exceeded its rate limit
+    pub rate_limited: usize,
+    /// Metric points whose aggregation temporality differs from earlier points
+    pub temporality_switches: usize,
+    /// Continuation log records merged into the record before them
+    pub multiline_joined: usize,
+}
+
+/// A decode/transform issue summarized for the caller in verbose mode
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ResponseWarning {
+    pub kind: &'static str,
+    pub count: usize,
+    pub message: &'static str,
+}
+
+/// Summarize skipped metrics and nonzero transform counts as response warnings
+pub(crate) fn collect_warnings(
+    skipped: Option<&SkippedMetricsWarning>,
+    counts: TransformCounts,
+) -> Vec<ResponseWarning> {
+    let candidates = [
+        (
+            "skipped_metrics",
+            skipped.map_or(0, |s| s.skipped_total),
+            "unsupported or invalid metric data points were skipped",
+        ),
+        (
+            "truncated",
+            counts.truncated,
+            "span events or links beyond the configured limits were dropped",
+        ),
+        ("redacted", counts.redacted, "fields had PII redacted"),
+        (
+            "out_of_bounds_timestamps",
+            counts.out_of_bounds_timestamps,
+            "records had timestamps outside the accepted window",
+        ),
+        (
+            "severity_filtered",
+            counts.severity_filtered,
+            "logs below the configured severity threshold were dropped",
+        ),
+        (
+            "rate_limited",
+            counts.rate_limited,
+            "records from services over their rate limit were dropped",
+        ),
+        (
+            "multiline_joined",
+            counts.multiline_joined,
+            "continuation log lines were joined into the preceding record",
+        ),
+        (
+            "temporality_switch",
+            counts.temporality_switches,
+            "metrics switched between delta and cumulative temporality",
+        ),
+    ];
+    candidates
+        .into_iter()
+        .filter(|(_, count, _)| *count > 0)
+        .map(|(kind, count, message)| ResponseWarning {
+            kind,
+            count,
+            message,
+        })
+        .collect()
+}
+
+/// Whether a request query string opts into verbose responses (`verbose=1` or `verbose=true`)
+pub fn verbose_requested(query: Option<&str>) -> bool {
+    query.is_some_and(|query| {
+        query.split('&').any(|pair| {
+            matches!(
+                pair.split_once('=').unwrap_or((pair, "1")),
+                ("verbose", "1") | ("verbose", "true")
+            )
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn skipped(total: usize) -> SkippedMetricsWarning {
+        SkippedMetricsWarning {
+            message: "some metrics were skipped",
+            skipped_total: total,
+            histograms: 0,
+            exponential_histograms: 0,
+            summaries: 0,
+            nan_values: total,
+            infinity_values: 0,
+            missing_values: 0,
+        }
+    }
+
+    #[test]
+    fn test_collect_warnings_only_reports_nonzero_counts() {
+        let counts = TransformCounts {
+            redacted: 3,
+            ..TransformCounts::default()
+        };
+        let warnings = collect_warnings(Some(&skipped(2)), counts);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings[0].kind, "skipped_metrics");
+        assert_eq!(warnings[0].count, 2);
+        assert_eq!(warnings[1].kind, "redacted");
+        assert_eq!(warnings[1].count, 3);
+
+        assert!(collect_warnings(None, TransformCounts::default()).is_empty());
+    }
+
+    #[test]
+    fn test_warnings_hidden_unless_verbose() {
+        let warnings = collect_warnings(Some(&skipped(1)), TransformCounts::default());
+        let response = HandleResponse::empty().with_warnings(warnings);
+
+        let json = serde_json::to_value(response.for_verbosity(false)).unwrap();
+        assert!(json.get("warnings").is_none());
+
+        let warnings = collect_warnings(Some(&skipped(1)), TransformCounts::default());
+        let response = HandleResponse::empty().with_warnings(warnings);
+        let json = serde_json::to_value(response.for_verbosity(true)).unwrap();
+        assert_eq!(json["warnings"][0]["count"], 1);
+    }
+
+    #[test]
+    fn test_verbose_requested() {
+        assert!(verbose_requested(Some("verbose=1")));
+        assert!(verbose_requested(Some("a=b&verbose=true")));
+        assert!(verbose_requested(Some("verbose")));
+        assert!(!verbose_requested(Some("verbose=0")));
+        assert!(!verbose_requested(Some("noverbose=1")));
+        assert!(!verbose_requested(None));
+    }
+
+    #[test]
+    fn test_rejections_make_response_partial() {
+        let mut result = crate::pipeline::SendResult::default();
+        result.succeeded.insert("traces".to_string(), 9);
+        result.add_rejections("traces", vec!["record 4 (traces): bad".to_string()]);
+
+        let response = HandleResponse::from_result(result);
+        assert_eq!(response.status, "partial");
+        assert_eq!(response.records["traces"], 9);
+        assert_eq!(
+            response.errors["traces"],
+            "1 record(s) rejected: record 4 (traces): bad"
+        );
+
+        let mut result = crate::pipeline::SendResult::default();
+        result.succeeded.insert("traces".to_string(), 0);
+        result.add_rejections("traces", vec!["bad".to_string()]);
+        assert_eq!(HandleResponse::from_result(result).status, "error");
+    }
+}
@@ -0,0 +1,44 @@
+//! `is_root` column for spans, so root-span filters avoid string checks.
+
+use serde_json::Value as JsonValue;
+
+/// Column added to span records
+pub(crate) const IS_ROOT_FIELD: &str = "is_root";
+
+/// Set `is_root` on each span: true when `parent_span_id` is missing, empty,
+/// or all zeros (how some SDKs encode "no parent").
+pub(crate) fn mark_root_spans(records: &mut [JsonValue]) {
+    for record in records.iter_mut() {
+        let is_root = record
+            .get("parent_span_id")
+            .and_then(JsonValue::as_str)
+            .is_none_or(|parent| parent.bytes().all(|b| b == b'0'));
+        if let Some(obj) = record.as_object_mut() {
+            obj.insert(IS_ROOT_FIELD.to_string(), JsonValue::Bool(is_root));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_root_and_child_spans() {
+        let mut records = vec![
+            json!({"span_id": "a", "parent_span_id": ""}),
+            json!({"span_id": "b", "parent_span_id": "0000000000000000"}),
+            json!({"span_id": "c"}),
+            json!({"span_id": "d", "parent_span_id": "b7ad6b7169203331"}),
+            json!({"span_id": "e", "parent_span_id": "0000000000000001"}),
+        ];
+        mark_root_spans(&mut records);
+
+        let roots: Vec<bool> = records
+            .iter()
+            .map(|r| r[IS_ROOT_FIELD].as_bool().unwrap())
+            .collect();
+        assert_eq!(roots, vec![true, true, true, false, false]);
+    }
+}
@@ -0,0 +1,126 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use crate::signal::Signal;
+
+/// Metric tables eligible for scope-based routing
+const ROUTABLE_SIGNALS: &[Signal] = &[
+    Signal::Gauge,
+    Signal::Sum,
+    Signal::Histogram,
+    Signal::ExpHistogram,
+];
+
+/// Table name for a metric table routed by scope suffix
+pub fn routed_table_name(base: &str, suffix: &str) -> String {
+    format!("{}_{}", base, suffix)
+}
+
+/// All routed table names for the configured scope routes
+pub fn routed_table_names(routes: &HashMap<String, String>) -> Vec<String> {
+    let mut names: Vec<String> = ROUTABLE_SIGNALS
+        .iter()
+        .flat_map(|signal| {
+            routes
+                .values()
+                .map(move |suffix| routed_table_name(signal.table_name(), suffix))
+        })
+        .collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// Move metric records whose `scope_name` matches a route into `{table}_{suffix}`.
+/// Records from other scopes stay in their base table.
+pub(crate) fn route_by_scope(
+    grouped: &mut HashMap<String, Vec<JsonValue>>,
+    routes: &HashMap<String, String>,
+) {
+    if routes.is_empty() {
+        return;
+    }
+
+    for signal in ROUTABLE_SIGNALS {
+        let base = signal.table_name();
+        let Some(records) = grouped.remove(base) else {
+            continue;
+        };
+
+        let mut kept = Vec::new();
+        for record in records {
+            let suffix = record
+                .get("scope_name")
+                .and_then(|v| v.as_str())
+                .and_then(|scope| routes.get(scope));
+            match suffix {
+                Some(suffix) => grouped
+                    .entry(routed_table_name(base, suffix))
+                    .or_default()
+                    .push(record),
+                None => kept.push(record),
+            }
+        }
+
+        if !kept.is_empty() {
+            grouped.insert(base.to_string(), kept);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn routes() -> HashMap<String, String> {
+        HashMap::from([("runtime".to_string(), "runtime".to_string())])
+    }
+
+    #[test]
+    fn test_routes_matching_scope_to_custom_table() {
+        let mut grouped = HashMap::from([
+            (
+                "gauge".to_string(),
+                vec![
+                    json!({"metric_name": "heap", "scope_name": "runtime"}),
+                    json!({"metric_name": "requests", "scope_name": "http"}),
+                ],
+            ),
+            (
+                "sum".to_string(),
+                vec![json!({"metric_name": "gc.count", "scope_name": "runtime"})],
+            ),
+        ]);
+        route_by_scope(&mut grouped, &routes());
+
+        assert_eq!(grouped["gauge_runtime"][0]["metric_name"], "heap");
+        assert_eq!(grouped["gauge"].len(), 1);
+        assert_eq!(grouped["gauge"][0]["metric_name"], "requests");
+        assert_eq!(grouped["sum_runtime"][0]["metric_name"], "gc.count");
+        // Base table emptied by routing is removed rather than left empty
+        assert!(!grouped.contains_key("sum"));
+    }
+
+    #[test]
+    fn test_no_routes_leaves_tables_untouched() {
+        let mut grouped =
+            HashMap::from([("gauge".to_string(), vec![json!({"scope_name": "runtime"})])]);
+        route_by_scope(&mut grouped, &HashMap::new());
+        assert_eq!(grouped.len(), 1);
+        assert_eq!(grouped["gauge"].len(), 1);
+    }
+
+    #[test]
+    fn test_routed_table_names() {
+        assert_eq!(
+            routed_table_names(&routes()),
+            vec![
+                "exp_histogram_runtime",
+                "gauge_runtime",
+                "histogram_runtime",
+                "sum_runtime"
+            ]
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Stable `series_id` for gauge and sum points.
+//!
+//! The ID is an FNV-1a 64-bit hash (16 hex chars) of the metric name plus the
+//! resource and metric attributes with object keys sorted, so attribute order
+//! on the wire does not change it.
+
+use serde_json::Value as JsonValue;
+
+/// Column added to gauge and sum records
+pub(crate) const SERIES_ID_FIELD: &str = "series_id";
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Set `series_id` on each record
+pub(crate) fn assign_series_ids(records: &mut [JsonValue]) {
+    for record in records.iter_mut() {
+        let id = series_id(record);
+        if let Some(obj) = record.as_object_mut() {
+            obj.insert(SERIES_ID_FIELD.to_string(), JsonValue::String(id));
+        }
+    }
+}
+
+/// Hash of `metric_name`, `resource_attributes` and `metric_attributes`
+fn series_id(record: &JsonValue) -> String {
+    fingerprint(
+        record,
+        &["metric_name", "resource_attributes", "metric_attributes"],
+    )
+}
+
+/// FNV-1a hash (16 hex chars) of the canonical form of `fields` in `record`.
+/// Missing fields hash as null.
+pub(crate) fn fingerprint<S: AsRef<str>>(record: &JsonValue, fields: &[S]) -> String {
+    let mut canonical = String::new();
+    for field in fields {
+        write_canonical(&attributes(record.get(field.as_ref())), &mut canonical);
+        canonical.push('\n');
+    }
+    format!("{:016x}", fnv1a(canonical.as_bytes()))
+}
+
+/// Attribute columns may hold a JSON object or its serialized string
+pub(crate) fn attributes(value: Option<&JsonValue>) -> JsonValue {
+    match value {
+        Some(JsonValue::String(s)) if s.starts_with('{') => {
+            serde_json::from_str(s).unwrap_or_else(|_| JsonValue::String(s.clone()))
+        }
+        Some(value) => value.clone(),
+        None => JsonValue::Null,
+    }
+}
+
+/// Serialize `value` with object keys sorted at every level
+fn write_canonical(value: &JsonValue, out: &mut String) {
+    match value {
+        JsonValue::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&JsonValue::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        JsonValue::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        other => out.push_str(&other.to_string()),
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(FNV_OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn point(metric_attributes: JsonValue) -> JsonValue {
+        json!({
+            "metric_name": "http.requests",
+            "resource_attributes": {"service.name": "api"},
+            "metric_attributes": metric_attributes,
+            "value": 1.0
+        })
+    }
+
+    #[test]
+    fn test_attribute_order_does_not_change_series_id() {
+        let mut records = vec![
+            point(json!({"method": "GET", "route": "/users", "status": 200})),
+            point(json!(
+                "{\"status\":200,\"route\":\"/users\",\"method\":\"GET\"}"
+            )),
+        ];
+        assign_series_ids(&mut records);
+
+        let id = records[0][SERIES_ID_FIELD].as_str().unwrap();
+        assert_eq!(id.len(), 16);
+        assert_eq!(records[1][SERIES_ID_FIELD], id);
+    }
+
+    #[test]
+    fn test_different_attributes_change_series_id() {
+        let mut records = vec![
+            point(json!({"method": "GET"})),
+            point(json!({"method": "POST"})),
+            json!({"metric_name": "http.errors", "metric_attributes": {"method": "GET"}}),
+        ];
+        assign_series_ids(&mut records);
+
+        assert_ne!(records[0][SERIES_ID_FIELD], records[1][SERIES_ID_FIELD]);
+        assert_ne!(records[0][SERIES_ID_FIELD], records[2][SERIES_ID_FIELD]);
+    }
+
+    #[test]
+    fn test_fnv1a_reference_values() {
+        assert_eq!(fnv1a(b""), FNV_OFFSET_BASIS);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
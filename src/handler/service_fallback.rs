@@ -0,0 +1,138 @@
+//! Fallback `service_name` for resources without `service.name`.
+//!
+//! `service.name` is required by the OTel spec, but misconfigured apps often
+//! omit it and all land in `unknown`. With `SERVICE_NAME_FALLBACK_ATTRIBUTES`
+//! (comma-separated resource attributes, e.g.
+//! `k8s.deployment.name,telemetry.sdk.language`), such records take their
+//! `service_name` from the first attribute present, and only fall back to
+//! `unknown` when none is.
+
+use serde_json::Value as JsonValue;
+
+use super::series_id::attributes;
+use super::stages::TransformStage;
+use super::TransformCounts;
+use crate::signal::Signal;
+
+/// `service_name` for records with no usable resource attribute
+pub const UNKNOWN_SERVICE: &str = "unknown";
+
+/// Resource attributes tried, in order, when `service.name` is missing
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ServiceNameFallback {
+    pub attributes: Vec<String>,
+}
+
+impl ServiceNameFallback {
+    /// Parse `SERVICE_NAME_FALLBACK_ATTRIBUTES`; empty entries are skipped
+    pub fn parse(value: &str) -> Self {
+        Self {
+            attributes: value
+                .split(',')
+                .map(str::trim)
+                .filter(|attr| !attr.is_empty())
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+
+    /// Set `service_name` from the fallback attributes on records whose
+    /// resource has no `service.name`, returning how many were renamed
+    pub(crate) fn apply(&self, records: &mut [JsonValue]) -> usize {
+        if self.attributes.is_empty() {
+            return 0;
+        }
+        let mut renamed = 0;
+        for record in records.iter_mut() {
+            let resource = attributes(record.get("resource_attributes"));
+            if resource.get("service.name").is_some_and(has_value) {
+                continue;
+            }
+            let fallback = self
+                .attributes
+                .iter()
+                .find_map(|attr| resource.get(attr).filter(|v| has_value(v)))
+                .map(|value| match value {
+                    JsonValue::String(s) => s.clone(),
+                    other => other.to_string(),
+                });
+            renamed += usize::from(fallback.is_some());
+            if let Some(obj) = record.as_object_mut() {
+                let name = fallback.unwrap_or_else(|| UNKNOWN_SERVICE.to_string());
+                obj.insert("service_name".to_string(), JsonValue::String(name));
+            }
+        }
+        renamed
+    }
+}
+
+fn has_value(value: &JsonValue) -> bool {
+    !matches!(value, JsonValue::Null) && value.as_str() != Some("")
+}
+
+impl TransformStage for ServiceNameFallback {
+    fn name(&self) -> &'static str {
+        "service_name_fallback"
+    }
+
+    fn apply(&self, _: Signal, records: &mut Vec<JsonValue>, _: &mut TransformCounts) {
+        ServiceNameFallback::apply(self, records);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn record(resource: JsonValue) -> JsonValue {
+        json!({
+            "service_name": "unknown",
+            "resource_attributes": resource.to_string(),
+        })
+    }
+
+    #[test]
+    fn test_first_present_fallback_attribute_wins() {
+        let fallback = ServiceNameFallback::parse("k8s.deployment.name, ,telemetry.sdk.language");
+        let mut records = vec![
+            record(json!({"telemetry.sdk.language": "go"})),
+            record(json!({"k8s.deployment.name": "checkout", "telemetry.sdk.language": "go"})),
+            record(json!({"k8s.deployment.name": "", "telemetry.sdk.language": "java"})),
+        ];
+
+        assert_eq!(fallback.apply(&mut records), 3);
+        assert_eq!(records[0]["service_name"], "go");
+        assert_eq!(records[1]["service_name"], "checkout");
+        assert_eq!(records[2]["service_name"], "java");
+    }
+
+    #[test]
+    fn test_unknown_when_no_attribute_matches() {
+        let fallback = ServiceNameFallback::parse("k8s.deployment.name");
+        let mut records = vec![
+            record(json!({"host.name": "web-1"})),
+            json!({"body": "no resource at all"}),
+        ];
+
+        assert_eq!(fallback.apply(&mut records), 0);
+        assert_eq!(records[0]["service_name"], UNKNOWN_SERVICE);
+        assert_eq!(records[1]["service_name"], UNKNOWN_SERVICE);
+    }
+
+    #[test]
+    fn test_real_service_name_and_disabled_untouched() {
+        let fallback = ServiceNameFallback::parse("k8s.deployment.name");
+        let named = json!({
+            "service_name": "unknown",
+            "resource_attributes": {"service.name": "unknown", "k8s.deployment.name": "api"},
+        });
+        let mut records = vec![named.clone()];
+        assert_eq!(fallback.apply(&mut records), 0);
+        assert_eq!(records[0], named);
+
+        let mut records = vec![record(json!({"k8s.deployment.name": "api"}))];
+        assert_eq!(ServiceNameFallback::default().apply(&mut records), 0);
+        assert_eq!(records[0]["service_name"], "unknown");
+    }
+}
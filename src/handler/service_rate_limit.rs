@@ -0,0 +1,252 @@
+//! Per-service record rate limits.
+//!
+//! A global limit can't keep one noisy service from starving the rest. With
+//! `SERVICE_RATE_LIMIT` (records per window, per `service_name`) and optional
+//! `SERVICE_RATE_LIMIT_OVERRIDES` (`service=N` pairs), each service gets its
+//! own fixed window of `SERVICE_RATE_LIMIT_WINDOW_SECS` (default 60). Once a
+//! service uses up its window, its further records are dropped and counted
+//! until the next window; other services are unaffected. Counts live in the
+//! process (or isolate), so limits apply per instance.
+
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use tracing::{debug, warn};
+
+use super::stages::TransformStage;
+use super::timestamp_bounds::current_time_micros;
+use super::TransformCounts;
+use crate::signal::Signal;
+
+/// Default rate limit window in seconds
+pub const DEFAULT_RATE_LIMIT_WINDOW_SECS: u64 = 60;
+
+/// Services tracked before expired windows are pruned
+const MAX_TRACKED_SERVICES: usize = 10_000;
+
+/// Records allowed per service per window; a limit of 0 means unlimited
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceRateLimit {
+    /// Limit for services without an override; 0 disables limiting
+    pub default_limit: u64,
+    /// `service_name` -> limit, overriding `default_limit` (0 = unlimited)
+    pub service_limits: HashMap<String, u64>,
+    /// Window length in seconds
+    pub window_secs: u64,
+}
+
+impl Default for ServiceRateLimit {
+    fn default() -> Self {
+        Self {
+            default_limit: 0,
+            service_limits: HashMap::new(),
+            window_secs: DEFAULT_RATE_LIMIT_WINDOW_SECS,
+        }
+    }
+}
+
+/// Records admitted for one service in its current window
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    start_secs: u64,
+    admitted: u64,
+}
+
+/// Per-service windows shared across requests
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    windows: HashMap<String, Window>,
+}
+
+impl ServiceRateLimit {
+    /// Build from `SERVICE_RATE_LIMIT`, `SERVICE_RATE_LIMIT_OVERRIDES` and
+    /// `SERVICE_RATE_LIMIT_WINDOW_SECS`. Unparseable entries are skipped with a warning.
+    pub fn from_lookup(mut var: impl FnMut(&str) -> Option<String>) -> Self {
+        let defaults = Self::default();
+        let default_limit = var("SERVICE_RATE_LIMIT")
+            .and_then(|v| parse_limit_or_warn(&v))
+            .unwrap_or(defaults.default_limit);
+        let service_limits = var("SERVICE_RATE_LIMIT_OVERRIDES")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|entry| {
+                let (service, limit) = entry.split_once('=')?;
+                let service = service.trim();
+                let limit = parse_limit_or_warn(limit)?;
+                (!service.is_empty()).then(|| (service.to_string(), limit))
+            })
+            .collect();
+        let window_secs = var("SERVICE_RATE_LIMIT_WINDOW_SECS")
+            .and_then(|v| v.trim().parse().ok())
+            .filter(|&secs| secs > 0)
+            .unwrap_or(defaults.window_secs);
+        Self {
+            default_limit,
+            service_limits,
+            window_secs,
+        }
+    }
+
+    fn enabled(&self) -> bool {
+        self.default_limit > 0 || self.service_limits.values().any(|&limit| limit > 0)
+    }
+
+    fn limit_for(&self, service: &str) -> u64 {
+        self.service_limits
+            .get(service)
+            .copied()
+            .unwrap_or(self.default_limit)
+    }
+
+    /// Drop records of services over their limit, using the process-wide
+    /// limiter; returns how many were dropped
+    pub(crate) fn apply(&self, records: &mut Vec<JsonValue>) -> usize {
+        static LIMITER: OnceLock<Mutex<RateLimiter>> = OnceLock::new();
+        if !self.enabled() {
+            return 0;
+        }
+        let now_secs = (current_time_micros() / 1_000_000).max(0) as u64;
+        let mut limiter = LIMITER
+            .get_or_init(Default::default)
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        limiter.admit(self, records, now_secs)
+    }
+}
+
+impl RateLimiter {
+    /// Keep records while their service has room in its window at `now_secs`
+    pub(crate) fn admit(
+        &mut self,
+        limits: &ServiceRateLimit,
+        records: &mut Vec<JsonValue>,
+        now_secs: u64,
+    ) -> usize {
+        let window_start = now_secs - now_secs % limits.window_secs.max(1);
+        if self.windows.len() > MAX_TRACKED_SERVICES {
+            self.windows.retain(|_, w| w.start_secs == window_start);
+        }
+        let before = records.len();
+        let mut dropped_by_service: HashMap<String, usize> = HashMap::new();
+        records.retain(|record| {
+            let service = record
+                .get("service_name")
+                .and_then(JsonValue::as_str)
+                .unwrap_or("");
+            let limit = limits.limit_for(service);
+            if limit == 0 {
+                return true;
+            }
+            let window = self.windows.entry(service.to_string()).or_insert(Window {
+                start_secs: window_start,
+                admitted: 0,
+            });
+            if window.start_secs != window_start {
+                *window = Window {
+                    start_secs: window_start,
+                    admitted: 0,
+                };
+            }
+            if window.admitted < limit {
+                window.admitted += 1;
+                true
+            } else {
+                *dropped_by_service.entry(service.to_string()).or_default() += 1;
+                false
+            }
+        });
+        for (service, dropped) in &dropped_by_service {
+            debug!(service = %service, dropped, "service over rate limit, records dropped");
+        }
+        before - records.len()
+    }
+}
+
+fn parse_limit_or_warn(value: &str) -> Option<u64> {
+    let limit = value.trim().parse().ok();
+    if limit.is_none() {
+        warn!(value, "ignoring unparseable service rate limit");
+    }
+    limit
+}
+
+impl TransformStage for ServiceRateLimit {
+    fn name(&self) -> &'static str {
+        "service_rate_limit"
+    }
+
+    fn apply(&self, _: Signal, records: &mut Vec<JsonValue>, counts: &mut TransformCounts) {
+        counts.rate_limited += ServiceRateLimit::apply(self, records);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const NOW_SECS: u64 = 1_700_000_040;
+
+    fn records(service: &str, count: usize) -> Vec<JsonValue> {
+        (0..count)
+            .map(|i| json!({"service_name": service, "i": i}))
+            .collect()
+    }
+
+    fn limits() -> ServiceRateLimit {
+        ServiceRateLimit::from_lookup(|name| match name {
+            "SERVICE_RATE_LIMIT" => Some("3".to_string()),
+            "SERVICE_RATE_LIMIT_OVERRIDES" => Some("batch=0, chatty = 1,bad=x".to_string()),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_over_limit_service_dropped_others_unaffected() {
+        let limits = limits();
+        let mut limiter = RateLimiter::default();
+
+        let mut batch = records("noisy", 5);
+        batch.extend(records("quiet", 2));
+        assert_eq!(limiter.admit(&limits, &mut batch, NOW_SECS), 2);
+        let kept = |batch: &[JsonValue], service: &str| {
+            batch
+                .iter()
+                .filter(|r| r["service_name"] == service)
+                .count()
+        };
+        assert_eq!(kept(&batch, "noisy"), 3);
+        assert_eq!(kept(&batch, "quiet"), 2);
+
+        // Same window: noisy stays blocked, quiet still has room for one more
+        let mut batch = records("noisy", 1);
+        batch.extend(records("quiet", 1));
+        assert_eq!(limiter.admit(&limits, &mut batch, NOW_SECS + 1), 1);
+        assert_eq!(batch, records("quiet", 1));
+
+        // Next window resets the count
+        let mut batch = records("noisy", 1);
+        assert_eq!(limiter.admit(&limits, &mut batch, NOW_SECS + 60), 0);
+    }
+
+    #[test]
+    fn test_overrides() {
+        let limits = limits();
+        assert_eq!(limits.service_limits.len(), 2);
+        let mut limiter = RateLimiter::default();
+
+        let mut batch = records("chatty", 2);
+        batch.extend(records("batch", 10));
+        assert_eq!(limiter.admit(&limits, &mut batch, NOW_SECS), 1);
+        assert_eq!(batch.len(), 11);
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let limits = ServiceRateLimit::from_lookup(|_| None);
+        assert_eq!(limits, ServiceRateLimit::default());
+        let mut batch = records("noisy", 100);
+        assert_eq!(limits.apply(&mut batch), 0);
+        assert_eq!(batch.len(), 100);
+    }
+}
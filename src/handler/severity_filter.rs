@@ -0,0 +1,166 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use tracing::{debug, warn};
+
+use super::stages::TransformStage;
+use super::TransformCounts;
+use crate::signal::Signal;
+
+/// OpenTelemetry severity names and the lowest severity number of each range
+const SEVERITY_NAMES: &[(&str, i64)] = &[
+    ("TRACE", 1),
+    ("DEBUG", 5),
+    ("INFO", 9),
+    ("WARN", 13),
+    ("ERROR", 17),
+    ("FATAL", 21),
+];
+
+/// Minimum log severity, globally and per service
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeverityFilter {
+    /// Logs below this severity number are dropped; 0 keeps everything
+    pub min_severity: i64,
+    /// `service_name` -> minimum severity, overriding `min_severity`
+    pub service_overrides: HashMap<String, i64>,
+}
+
+impl SeverityFilter {
+    /// Build from `MIN_LOG_SEVERITY` (e.g. `WARN` or `13`) and
+    /// `LOG_SEVERITY_OVERRIDES` (`service=LEVEL` pairs, comma-separated).
+    /// Unparseable levels are skipped with a warning.
+    pub fn from_lookup(mut var: impl FnMut(&str) -> Option<String>) -> Self {
+        let min_severity = var("MIN_LOG_SEVERITY")
+            .and_then(|v| parse_level_or_warn(&v))
+            .unwrap_or(0);
+        let service_overrides = var("LOG_SEVERITY_OVERRIDES")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|entry| {
+                let (service, level) = entry.split_once('=')?;
+                let service = service.trim();
+                let level = parse_level_or_warn(level)?;
+                (!service.is_empty()).then(|| (service.to_string(), level))
+            })
+            .collect();
+        Self {
+            min_severity,
+            service_overrides,
+        }
+    }
+
+    /// Drop logs below their service's threshold, returning how many were dropped.
+    /// Logs with unspecified severity (0 or missing) are always kept.
+    pub(crate) fn apply(&self, records: &mut Vec<JsonValue>) -> usize {
+        if self.min_severity == 0 && self.service_overrides.is_empty() {
+            return 0;
+        }
+        let before = records.len();
+        records.retain(|record| {
+            let severity = record
+                .get("severity_number")
+                .and_then(JsonValue::as_i64)
+                .unwrap_or(0);
+            let threshold = record
+                .get("service_name")
+                .and_then(JsonValue::as_str)
+                .and_then(|service| self.service_overrides.get(service))
+                .copied()
+                .unwrap_or(self.min_severity);
+            severity == 0 || severity >= threshold
+        });
+
+        let dropped = before - records.len();
+        if dropped > 0 {
+            debug!(dropped, "dropped logs below severity threshold");
+        }
+        dropped
+    }
+}
+
+/// Parse a severity name (case-insensitive) or number 1-24
+fn parse_level(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(number) = value.parse::<i64>() {
+        return (1..=24).contains(&number).then_some(number);
+    }
+    SEVERITY_NAMES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(value))
+        .map(|(_, number)| *number)
+}
+
+fn parse_level_or_warn(value: &str) -> Option<i64> {
+    let level = parse_level(value);
+    if level.is_none() {
+        warn!(level = value.trim(), "unknown log severity level");
+    }
+    level
+}
+
+impl TransformStage for SeverityFilter {
+    fn name(&self) -> &'static str {
+        "severity_filter"
+    }
+
+    fn apply(&self, signal: Signal, records: &mut Vec<JsonValue>, counts: &mut TransformCounts) {
+        if signal == Signal::Logs {
+            counts.severity_filtered += SeverityFilter::apply(self, records);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn log(service: &str, severity: i64) -> JsonValue {
+        json!({"service_name": service, "severity_number": severity})
+    }
+
+    fn filter(vars: &[(&str, &str)]) -> SeverityFilter {
+        SeverityFilter::from_lookup(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_service_override_drops_below_its_threshold() {
+        let filter = filter(&[
+            ("MIN_LOG_SEVERITY", "info"),
+            ("LOG_SEVERITY_OVERRIDES", "batch-job=ERROR, api = 5"),
+        ]);
+        let mut records = vec![
+            log("batch-job", 9),
+            log("batch-job", 17),
+            log("web", 5),
+            log("web", 9),
+            log("api", 5),
+        ];
+
+        assert_eq!(filter.apply(&mut records), 2);
+        assert_eq!(
+            records,
+            vec![log("batch-job", 17), log("web", 9), log("api", 5)]
+        );
+    }
+
+    #[test]
+    fn test_unspecified_severity_is_kept() {
+        let filter = filter(&[("MIN_LOG_SEVERITY", "WARN")]);
+        let mut records = vec![log("web", 0), json!({"service_name": "web"})];
+        assert_eq!(filter.apply(&mut records), 0);
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("error"), Some(17));
+        assert_eq!(parse_level(" 13 "), Some(13));
+        assert_eq!(parse_level("25"), None);
+        assert_eq!(parse_level("loud"), None);
+        assert_eq!(filter(&[]), SeverityFilter::default());
+    }
+}
@@ -7,7 +7,41 @@ use crate::signal::Signal;
 use crate::InputFormat;
 use otlp2records::{transform_logs_json, transform_metrics_json, transform_traces_json};
 
-use super::{SignalHandler, SkippedMetricsWarning, TransformResult};
+use super::counter_resets::detect_resets;
+use super::float_precision::round_metric_values;
+use super::json_batch::{transform_each, transform_metrics_each};
+use super::log_id::assign_log_ids;
+use super::log_uid::assign_log_uids;
+use super::root_span::mark_root_spans;
+use super::scope_routing::route_by_scope;
+use super::series_id::assign_series_ids;
+use super::span_limits::apply_span_limits;
+use super::span_links::{extract_span_links, SPAN_LINKS_TABLE};
+use super::summary::take_summaries;
+use super::temporality::check_temporality;
+use super::{
+    HandlerConfig, SignalHandler, SkippedMetricsWarning, TransformCounts, TransformResult,
+};
+
+/// Scope columns shared by every signal schema, with their empty defaults.
+/// VRL emits null (dropped from JSON) when the instrumentation scope is absent.
+const SCOPE_FIELD_DEFAULTS: &[(&str, &str)] = &[
+    ("scope_name", ""),
+    ("scope_version", ""),
+    ("scope_attributes", "{}"),
+];
+
+/// Populate missing scope columns so all signals carry them consistently.
+fn default_scope_fields(records: &mut [JsonValue]) {
+    for record in records {
+        if let Some(obj) = record.as_object_mut() {
+            for (field, default) in SCOPE_FIELD_DEFAULTS {
+                obj.entry(*field)
+                    .or_insert_with(|| JsonValue::String((*default).to_string()));
+            }
+        }
+    }
+}
 
 /// Handler for OTLP logs
 pub struct LogsHandler;
@@ -15,8 +49,15 @@ pub struct LogsHandler;
 impl SignalHandler for LogsHandler {
     const SIGNAL: Signal = Signal::Logs;
 
-    fn transform(body: Bytes, format: InputFormat) -> Result<TransformResult, otlp2records::Error> {
-        let transformed = transform_logs_json(&body, format)?;
+    fn transform(
+        body: Bytes,
+        format: InputFormat,
+        config: &HandlerConfig,
+    ) -> Result<TransformResult, otlp2records::Error> {
+        let mut transformed = transform_each(&body, format, transform_logs_json)?;
+        default_scope_fields(&mut transformed);
+        assign_log_ids(&mut transformed, &config.log_id_fields);
+        assign_log_uids(&mut transformed, &config.log_uid_attribute);
         let mut grouped = HashMap::new();
         if !transformed.is_empty() {
             grouped.insert(Signal::Logs.table_name().to_string(), transformed);
@@ -24,6 +65,7 @@ impl SignalHandler for LogsHandler {
         Ok(TransformResult {
             grouped,
             skipped: None,
+            counts: TransformCounts::default(),
         })
     }
 }
@@ -34,28 +76,46 @@ pub struct TracesHandler;
 impl SignalHandler for TracesHandler {
     const SIGNAL: Signal = Signal::Traces;
 
-    fn transform(body: Bytes, format: InputFormat) -> Result<TransformResult, otlp2records::Error> {
-        let transformed = transform_traces_json(&body, format)?;
+    fn transform(
+        body: Bytes,
+        format: InputFormat,
+        config: &HandlerConfig,
+    ) -> Result<TransformResult, otlp2records::Error> {
+        let mut transformed = transform_each(&body, format, transform_traces_json)?;
+        default_scope_fields(&mut transformed);
+        mark_root_spans(&mut transformed);
+        let counts = TransformCounts {
+            truncated: apply_span_limits(&mut transformed, config),
+            ..TransformCounts::default()
+        };
         let mut grouped = HashMap::new();
+        if config.span_links_table {
+            let links = extract_span_links(&transformed);
+            if !links.is_empty() {
+                grouped.insert(SPAN_LINKS_TABLE.to_string(), links);
+            }
+        }
         if !transformed.is_empty() {
             grouped.insert(Signal::Traces.table_name().to_string(), transformed);
         }
         Ok(TransformResult {
             grouped,
             skipped: None,
+            counts,
         })
     }
 }
 
-/// Handler for OTLP metrics (gauge and sum)
+/// Handler for OTLP metrics (gauge, sum, histograms and, with `summary_table`, summary)
 pub struct MetricsHandler;
 
 impl MetricsHandler {
     fn insert_if_not_empty(
         grouped: &mut HashMap<String, Vec<JsonValue>>,
         table: &str,
-        values: Vec<JsonValue>,
+        mut values: Vec<JsonValue>,
     ) {
+        default_scope_fields(&mut values);
         if !values.is_empty() {
             grouped.insert(table.to_string(), values);
         }
@@ -65,8 +125,30 @@ impl MetricsHandler {
 impl SignalHandler for MetricsHandler {
     const SIGNAL: Signal = Signal::Gauge;
 
-    fn transform(body: Bytes, format: InputFormat) -> Result<TransformResult, otlp2records::Error> {
-        let metric_values = transform_metrics_json(&body, format)?;
+    fn transform(
+        body: Bytes,
+        format: InputFormat,
+        config: &HandlerConfig,
+    ) -> Result<TransformResult, otlp2records::Error> {
+        let mut metric_values = transform_metrics_each(&body, format, transform_metrics_json)?;
+        assign_series_ids(&mut metric_values.gauge);
+        assign_series_ids(&mut metric_values.sum);
+        if config.counter_reset_detection {
+            detect_resets(&mut metric_values.sum, config.max_tracked_series);
+        }
+        let mut counts = TransformCounts::default();
+        if config.temporality_validation {
+            counts.temporality_switches = [
+                &metric_values.sum,
+                &metric_values.histogram,
+                &metric_values.exp_histogram,
+            ]
+            .into_iter()
+            .map(|records| check_temporality(records, config.max_tracked_series))
+            .sum();
+        }
+
+        let summaries = take_summaries(&body, format, config, &mut metric_values.skipped)?;
 
         // Build warning if any metrics were skipped
         let skipped = if metric_values.skipped.has_skipped() {
@@ -93,23 +175,322 @@ impl SignalHandler for MetricsHandler {
         };
 
         let mut grouped = HashMap::new();
-        Self::insert_if_not_empty(
-            &mut grouped,
-            Signal::Gauge.table_name(),
-            metric_values.gauge,
-        );
-        Self::insert_if_not_empty(&mut grouped, Signal::Sum.table_name(), metric_values.sum);
-        Self::insert_if_not_empty(
-            &mut grouped,
-            Signal::Histogram.table_name(),
-            metric_values.histogram,
-        );
-        Self::insert_if_not_empty(
-            &mut grouped,
-            Signal::ExpHistogram.table_name(),
-            metric_values.exp_histogram,
-        );
-
-        Ok(TransformResult { grouped, skipped })
+        for (signal, values) in [
+            (Signal::Gauge, metric_values.gauge),
+            (Signal::Sum, metric_values.sum),
+            (Signal::Histogram, metric_values.histogram),
+            (Signal::ExpHistogram, metric_values.exp_histogram),
+            (Signal::Summary, summaries),
+        ] {
+            Self::insert_if_not_empty(&mut grouped, signal.table_name(), values);
+        }
+        for records in grouped.values_mut() {
+            round_metric_values(records, config.metric_value_precision);
+        }
+        route_by_scope(&mut grouped, &config.metric_scope_routes);
+
+        Ok(TransformResult {
+            grouped,
+            skipped,
+            counts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn scope_json(with_scope: bool) -> JsonValue {
+        if with_scope {
+            json!({
+                "name": "my-library",
+                "version": "1.2.3",
+                "attributes": [{"key": "lib.kind", "value": {"stringValue": "http"}}]
+            })
+        } else {
+            JsonValue::Null
+        }
+    }
+
+    fn logs_payload(with_scope: bool) -> Bytes {
+        let mut scope_logs = json!({
+            "logRecords": [{
+                "timeUnixNano": "1703265600000000000",
+                "severityNumber": 9,
+                "body": {"stringValue": "hello"}
+            }]
+        });
+        if with_scope {
+            scope_logs["scope"] = scope_json(true);
+        }
+        let payload = json!({
+            "resourceLogs": [{
+                "resource": {"attributes": [{"key": "service.name", "value": {"stringValue": "svc"}}]},
+                "scopeLogs": [scope_logs]
+            }]
+        });
+        Bytes::from(payload.to_string())
+    }
+
+    fn traces_payload(with_scope: bool) -> Bytes {
+        let mut scope_spans = json!({
+            "spans": [{
+                "traceId": "0af7651916cd43dd8448eb211c80319c",
+                "spanId": "b7ad6b7169203331",
+                "name": "GET /",
+                "kind": 2,
+                "startTimeUnixNano": "1703265600000000000",
+                "endTimeUnixNano": "1703265600100000000"
+            }]
+        });
+        if with_scope {
+            scope_spans["scope"] = scope_json(true);
+        }
+        let payload = json!({
+            "resourceSpans": [{
+                "resource": {"attributes": [{"key": "service.name", "value": {"stringValue": "svc"}}]},
+                "scopeSpans": [scope_spans]
+            }]
+        });
+        Bytes::from(payload.to_string())
+    }
+
+    fn metrics_payload(with_scope: bool) -> Bytes {
+        let mut scope_metrics = json!({
+            "metrics": [{
+                "name": "cpu.usage",
+                "gauge": {"dataPoints": [{"timeUnixNano": "1703265600000000000", "asDouble": 0.5}]}
+            }]
+        });
+        if with_scope {
+            scope_metrics["scope"] = scope_json(true);
+        }
+        let payload = json!({
+            "resourceMetrics": [{
+                "resource": {"attributes": [{"key": "service.name", "value": {"stringValue": "svc"}}]},
+                "scopeMetrics": [scope_metrics]
+            }]
+        });
+        Bytes::from(payload.to_string())
+    }
+
+    fn assert_scope_present(record: &JsonValue) {
+        assert_eq!(record["scope_name"], "my-library");
+        assert_eq!(record["scope_version"], "1.2.3");
+        let attrs: JsonValue =
+            serde_json::from_str(record["scope_attributes"].as_str().unwrap()).unwrap();
+        assert_eq!(attrs["lib.kind"], "http");
+    }
+
+    fn assert_scope_empty(record: &JsonValue) {
+        assert_eq!(record["scope_name"], "");
+        assert_eq!(record["scope_version"], "");
+        assert_eq!(record["scope_attributes"], "{}");
+    }
+
+    #[test]
+    fn logs_carry_scope_fields() {
+        let result = LogsHandler::transform(
+            logs_payload(true),
+            InputFormat::Json,
+            &HandlerConfig::default(),
+        )
+        .unwrap();
+        assert_scope_present(&result.grouped["logs"][0]);
+
+        let result = LogsHandler::transform(
+            logs_payload(false),
+            InputFormat::Json,
+            &HandlerConfig::default(),
+        )
+        .unwrap();
+        assert_scope_empty(&result.grouped["logs"][0]);
+    }
+
+    #[test]
+    fn traces_carry_scope_fields() {
+        let result = TracesHandler::transform(
+            traces_payload(true),
+            InputFormat::Json,
+            &HandlerConfig::default(),
+        )
+        .unwrap();
+        assert_scope_present(&result.grouped["traces"][0]);
+
+        let result = TracesHandler::transform(
+            traces_payload(false),
+            InputFormat::Json,
+            &HandlerConfig::default(),
+        )
+        .unwrap();
+        assert_scope_empty(&result.grouped["traces"][0]);
+    }
+
+    #[test]
+    fn metrics_carry_scope_fields() {
+        let result = MetricsHandler::transform(
+            metrics_payload(true),
+            InputFormat::Json,
+            &HandlerConfig::default(),
+        )
+        .unwrap();
+        assert_scope_present(&result.grouped["gauge"][0]);
+
+        let result = MetricsHandler::transform(
+            metrics_payload(false),
+            InputFormat::Json,
+            &HandlerConfig::default(),
+        )
+        .unwrap();
+        assert_scope_empty(&result.grouped["gauge"][0]);
+    }
+
+    #[test]
+    fn logs_timestamps_share_conversion_unit() {
+        // Both columns are converted from nanos to micros by the same path,
+        // so distinct inputs must keep their relative ordering and precision.
+        let payload = json!({
+            "resourceLogs": [{
+                "scopeLogs": [{
+                    "logRecords": [{
+                        "timeUnixNano": "1703265600123456789",
+                        "observedTimeUnixNano": "1703265600987654321",
+                        "body": {"stringValue": "hello"}
+                    }]
+                }]
+            }]
+        });
+        let result = LogsHandler::transform(
+            Bytes::from(payload.to_string()),
+            InputFormat::Json,
+            &HandlerConfig::default(),
+        )
+        .unwrap();
+        let record = &result.grouped["logs"][0];
+
+        assert_eq!(record["timestamp"], 1703265600123456_i64);
+        assert_eq!(record["observed_timestamp"], 1703265600987654_i64);
+    }
+
+    #[test]
+    fn logs_drop_far_future_timestamps() {
+        let payload = json!({
+            "resourceLogs": [{
+                "scopeLogs": [{
+                    "logRecords": [
+                        {"timeUnixNano": "1703265600000000000", "body": {"stringValue": "ok"}},
+                        {"timeUnixNano": "7258118400000000000", "body": {"stringValue": "future"}}
+                    ]
+                }]
+            }]
+        });
+        let config = HandlerConfig {
+            timestamp_bounds: crate::handler::TimestampBounds {
+                action: crate::handler::OutOfBoundsAction::Drop,
+                ..Default::default()
+            },
+            ..HandlerConfig::default()
+        };
+        let mut result =
+            LogsHandler::transform(Bytes::from(payload.to_string()), InputFormat::Json, &config)
+                .unwrap();
+        super::super::stages::run_configured(&config, Signal::Logs, &mut result).unwrap();
+
+        assert_eq!(result.counts.out_of_bounds_timestamps, 1);
+        assert_eq!(result.grouped["logs"].len(), 1);
+        assert_eq!(result.grouped["logs"][0]["timestamp"], 1703265600000000_i64);
+    }
+
+    #[test]
+    fn metrics_store_optional_description() {
+        let payload = json!({
+            "resourceMetrics": [{
+                "scopeMetrics": [{
+                    "metrics": [
+                        {
+                            "name": "cpu.usage",
+                            "description": "CPU usage ratio",
+                            "gauge": {"dataPoints": [{"timeUnixNano": "1703265600000000000", "asDouble": 0.5}]}
+                        },
+                        {
+                            "name": "mem.usage",
+                            "gauge": {"dataPoints": [{"timeUnixNano": "1703265600000000000", "asDouble": 0.25}]}
+                        }
+                    ]
+                }]
+            }]
+        });
+        let result = MetricsHandler::transform(
+            Bytes::from(payload.to_string()),
+            InputFormat::Json,
+            &HandlerConfig::default(),
+        )
+        .unwrap();
+        let gauge = &result.grouped["gauge"];
+
+        assert_eq!(gauge[0]["metric_description"], "CPU usage ratio");
+        let missing = &gauge[1]["metric_description"];
+        assert!(missing.is_null() || missing == "", "got {}", missing);
+
+        // Nullable column in both metric schemas
+        for table in ["gauge", "sum"] {
+            let schema = otlp2records::schema_def(table).unwrap();
+            let field = schema
+                .fields
+                .iter()
+                .find(|f| f.name == "metric_description")
+                .unwrap();
+            assert_eq!((field.field_type, field.required), ("string", false));
+        }
+    }
+
+    #[test]
+    fn metrics_series_id_ignores_attribute_order() {
+        let point = |attributes: JsonValue| json!({"timeUnixNano": "1703265600000000000", "asDouble": 1.0, "attributes": attributes});
+        let host = json!({"key": "host", "value": {"stringValue": "a"}});
+        let region = json!({"key": "region", "value": {"stringValue": "us"}});
+        let payload = json!({
+            "resourceMetrics": [{
+                "scopeMetrics": [{
+                    "metrics": [{
+                        "name": "cpu.usage",
+                        "gauge": {"dataPoints": [
+                            point(json!([host, region])),
+                            point(json!([region, host])),
+                        ]}
+                    }]
+                }]
+            }]
+        });
+        let result = MetricsHandler::transform(
+            Bytes::from(payload.to_string()),
+            InputFormat::Json,
+            &HandlerConfig::default(),
+        )
+        .unwrap();
+        let gauge = &result.grouped["gauge"];
+
+        assert!(gauge[0]["series_id"].is_string());
+        assert_eq!(gauge[0]["series_id"], gauge[1]["series_id"]);
+    }
+
+    #[test]
+    fn metrics_route_by_configured_scope() {
+        let config = HandlerConfig {
+            metric_scope_routes: HashMap::from([("my-library".to_string(), "lib".to_string())]),
+            ..HandlerConfig::default()
+        };
+
+        let result =
+            MetricsHandler::transform(metrics_payload(true), InputFormat::Json, &config).unwrap();
+        assert_eq!(result.grouped["gauge_lib"].len(), 1);
+        assert!(!result.grouped.contains_key("gauge"));
+
+        let result =
+            MetricsHandler::transform(metrics_payload(false), InputFormat::Json, &config).unwrap();
+        assert_eq!(result.grouped["gauge"].len(), 1);
+        assert!(!result.grouped.contains_key("gauge_lib"));
     }
 }
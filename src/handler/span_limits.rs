@@ -0,0 +1,116 @@
+use serde_json::Value as JsonValue;
+use tracing::debug;
+
+use super::HandlerConfig;
+
+/// Cap span events and links, adding truncated entries to the dropped counts.
+/// Returns the total number of entries truncated.
+pub(crate) fn apply_span_limits(records: &mut [JsonValue], config: &HandlerConfig) -> usize {
+    let mut truncated = 0;
+    for record in records {
+        if let Some(obj) = record.as_object_mut() {
+            truncated += truncate_json_array(
+                obj,
+                "events_json",
+                "dropped_events_count",
+                config.max_span_events,
+            );
+            truncated += truncate_json_array(
+                obj,
+                "links_json",
+                "dropped_links_count",
+                config.max_span_links,
+            );
+        }
+    }
+    truncated
+}
+
+/// Truncate a JSON-encoded array column to `max` entries, returning the excess.
+fn truncate_json_array(
+    obj: &mut serde_json::Map<String, JsonValue>,
+    field: &str,
+    dropped_field: &str,
+    max: usize,
+) -> usize {
+    let Some(JsonValue::String(encoded)) = obj.get(field) else {
+        return 0;
+    };
+    let Ok(JsonValue::Array(mut items)) = serde_json::from_str::<JsonValue>(encoded) else {
+        return 0;
+    };
+    if items.len() <= max {
+        return 0;
+    }
+
+    let excess = items.len() - max;
+    items.truncate(max);
+    debug!(field, excess, max, "truncated span array");
+
+    obj.insert(
+        field.to_string(),
+        JsonValue::String(JsonValue::Array(items).to_string()),
+    );
+    let dropped = obj.get(dropped_field).and_then(|v| v.as_u64()).unwrap_or(0);
+    obj.insert(dropped_field.to_string(), (dropped + excess as u64).into());
+    excess
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn span_with(events: usize, links: usize) -> JsonValue {
+        let events: Vec<_> = (0..events)
+            .map(|i| json!({"name": format!("e{i}")}))
+            .collect();
+        let links: Vec<_> = (0..links)
+            .map(|i| json!({"span_id": format!("{i}")}))
+            .collect();
+        json!({
+            "span_name": "op",
+            "events_json": JsonValue::Array(events).to_string(),
+            "links_json": JsonValue::Array(links).to_string(),
+            "dropped_events_count": 2,
+            "dropped_links_count": 0
+        })
+    }
+
+    fn array_len(record: &JsonValue, field: &str) -> usize {
+        let parsed: JsonValue = serde_json::from_str(record[field].as_str().unwrap()).unwrap();
+        parsed.as_array().unwrap().len()
+    }
+
+    #[test]
+    fn test_truncates_events_and_links_over_cap() {
+        let config = HandlerConfig {
+            max_span_events: 3,
+            max_span_links: 1,
+            ..HandlerConfig::default()
+        };
+        let mut records = vec![span_with(10, 4)];
+        assert_eq!(apply_span_limits(&mut records, &config), 10);
+
+        assert_eq!(array_len(&records[0], "events_json"), 3);
+        assert_eq!(array_len(&records[0], "links_json"), 1);
+        // Existing dropped count is preserved and incremented
+        assert_eq!(records[0]["dropped_events_count"], 9);
+        assert_eq!(records[0]["dropped_links_count"], 3);
+    }
+
+    #[test]
+    fn test_leaves_spans_under_cap_untouched() {
+        let mut records = vec![span_with(2, 1)];
+        let original = records.clone();
+        apply_span_limits(&mut records, &HandlerConfig::default());
+        assert_eq!(records, original);
+    }
+
+    #[test]
+    fn test_ignores_missing_arrays() {
+        let mut records = vec![json!({"span_name": "op"})];
+        apply_span_limits(&mut records, &HandlerConfig::default());
+        assert_eq!(records[0], json!({"span_name": "op"}));
+    }
+}
@@ -0,0 +1,74 @@
+//! Optional `span_links` table: one row per span link.
+//!
+//! Spans keep their `links_json` column; this flattens it so "which spans
+//! link to trace X" is a plain filter instead of JSON unnesting.
+
+use serde_json::{json, Value as JsonValue};
+
+/// Table name for flattened span links
+pub const SPAN_LINKS_TABLE: &str = "span_links";
+
+/// Flatten `links_json` of each span into `span_links` rows.
+/// Spans without links, or with unparseable `links_json`, produce no rows.
+pub(crate) fn extract_span_links(spans: &[JsonValue]) -> Vec<JsonValue> {
+    let mut rows = Vec::new();
+    for span in spans {
+        let links = match span.get("links_json") {
+            Some(JsonValue::String(s)) => serde_json::from_str(s).unwrap_or(JsonValue::Null),
+            Some(value) => value.clone(),
+            None => continue,
+        };
+        let Some(links) = links.as_array() else {
+            continue;
+        };
+        for link in links {
+            let attributes = link.get("attributes").cloned().unwrap_or_else(|| json!({}));
+            rows.push(json!({
+                "timestamp": span.get("timestamp"),
+                "trace_id": span.get("trace_id"),
+                "span_id": span.get("span_id"),
+                "service_name": span.get("service_name"),
+                "linked_trace_id": link.get("trace_id"),
+                "linked_span_id": link.get("span_id"),
+                "linked_trace_state": link.get("trace_state"),
+                "attributes": attributes.to_string(),
+            }));
+        }
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_row_per_link() {
+        let links = json!([
+            {"trace_id": "aa", "span_id": "01", "trace_state": "", "attributes": {"reason": "retry"}},
+            {"trace_id": "bb", "span_id": "02", "trace_state": "k=v", "attributes": {}}
+        ]);
+        let spans = vec![
+            json!({"timestamp": 1, "trace_id": "t1", "span_id": "s1", "service_name": "api",
+                   "links_json": links.to_string()}),
+            json!({"timestamp": 2, "trace_id": "t2", "span_id": "s2", "links_json": "[]"}),
+        ];
+
+        let rows = extract_span_links(&spans);
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0]["span_id"], "s1");
+        assert_eq!(rows[0]["linked_trace_id"], "aa");
+        assert_eq!(rows[0]["attributes"], r#"{"reason":"retry"}"#);
+        assert_eq!(rows[1]["linked_span_id"], "02");
+        assert_eq!(rows[1]["linked_trace_state"], "k=v");
+    }
+
+    #[test]
+    fn test_spans_without_links_produce_no_rows() {
+        let spans = vec![
+            json!({"span_id": "s1"}),
+            json!({"span_id": "s2", "links_json": "not json"}),
+        ];
+        assert!(extract_span_links(&spans).is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+//! Ordered record stages run between decode and send.
+//!
+//! Each stage rewrites or drops records of one table in place. The order (and
+//! which stages run at all) comes from `TRANSFORM_STAGES`, e.g.
+//! `TRANSFORM_STAGES=redaction,severity_filter` runs redaction first and
+//! disables timestamp bounds.
+
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use super::{HandleError, HandlerConfig, TransformCounts, TransformResult};
+use crate::signal::Signal;
+
+/// A step applied to decoded records before they are sent
+pub trait TransformStage {
+    /// Name used in `TRANSFORM_STAGES`
+    fn name(&self) -> &'static str;
+
+    /// Process one table's records in place, adding what changed to `counts`.
+    /// `signal` is the request's signal (`Gauge` for every metric table).
+    fn apply(&self, signal: Signal, records: &mut Vec<JsonValue>, counts: &mut TransformCounts);
+}
+
+/// Built-in stages that can be listed in `TRANSFORM_STAGES`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StageKind {
+    ServiceNameFallback,
+    MultilineJoin,
+    SeverityFilter,
+    ServiceRateLimit,
+    Redaction,
+    TimestampBounds,
+    AttributeKeys,
+}
+
+/// Stage order when `TRANSFORM_STAGES` is unset
+pub const DEFAULT_STAGES: &[StageKind] = &[
+    StageKind::ServiceNameFallback,
+    StageKind::MultilineJoin,
+    StageKind::SeverityFilter,
+    StageKind::ServiceRateLimit,
+    StageKind::Redaction,
+    StageKind::TimestampBounds,
+    StageKind::AttributeKeys,
+];
+
+impl std::str::FromStr for StageKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "service_name_fallback" => Ok(Self::ServiceNameFallback),
+            "multiline_join" => Ok(Self::MultilineJoin),
+            "severity_filter" => Ok(Self::SeverityFilter),
+            "service_rate_limit" => Ok(Self::ServiceRateLimit),
+            "redaction" => Ok(Self::Redaction),
+            "timestamp_bounds" => Ok(Self::TimestampBounds),
+            "attribute_keys" => Ok(Self::AttributeKeys),
+            other => Err(format!("unknown transform stage: {}", other)),
+        }
+    }
+}
+
+/// Parse a comma-separated stage list, skipping unknown names and repeats.
+/// An empty value disables every stage.
+pub(crate) fn parse_stages(value: &str) -> Vec<StageKind> {
+    let mut stages = Vec::new();
+    for kind in value.split(',').filter(|s| !s.trim().is_empty()) {
+        match kind.parse::<StageKind>() {
+            Ok(kind) if !stages.contains(&kind) => stages.push(kind),
+            Ok(_) => {}
+            Err(e) => tracing::warn!(error = %e, "ignoring transform stage"),
+        }
+    }
+    stages
+}
+
+impl HandlerConfig {
+    /// The configured stages, in order, borrowing their settings from this config
+    pub fn transform_stages(&self) -> Vec<Box<dyn TransformStage + '_>> {
+        self.stages
+            .iter()
+            .map(|kind| -> Box<dyn TransformStage + '_> {
+                match kind {
+                    StageKind::ServiceNameFallback => Box::new(&self.service_name_fallback),
+                    StageKind::MultilineJoin => Box::new(&self.multiline_join),
+                    StageKind::SeverityFilter => Box::new(&self.severity_filter),
+                    StageKind::ServiceRateLimit => Box::new(&self.service_rate_limit),
+                    StageKind::Redaction => Box::new(&self.redaction),
+                    StageKind::TimestampBounds => Box::new(&self.timestamp_bounds),
+                    StageKind::AttributeKeys => Box::new(&self.attribute_keys),
+                }
+            })
+            .collect()
+    }
+}
+
+impl<T: TransformStage + ?Sized> TransformStage for &T {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    fn apply(&self, signal: Signal, records: &mut Vec<JsonValue>, counts: &mut TransformCounts) {
+        (**self).apply(signal, records, counts)
+    }
+}
+
+/// Run `stages` in order over every table, removing tables left empty.
+/// Later stages are skipped once no records remain.
+pub fn run_stages(
+    stages: &[Box<dyn TransformStage + '_>],
+    signal: Signal,
+    grouped: &mut HashMap<String, Vec<JsonValue>>,
+    counts: &mut TransformCounts,
+) {
+    for stage in stages {
+        if grouped.is_empty() {
+            tracing::debug!(stage = stage.name(), "no records left, skipping stage");
+            break;
+        }
+        for records in grouped.values_mut() {
+            stage.apply(signal, records, counts);
+        }
+        grouped.retain(|_, records| !records.is_empty());
+    }
+}
+
+/// Run the stages configured in `config` over a transform result, then drop
+/// projected-out columns. Fails when strict timestamp bounds reject a record
+/// still present when the bounds stage is reached.
+pub(crate) fn run_configured(
+    config: &HandlerConfig,
+    signal: Signal,
+    result: &mut TransformResult,
+) -> Result<(), HandleError> {
+    for (kind, stage) in config.stages.iter().zip(config.transform_stages()) {
+        if *kind == StageKind::TimestampBounds {
+            config
+                .timestamp_bounds
+                .check_strict(&result.grouped)
+                .map_err(HandleError::Decode)?;
+        }
+        run_stages(&[stage], signal, &mut result.grouped, &mut result.counts);
+    }
+    config.projection.apply(&mut result.grouped);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::RefCell;
+
+    /// Appends its name to each record's `trail` and logs each call
+    struct Mark<'a> {
+        name: &'static str,
+        calls: &'a RefCell<Vec<&'static str>>,
+    }
+
+    impl TransformStage for Mark<'_> {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn apply(&self, _: Signal, records: &mut Vec<JsonValue>, _: &mut TransformCounts) {
+            self.calls.borrow_mut().push(self.name);
+            for record in records {
+                let trail = record["trail"].as_str().unwrap_or("").to_string();
+                record["trail"] = json!(format!("{}{}", trail, self.name));
+            }
+        }
+    }
+
+    struct DropAll;
+
+    impl TransformStage for DropAll {
+        fn name(&self) -> &'static str {
+            "drop_all"
+        }
+
+        fn apply(&self, _: Signal, records: &mut Vec<JsonValue>, _: &mut TransformCounts) {
+            records.clear();
+        }
+    }
+
+    fn grouped() -> HashMap<String, Vec<JsonValue>> {
+        HashMap::from([("logs".to_string(), vec![json!({"severity_number": 9})])])
+    }
+
+    #[test]
+    fn test_stages_run_in_order() {
+        let calls = RefCell::new(Vec::new());
+        let stages: Vec<Box<dyn TransformStage + '_>> = vec![
+            Box::new(Mark {
+                name: "b",
+                calls: &calls,
+            }),
+            Box::new(Mark {
+                name: "a",
+                calls: &calls,
+            }),
+        ];
+        let mut grouped = grouped();
+        run_stages(
+            &stages,
+            Signal::Logs,
+            &mut grouped,
+            &mut TransformCounts::default(),
+        );
+        assert_eq!(grouped["logs"][0]["trail"], "ba");
+        assert_eq!(*calls.borrow(), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn test_stage_dropping_everything_short_circuits() {
+        let calls = RefCell::new(Vec::new());
+        let stages: Vec<Box<dyn TransformStage + '_>> = vec![
+            Box::new(DropAll),
+            Box::new(Mark {
+                name: "after",
+                calls: &calls,
+            }),
+        ];
+        let mut grouped = grouped();
+        run_stages(
+            &stages,
+            Signal::Logs,
+            &mut grouped,
+            &mut TransformCounts::default(),
+        );
+        assert!(grouped.is_empty());
+        assert!(calls.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_configured_order_changes_outcome() {
+        // Severity filtering before bounds never counts the dropped log's timestamp
+        let record = json!({"severity_number": 1, "timestamp": 1});
+        let mut config = HandlerConfig::default();
+        config.severity_filter.min_severity = 5;
+
+        for (order, expected_out_of_bounds) in [
+            ("severity_filter,timestamp_bounds", 0),
+            ("timestamp_bounds,severity_filter", 1),
+        ] {
+            config.stages = parse_stages(order);
+            let mut result = TransformResult {
+                grouped: HashMap::from([("logs".to_string(), vec![record.clone()])]),
+                skipped: None,
+                counts: TransformCounts::default(),
+            };
+            run_configured(&config, Signal::Logs, &mut result).unwrap();
+            assert!(result.grouped.is_empty(), "{}", order);
+            assert_eq!(result.counts.severity_filtered, 1, "{}", order);
+            assert_eq!(
+                result.counts.out_of_bounds_timestamps, expected_out_of_bounds,
+                "{}",
+                order
+            );
+        }
+    }
+
+    #[test]
+    fn test_strict_bounds_check_runs_at_stage_position() {
+        // A filtered-out record never reaches strict bounds listed after the filter
+        let record = json!({"severity_number": 1, "timestamp": 1});
+        let mut config = HandlerConfig::default();
+        config.severity_filter.min_severity = 5;
+        config.timestamp_bounds.action = crate::handler::OutOfBoundsAction::Reject;
+
+        for (order, rejected) in [
+            ("severity_filter,timestamp_bounds", false),
+            ("timestamp_bounds,severity_filter", true),
+        ] {
+            config.stages = parse_stages(order);
+            let mut result = TransformResult {
+                grouped: HashMap::from([("logs".to_string(), vec![record.clone()])]),
+                skipped: None,
+                counts: TransformCounts::default(),
+            };
+            let outcome = run_configured(&config, Signal::Logs, &mut result);
+            assert_eq!(
+                matches!(outcome, Err(HandleError::Decode(_))),
+                rejected,
+                "{}",
+                order
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_stages() {
+        assert_eq!(
+            parse_stages("redaction, bogus,SEVERITY_FILTER,redaction"),
+            vec![StageKind::Redaction, StageKind::SeverityFilter]
+        );
+        assert!(parse_stages("").is_empty());
+
+        let config = HandlerConfig::from_lookup(|name| {
+            (name == "TRANSFORM_STAGES").then(|| "timestamp_bounds".to_string())
+        });
+        let names: Vec<_> = config.transform_stages().iter().map(|s| s.name()).collect();
+        assert_eq!(names, vec!["timestamp_bounds"]);
+    }
+}
@@ -0,0 +1,90 @@
+//! Strict top-level field checks for OTLP JSON bodies.
+//!
+//! The JSON decoders ignore unknown fields, so a typo like `resourceMetric`
+//! decodes to zero records without an error. With `STRICT_JSON=true`, JSON
+//! requests whose top-level object has any field besides the signal's
+//! `resource*` list are rejected instead.
+
+use serde_json::Value as JsonValue;
+
+use super::HandleError;
+use crate::signal::Signal;
+use crate::InputFormat;
+
+/// Top-level fields accepted for a signal (proto3 JSON allows either spelling)
+fn allowed_fields(signal: Signal) -> [&'static str; 2] {
+    match signal {
+        Signal::Logs => ["resourceLogs", "resource_logs"],
+        Signal::Traces => ["resourceSpans", "resource_spans"],
+        _ => ["resourceMetrics", "resource_metrics"],
+    }
+}
+
+/// Reject JSON bodies with unknown top-level fields. Protobuf bodies and
+/// bodies that aren't valid JSON are left for the decoder to handle.
+pub(crate) fn check_top_level(
+    body: &[u8],
+    format: InputFormat,
+    signal: Signal,
+) -> Result<(), HandleError> {
+    let first = body.iter().find(|b| !b.is_ascii_whitespace());
+    let requests: Vec<JsonValue> = match format {
+        InputFormat::Protobuf => return Ok(()),
+        InputFormat::Auto if !matches!(first, Some(b'{') | Some(b'[')) => return Ok(()),
+        InputFormat::Jsonl => body
+            .split(|b| *b == b'\n')
+            .filter(|line| !line.trim_ascii().is_empty())
+            .filter_map(|line| serde_json::from_slice(line).ok())
+            .collect(),
+        _ => match serde_json::from_slice(body) {
+            Ok(JsonValue::Array(elements)) => elements,
+            Ok(request) => vec![request],
+            Err(_) => return Ok(()),
+        },
+    };
+
+    let allowed = allowed_fields(signal);
+    for request in &requests {
+        let Some(obj) = request.as_object() else {
+            continue;
+        };
+        if let Some(unknown) = obj.keys().find(|k| !allowed.contains(&k.as_str())) {
+            return Err(HandleError::Decode(format!(
+                "strict JSON: unknown top-level field '{}' (expected '{}')",
+                unknown, allowed[0]
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MISSPELLED: &[u8] = br#"{"resourceMetric": [{"scopeMetrics": []}]}"#;
+
+    #[test]
+    fn test_misspelled_field_rejected() {
+        let err = check_top_level(MISSPELLED, InputFormat::Json, Signal::Gauge).unwrap_err();
+        assert!(err.to_string().contains("'resourceMetric'"), "{}", err);
+
+        let jsonl = [br#"{"resourceMetrics": []}"#.as_slice(), MISSPELLED].join(&b'\n');
+        assert!(check_top_level(&jsonl, InputFormat::Jsonl, Signal::Gauge).is_err());
+        let array = [b"[".as_slice(), MISSPELLED, b"]"].concat();
+        assert!(check_top_level(&array, InputFormat::Auto, Signal::Gauge).is_err());
+    }
+
+    #[test]
+    fn test_known_fields_and_other_formats_pass() {
+        for (body, signal) in [
+            (br#"{"resourceMetrics": []}"#.as_slice(), Signal::Gauge),
+            (br#"{"resource_spans": []}"#.as_slice(), Signal::Traces),
+            (br#"{}"#.as_slice(), Signal::Logs),
+            (b"not json".as_slice(), Signal::Logs),
+        ] {
+            assert!(check_top_level(body, InputFormat::Json, signal).is_ok());
+        }
+        assert!(check_top_level(MISSPELLED, InputFormat::Protobuf, Signal::Gauge).is_ok());
+    }
+}
@@ -0,0 +1,387 @@
+//! Summary metric decoding.
+//!
+//! otlp2records skips OTLP Summary metrics and only counts them. With
+//! `SUMMARY_TABLE=true` each summary data point becomes one `summary` row
+//! carrying its `count`, `sum` and `quantile_values` (a JSON array of
+//! `{quantile, value}`). The shared metric columns (service, resource, scope
+//! and point attributes) come from running each point through the gauge
+//! transform as a placeholder gauge point, so they match the other metric
+//! tables exactly.
+
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
+use opentelemetry_proto::tonic::metrics::v1::metric::Data;
+use opentelemetry_proto::tonic::metrics::v1::number_data_point::Value;
+use opentelemetry_proto::tonic::metrics::v1::{Gauge, NumberDataPoint};
+use otlp2records::decode::{looks_like_json, DecodeError, SkippedMetrics};
+use otlp2records::{transform_metrics_json, InputFormat};
+use prost::Message;
+use serde_json::{json, Map, Value as JsonValue};
+
+use super::json_batch::transform_each;
+use super::HandlerConfig;
+
+/// Gauge-only columns dropped from the placeholder rows
+const GAUGE_ONLY_FIELDS: &[&str] = &["value", "exemplars_json"];
+
+/// The summary-specific values of one data point
+struct SummaryValues {
+    count: u64,
+    sum: f64,
+    quantiles: Vec<(f64, f64)>,
+}
+
+/// Summary rows for a request when `summary_table` is enabled and otlp2records
+/// skipped summaries; they are then no longer reported as skipped
+pub(crate) fn take_summaries(
+    body: &[u8],
+    format: InputFormat,
+    config: &HandlerConfig,
+    skipped: &mut SkippedMetrics,
+) -> Result<Vec<JsonValue>, otlp2records::Error> {
+    if !config.summary_table || skipped.summaries == 0 {
+        return Ok(Vec::new());
+    }
+    let rows = transform_each(body, format, transform_summaries)?;
+    skipped.summaries = 0;
+    Ok(rows)
+}
+
+/// Decode the summary metrics in an OTLP metrics request into `summary` rows
+pub(crate) fn transform_summaries(
+    body: &[u8],
+    format: InputFormat,
+) -> Result<Vec<JsonValue>, otlp2records::Error> {
+    match format {
+        InputFormat::Protobuf => from_protobuf(body),
+        InputFormat::Json => from_json(body),
+        InputFormat::Auto if looks_like_json(body) => from_json(body),
+        InputFormat::Auto => from_protobuf(body),
+        InputFormat::Jsonl => {
+            let mut records = Vec::new();
+            for line in body.split(|b| *b == b'\n') {
+                if !line.iter().all(u8::is_ascii_whitespace) {
+                    records.extend(from_json(line)?);
+                }
+            }
+            Ok(records)
+        }
+    }
+}
+
+fn from_protobuf(body: &[u8]) -> Result<Vec<JsonValue>, otlp2records::Error> {
+    let mut request = ExportMetricsServiceRequest::decode(body).map_err(DecodeError::Protobuf)?;
+    let mut values = Vec::new();
+    for resource in &mut request.resource_metrics {
+        for scope in &mut resource.scope_metrics {
+            scope.metrics.retain_mut(|metric| {
+                let Some(Data::Summary(summary)) = metric.data.take() else {
+                    return false;
+                };
+                let points = summary
+                    .data_points
+                    .into_iter()
+                    .map(|point| {
+                        values.push(SummaryValues {
+                            count: point.count,
+                            sum: point.sum,
+                            quantiles: point
+                                .quantile_values
+                                .iter()
+                                .map(|q| (q.quantile, q.value))
+                                .collect(),
+                        });
+                        NumberDataPoint {
+                            attributes: point.attributes,
+                            start_time_unix_nano: point.start_time_unix_nano,
+                            time_unix_nano: point.time_unix_nano,
+                            flags: point.flags,
+                            value: Some(Value::AsDouble(0.0)),
+                            ..Default::default()
+                        }
+                    })
+                    .collect();
+                metric.data = Some(Data::Gauge(Gauge {
+                    data_points: points,
+                }));
+                true
+            });
+        }
+    }
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+    let gauges = transform_metrics_json(&request.encode_to_vec(), InputFormat::Protobuf)?.gauge;
+    Ok(summary_rows(gauges, values))
+}
+
+fn from_json(body: &[u8]) -> Result<Vec<JsonValue>, otlp2records::Error> {
+    let mut request: JsonValue = serde_json::from_slice(body).map_err(DecodeError::Json)?;
+    let mut values = Vec::new();
+    for resource in array_mut(&mut request, "resourceMetrics", "resource_metrics") {
+        for scope in array_mut(resource, "scopeMetrics", "scope_metrics") {
+            let Some(metrics) = scope.get_mut("metrics").and_then(JsonValue::as_array_mut) else {
+                continue;
+            };
+            metrics.retain_mut(|metric| {
+                let Some(summary) = metric.as_object_mut().and_then(|m| m.remove("summary")) else {
+                    return false;
+                };
+                let points: Vec<JsonValue> = field(&summary, "dataPoints", "data_points")
+                    .and_then(JsonValue::as_array)
+                    .into_iter()
+                    .flatten()
+                    .filter_map(JsonValue::as_object)
+                    .map(|point| {
+                        values.push(json_summary_values(point));
+                        json_gauge_point(point)
+                    })
+                    .collect();
+                metric["gauge"] = json!({ "dataPoints": points });
+                true
+            });
+        }
+    }
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+    let body = serde_json::to_vec(&request).map_err(DecodeError::Json)?;
+    let gauges = transform_metrics_json(&body, InputFormat::Json)?.gauge;
+    Ok(summary_rows(gauges, values))
+}
+
+/// Elements of the array at `camel` (or `snake`), as OTLP JSON allows either
+fn array_mut<'a>(
+    value: &'a mut JsonValue,
+    camel: &str,
+    snake: &str,
+) -> impl Iterator<Item = &'a mut JsonValue> {
+    let key = if value.get(camel).is_some() {
+        camel
+    } else {
+        snake
+    };
+    value
+        .get_mut(key)
+        .and_then(JsonValue::as_array_mut)
+        .into_iter()
+        .flatten()
+}
+
+fn field<'a>(value: &'a JsonValue, camel: &str, snake: &str) -> Option<&'a JsonValue> {
+    value.get(camel).or_else(|| value.get(snake))
+}
+
+/// A number that OTLP JSON may encode as a string (64-bit integers, `NaN`)
+fn json_f64(value: Option<&JsonValue>) -> f64 {
+    match value {
+        Some(JsonValue::String(s)) => s.parse().unwrap_or(0.0),
+        Some(v) => v.as_f64().unwrap_or(0.0),
+        None => 0.0,
+    }
+}
+
+fn json_summary_values(point: &Map<String, JsonValue>) -> SummaryValues {
+    let point = JsonValue::Object(point.clone());
+    let count = match point.get("count") {
+        Some(JsonValue::String(s)) => s.parse().unwrap_or(0),
+        Some(v) => v.as_u64().unwrap_or(0),
+        None => 0,
+    };
+    let quantiles = field(&point, "quantileValues", "quantile_values")
+        .and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten()
+        .map(|q| (json_f64(q.get("quantile")), json_f64(q.get("value"))))
+        .collect();
+    SummaryValues {
+        count,
+        sum: json_f64(point.get("sum")),
+        quantiles,
+    }
+}
+
+/// The point's timestamps, attributes and flags as a placeholder gauge point
+fn json_gauge_point(point: &Map<String, JsonValue>) -> JsonValue {
+    let mut gauge = point.clone();
+    for key in ["count", "sum", "quantileValues", "quantile_values"] {
+        gauge.remove(key);
+    }
+    gauge.insert("asDouble".to_string(), json!(0.0));
+    JsonValue::Object(gauge)
+}
+
+/// Replace the placeholder gauge values with each point's summary values.
+/// Non-finite sums are left out and non-finite quantiles dropped.
+fn summary_rows(gauges: Vec<JsonValue>, values: Vec<SummaryValues>) -> Vec<JsonValue> {
+    gauges
+        .into_iter()
+        .zip(values)
+        .map(|(mut row, values)| {
+            if let Some(row) = row.as_object_mut() {
+                for field in GAUGE_ONLY_FIELDS {
+                    row.remove(*field);
+                }
+                row.insert("count".to_string(), values.count.into());
+                if values.sum.is_finite() {
+                    row.insert("sum".to_string(), values.sum.into());
+                }
+                let quantiles: Vec<JsonValue> = values
+                    .quantiles
+                    .into_iter()
+                    .filter(|(quantile, value)| quantile.is_finite() && value.is_finite())
+                    .map(|(quantile, value)| json!({"quantile": quantile, "value": value}))
+                    .collect();
+                row.insert(
+                    "quantile_values".to_string(),
+                    JsonValue::String(JsonValue::Array(quantiles).to_string()),
+                );
+            }
+            row
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::{MetricsHandler, SignalHandler};
+    use opentelemetry_proto::tonic::common::v1::{any_value, AnyValue, KeyValue};
+    use opentelemetry_proto::tonic::metrics::v1::summary_data_point::ValueAtQuantile;
+    use opentelemetry_proto::tonic::metrics::v1::{
+        Metric, ResourceMetrics, ScopeMetrics, Summary, SummaryDataPoint,
+    };
+    use opentelemetry_proto::tonic::resource::v1::Resource;
+
+    fn json_request() -> JsonValue {
+        json!({
+            "resourceMetrics": [{
+                "resource": {"attributes": [{"key": "service.name", "value": {"stringValue": "legacy"}}]},
+                "scopeMetrics": [{
+                    "metrics": [
+                        {
+                            "name": "rpc_latency",
+                            "unit": "ms",
+                            "summary": {"dataPoints": [{
+                                "timeUnixNano": "1703265600000000000",
+                                "count": "12",
+                                "sum": 340.5,
+                                "quantileValues": [
+                                    {"quantile": 0.5, "value": 20.0},
+                                    {"quantile": 0.99, "value": 95.0}
+                                ],
+                                "attributes": [{"key": "method", "value": {"stringValue": "Get"}}]
+                            }]}
+                        },
+                        {"name": "cpu", "gauge": {"dataPoints": [{"timeUnixNano": "1703265600000000000", "asDouble": 0.5}]}}
+                    ]
+                }]
+            }]
+        })
+    }
+
+    fn quantiles(row: &JsonValue) -> JsonValue {
+        serde_json::from_str(row["quantile_values"].as_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_json_summary_becomes_summary_row() {
+        let body = json_request().to_string();
+        let rows = transform_summaries(body.as_bytes(), InputFormat::Json).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!(row["metric_name"], "rpc_latency");
+        assert_eq!(row["metric_unit"], "ms");
+        assert_eq!(row["service_name"], "legacy");
+        assert_eq!(row["count"], 12);
+        assert_eq!(row["sum"], 340.5);
+        assert_eq!(row["timestamp"], 1703265600000000_i64);
+        assert_eq!(
+            quantiles(row),
+            json!([{"quantile": 0.5, "value": 20.0}, {"quantile": 0.99, "value": 95.0}])
+        );
+        assert!(row["metric_attributes"].as_str().unwrap().contains("Get"));
+        assert!(row.get("value").is_none());
+    }
+
+    #[test]
+    fn test_protobuf_summary_becomes_summary_row() {
+        let request = ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(Resource {
+                    attributes: vec![KeyValue {
+                        key: "service.name".to_string(),
+                        value: Some(AnyValue {
+                            value: Some(any_value::Value::StringValue("legacy".to_string())),
+                        }),
+                    }],
+                    ..Default::default()
+                }),
+                scope_metrics: vec![ScopeMetrics {
+                    metrics: vec![Metric {
+                        name: "rpc_latency".to_string(),
+                        data: Some(Data::Summary(Summary {
+                            data_points: vec![SummaryDataPoint {
+                                time_unix_nano: 1_703_265_600_000_000_000,
+                                count: 3,
+                                sum: f64::NAN,
+                                quantile_values: vec![
+                                    ValueAtQuantile {
+                                        quantile: 0.9,
+                                        value: 7.0,
+                                    },
+                                    ValueAtQuantile {
+                                        quantile: 1.0,
+                                        value: f64::INFINITY,
+                                    },
+                                ],
+                                ..Default::default()
+                            }],
+                        })),
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+        };
+        let rows = transform_summaries(&request.encode_to_vec(), InputFormat::Auto).unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["service_name"], "legacy");
+        assert_eq!(rows[0]["count"], 3);
+        // Non-finite values are left out
+        assert!(rows[0].get("sum").is_none());
+        assert_eq!(
+            quantiles(&rows[0]),
+            json!([{"quantile": 0.9, "value": 7.0}])
+        );
+    }
+
+    #[test]
+    fn test_requests_without_summaries_yield_nothing() {
+        let body = json!({"resourceMetrics": []}).to_string();
+        assert!(transform_summaries(body.as_bytes(), InputFormat::Json)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_summary_table_is_opt_in() {
+        let body = bytes::Bytes::from(json_request().to_string());
+        let skipped =
+            MetricsHandler::transform(body.clone(), InputFormat::Json, &HandlerConfig::default())
+                .unwrap();
+        assert!(!skipped.grouped.contains_key("summary"));
+        assert_eq!(skipped.skipped.unwrap().summaries, 1);
+
+        let config = HandlerConfig {
+            summary_table: true,
+            ..HandlerConfig::default()
+        };
+        let result = MetricsHandler::transform(body, InputFormat::Json, &config).unwrap();
+        assert_eq!(result.grouped["summary"].len(), 1);
+        assert_eq!(result.grouped["gauge"].len(), 1);
+        assert!(result.skipped.is_none());
+    }
+}
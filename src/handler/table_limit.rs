@@ -0,0 +1,48 @@
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use tracing::warn;
+
+use super::HandleError;
+
+/// Reject requests that would fan out to more than `max_tables` distinct tables
+pub(crate) fn check_table_limit(
+    grouped: &HashMap<String, Vec<JsonValue>>,
+    max_tables: usize,
+) -> Result<(), HandleError> {
+    if grouped.len() <= max_tables {
+        return Ok(());
+    }
+
+    warn!(
+        tables = grouped.len(),
+        max_tables, "request exceeds table fan-out limit"
+    );
+    Err(HandleError::TooManyTables(format!(
+        "request produced {} tables, limit is {}",
+        grouped.len(),
+        max_tables
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grouped(tables: usize) -> HashMap<String, Vec<JsonValue>> {
+        (0..tables)
+            .map(|i| (format!("gauge_{}", i), vec![JsonValue::from(i)]))
+            .collect()
+    }
+
+    #[test]
+    fn test_allows_tables_up_to_limit() {
+        assert!(check_table_limit(&grouped(4), 4).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_tables_over_limit() {
+        let err = check_table_limit(&grouped(5), 4).unwrap_err();
+        assert!(matches!(err, HandleError::TooManyTables(_)));
+        assert!(err.to_string().contains("5 tables, limit is 4"));
+    }
+}
@@ -0,0 +1,121 @@
+//! Aggregation temporality consistency checks.
+//!
+//! Mixing delta and cumulative points under one metric name breaks rate and
+//! sum queries in subtle ways. Long-lived processes (native server, warm
+//! Lambda) remember each metric's last temporality and warn when it changes.
+
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use tracing::warn;
+
+/// Last temporality seen per metric name, evicting the oldest name past `max_metrics`
+#[derive(Debug, Default)]
+pub(crate) struct TemporalityTracker {
+    metrics: HashMap<String, i64>,
+    order: VecDeque<String>,
+}
+
+impl TemporalityTracker {
+    /// Remember each record's temporality, returning how many points switched it
+    pub(crate) fn observe(&mut self, records: &[JsonValue], max_metrics: usize) -> usize {
+        if max_metrics == 0 {
+            return 0;
+        }
+        let mut switches = 0;
+        for record in records {
+            let (Some(name), Some(temporality)) = (
+                record.get("metric_name").and_then(JsonValue::as_str),
+                record
+                    .get("aggregation_temporality")
+                    .and_then(JsonValue::as_i64),
+            ) else {
+                continue;
+            };
+            match self.metrics.get_mut(name) {
+                Some(last) if *last != temporality => {
+                    warn!(
+                        metric_name = name,
+                        from = temporality_name(*last),
+                        to = temporality_name(temporality),
+                        "metric switched aggregation temporality"
+                    );
+                    *last = temporality;
+                    switches += 1;
+                }
+                Some(_) => {}
+                None => {
+                    while self.metrics.len() >= max_metrics {
+                        let Some(oldest) = self.order.pop_front() else {
+                            break;
+                        };
+                        self.metrics.remove(&oldest);
+                    }
+                    self.metrics.insert(name.to_string(), temporality);
+                    self.order.push_back(name.to_string());
+                }
+            }
+        }
+        switches
+    }
+}
+
+fn temporality_name(value: i64) -> &'static str {
+    match value {
+        1 => "delta",
+        2 => "cumulative",
+        _ => "unspecified",
+    }
+}
+
+/// Run `records` through the process-wide tracker
+pub(crate) fn check_temporality(records: &[JsonValue], max_metrics: usize) -> usize {
+    static TRACKER: OnceLock<Mutex<TemporalityTracker>> = OnceLock::new();
+    let mut tracker = TRACKER
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    tracker.observe(records, max_metrics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn point(name: &str, temporality: i64) -> JsonValue {
+        json!({"metric_name": name, "aggregation_temporality": temporality, "value": 1.0})
+    }
+
+    #[test]
+    fn test_switch_is_reported_and_consistent_metrics_are_not() {
+        let mut tracker = TemporalityTracker::default();
+        assert_eq!(
+            tracker.observe(&[point("requests", 2), point("bytes", 1)], 100),
+            0
+        );
+        assert_eq!(
+            tracker.observe(&[point("requests", 2), point("bytes", 1)], 100),
+            0
+        );
+
+        // Only the metric that changed counts, and switching back counts again
+        assert_eq!(
+            tracker.observe(&[point("requests", 1), point("bytes", 1)], 100),
+            1
+        );
+        assert_eq!(tracker.observe(&[point("requests", 2)], 100), 1);
+    }
+
+    #[test]
+    fn test_tracked_metrics_are_bounded() {
+        let mut tracker = TemporalityTracker::default();
+        tracker.observe(&[point("a", 2), point("b", 2), point("c", 2)], 2);
+        assert_eq!(tracker.metrics.len(), 2);
+
+        // "a" was evicted, so its first point after eviction is a fresh baseline
+        assert_eq!(tracker.observe(&[point("a", 1)], 2), 0);
+        assert_eq!(tracker.observe(&[point("c", 1)], 2), 1);
+        assert_eq!(tracker.observe(&[point("a", 1)], 0), 0);
+    }
+}
@@ -0,0 +1,339 @@
+//! Sanity bounds for record timestamps.
+//!
+//! Buggy clocks emit year-1970 or year-2286 timestamps that land in absurd
+//! partitions. Records whose `timestamp` (microseconds), `start_timestamp` or
+//! `end_timestamp` (milliseconds) falls outside the window are counted and
+//! logged by default. `TIMESTAMP_OUT_OF_BOUNDS=clamp` moves them to the
+//! window's edge, `drop` drops the record, and `reject` fails the whole
+//! request so misconfigured clocks surface loudly.
+
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+use tracing::warn;
+
+use super::stages::TransformStage;
+use super::TransformCounts;
+use crate::signal::Signal;
+
+const MICROS_PER_SEC: i64 = 1_000_000;
+const MILLIS_PER_SEC: i64 = 1_000;
+
+/// Bounded fields and their units per second. A `start_timestamp` of 0 means
+/// the start time is unknown and is left alone.
+const BOUNDED_FIELDS: &[(&str, i64)] = &[
+    ("timestamp", MICROS_PER_SEC),
+    ("start_timestamp", MILLIS_PER_SEC),
+    ("end_timestamp", MILLIS_PER_SEC),
+];
+
+/// Default lower bound: 2000-01-01T00:00:00Z
+pub const DEFAULT_TIMESTAMP_MIN_UNIX_SECS: i64 = 946_684_800;
+/// Default upper bound: one day past the current time
+pub const DEFAULT_TIMESTAMP_MAX_FUTURE_SECS: i64 = 86_400;
+
+/// What to do with a record whose timestamp is out of bounds
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutOfBoundsAction {
+    /// Count and log the record, leaving it unchanged
+    #[default]
+    Count,
+    /// Move the timestamp to the nearest bound
+    Clamp,
+    /// Drop the record
+    Drop,
+    /// Reject the request with a decode error (strict timestamps)
+    Reject,
+}
+
+impl std::str::FromStr for OutOfBoundsAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "count" => Ok(Self::Count),
+            "clamp" => Ok(Self::Clamp),
+            "drop" => Ok(Self::Drop),
+            "reject" => Ok(Self::Reject),
+            other => Err(format!(
+                "unknown out-of-bounds action '{}' (expected count, clamp, drop or reject)",
+                other
+            )),
+        }
+    }
+}
+
+/// Accepted timestamp window, relative to the time a request is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampBounds {
+    /// Earliest accepted timestamp, in unix seconds
+    pub min_unix_secs: i64,
+    /// How far past now a timestamp may be, in seconds
+    pub max_future_secs: i64,
+    pub action: OutOfBoundsAction,
+}
+
+impl Default for TimestampBounds {
+    fn default() -> Self {
+        Self {
+            min_unix_secs: DEFAULT_TIMESTAMP_MIN_UNIX_SECS,
+            max_future_secs: DEFAULT_TIMESTAMP_MAX_FUTURE_SECS,
+            action: OutOfBoundsAction::default(),
+        }
+    }
+}
+
+impl TimestampBounds {
+    /// Count, clamp or drop records outside the window, returning how many were affected
+    pub(crate) fn apply(&self, records: &mut Vec<JsonValue>) -> usize {
+        self.apply_at(records, current_time_micros())
+    }
+
+    /// With the `Reject` action, fail on the first out-of-bounds timestamp
+    pub(crate) fn check_strict(
+        &self,
+        grouped: &HashMap<String, Vec<JsonValue>>,
+    ) -> Result<(), String> {
+        self.check_strict_at(grouped, current_time_micros())
+    }
+
+    fn check_strict_at(
+        &self,
+        grouped: &HashMap<String, Vec<JsonValue>>,
+        now_micros: i64,
+    ) -> Result<(), String> {
+        if self.action != OutOfBoundsAction::Reject {
+            return Ok(());
+        }
+        for (table, records) in grouped {
+            for (idx, record) in records.iter().enumerate() {
+                if let Some((field, value, (min, max))) =
+                    self.out_of_bounds(record, now_micros).next()
+                {
+                    return Err(format!(
+                        "record {} ({}): field '{}' value {} is outside the accepted window [{}, {}]",
+                        idx, table, field, value, min, max
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Accepted `[min, max]` timestamps in `per_sec` units (1_000_000 for microseconds)
+    fn window(&self, now_micros: i64, per_sec: i64) -> (i64, i64) {
+        let min = self.min_unix_secs.saturating_mul(per_sec);
+        let now = now_micros / (MICROS_PER_SEC / per_sec);
+        let max = now.saturating_add(self.max_future_secs.saturating_mul(per_sec));
+        (min, max)
+    }
+
+    /// Bounded fields of `record` outside their window, with the value and window
+    fn out_of_bounds<'a>(
+        &'a self,
+        record: &'a JsonValue,
+        now_micros: i64,
+    ) -> impl Iterator<Item = (&'static str, i64, (i64, i64))> + 'a {
+        BOUNDED_FIELDS.iter().filter_map(move |&(field, per_sec)| {
+            let value = record.get(field).and_then(JsonValue::as_i64)?;
+            if field == "start_timestamp" && value == 0 {
+                return None;
+            }
+            let (min, max) = self.window(now_micros, per_sec);
+            (!(min..=max).contains(&value)).then_some((field, value, (min, max)))
+        })
+    }
+
+    fn apply_at(&self, records: &mut Vec<JsonValue>, now_micros: i64) -> usize {
+        let before = records.len();
+        let mut kept_out_of_bounds = 0;
+        records.retain_mut(|record| {
+            let fields: Vec<_> = self.out_of_bounds(record, now_micros).collect();
+            if fields.is_empty() {
+                return true;
+            }
+            match self.action {
+                OutOfBoundsAction::Drop => return false,
+                OutOfBoundsAction::Clamp => {
+                    for (field, value, (min, max)) in fields {
+                        record[field] = value.clamp(min, max).into();
+                    }
+                }
+                // Already rejected by `check_strict`
+                OutOfBoundsAction::Count | OutOfBoundsAction::Reject => {}
+            }
+            kept_out_of_bounds += 1;
+            true
+        });
+
+        let affected = kept_out_of_bounds + (before - records.len());
+        if affected > 0 {
+            let (min, max) = self.window(now_micros, MICROS_PER_SEC);
+            warn!(
+                affected,
+                action = ?self.action,
+                min_micros = min,
+                max_micros = max,
+                "records with out-of-bounds timestamps"
+            );
+        }
+        affected
+    }
+}
+
+/// Get current time in microseconds since epoch.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn current_time_micros() -> i64 {
+    worker::Date::now().as_millis() as i64 * 1_000
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn current_time_micros() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_micros() as i64
+}
+
+impl TransformStage for TimestampBounds {
+    fn name(&self) -> &'static str {
+        "timestamp_bounds"
+    }
+
+    fn apply(&self, _: Signal, records: &mut Vec<JsonValue>, counts: &mut TransformCounts) {
+        counts.out_of_bounds_timestamps += TimestampBounds::apply(self, records);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 2024-01-15T14:10:00Z
+    const NOW_MICROS: i64 = 1_705_327_800_000_000;
+    /// 2286-11-20T17:46:39Z
+    const FAR_FUTURE_MICROS: i64 = 9_999_999_999_000_000;
+
+    fn records() -> Vec<JsonValue> {
+        vec![
+            json!({"timestamp": NOW_MICROS, "body": "ok"}),
+            json!({"timestamp": FAR_FUTURE_MICROS, "body": "future"}),
+            json!({"timestamp": 0, "body": "epoch"}),
+        ]
+    }
+
+    fn with_action(action: OutOfBoundsAction) -> TimestampBounds {
+        TimestampBounds {
+            action,
+            ..TimestampBounds::default()
+        }
+    }
+
+    #[test]
+    fn test_default_counts_without_rewriting() {
+        let mut records = records();
+
+        assert_eq!(
+            TimestampBounds::default().apply_at(&mut records, NOW_MICROS),
+            2
+        );
+        assert_eq!(records, self::records());
+    }
+
+    #[test]
+    fn test_clamps_out_of_bounds_timestamps() {
+        let bounds = with_action(OutOfBoundsAction::Clamp);
+        let mut records = records();
+
+        assert_eq!(bounds.apply_at(&mut records, NOW_MICROS), 2);
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0]["timestamp"], NOW_MICROS);
+        assert_eq!(
+            records[1]["timestamp"],
+            NOW_MICROS + DEFAULT_TIMESTAMP_MAX_FUTURE_SECS * MICROS_PER_SEC
+        );
+        assert_eq!(
+            records[2]["timestamp"],
+            DEFAULT_TIMESTAMP_MIN_UNIX_SECS * MICROS_PER_SEC
+        );
+    }
+
+    #[test]
+    fn test_drops_out_of_bounds_records() {
+        let bounds = with_action(OutOfBoundsAction::Drop);
+        let mut records = records();
+
+        assert_eq!(bounds.apply_at(&mut records, NOW_MICROS), 2);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["body"], "ok");
+    }
+
+    #[test]
+    fn test_records_without_timestamp_pass() {
+        let mut records = vec![json!({"body": "no time"})];
+        assert_eq!(
+            TimestampBounds::default().apply_at(&mut records, NOW_MICROS),
+            0
+        );
+        assert_eq!(records.len(), 1);
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_year_2286_timestamp() {
+        let future = vec![json!({"timestamp": FAR_FUTURE_MICROS, "body": "future"})];
+        let grouped = HashMap::from([("logs".to_string(), future.clone())]);
+        let strict = with_action(OutOfBoundsAction::Reject);
+
+        let err = strict.check_strict_at(&grouped, NOW_MICROS).unwrap_err();
+        assert!(err.contains("field 'timestamp'"), "{}", err);
+        assert!(err.contains(&FAR_FUTURE_MICROS.to_string()), "{}", err);
+
+        // Lenient mode never errors; it clamps instead
+        let lenient = with_action(OutOfBoundsAction::Clamp);
+        assert!(lenient.check_strict_at(&grouped, NOW_MICROS).is_ok());
+        let mut clamped = future;
+        assert_eq!(lenient.apply_at(&mut clamped, NOW_MICROS), 1);
+        assert!(clamped[0]["timestamp"].as_i64().unwrap() < FAR_FUTURE_MICROS);
+    }
+
+    #[test]
+    fn test_bounds_start_and_end_timestamps_in_millis() {
+        let now_millis = NOW_MICROS / 1_000;
+        let mut records = vec![
+            json!({"timestamp": NOW_MICROS, "start_timestamp": 0, "end_timestamp": now_millis}),
+            json!({"timestamp": NOW_MICROS, "start_timestamp": 1}),
+            json!({"timestamp": NOW_MICROS, "end_timestamp": FAR_FUTURE_MICROS / 1_000}),
+        ];
+        let bounds = with_action(OutOfBoundsAction::Clamp);
+
+        assert_eq!(bounds.apply_at(&mut records, NOW_MICROS), 2);
+        assert_eq!(records[0]["start_timestamp"], 0);
+        assert_eq!(records[0]["end_timestamp"], now_millis);
+        assert_eq!(
+            records[1]["start_timestamp"],
+            DEFAULT_TIMESTAMP_MIN_UNIX_SECS * 1_000
+        );
+        assert_eq!(
+            records[2]["end_timestamp"],
+            now_millis + DEFAULT_TIMESTAMP_MAX_FUTURE_SECS * 1_000
+        );
+
+        let grouped = HashMap::from([(
+            "spans".to_string(),
+            vec![json!({"timestamp": NOW_MICROS, "end_timestamp": FAR_FUTURE_MICROS})],
+        )]);
+        let err = with_action(OutOfBoundsAction::Reject)
+            .check_strict_at(&grouped, NOW_MICROS)
+            .unwrap_err();
+        assert!(err.contains("field 'end_timestamp'"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!("count".parse(), Ok(OutOfBoundsAction::Count));
+        assert_eq!("reject".parse(), Ok(OutOfBoundsAction::Reject));
+        assert_eq!("DROP".parse(), Ok(OutOfBoundsAction::Drop));
+        assert_eq!("clamp".parse(), Ok(OutOfBoundsAction::Clamp));
+        assert!("ignore".parse::<OutOfBoundsAction>().is_err());
+    }
+}
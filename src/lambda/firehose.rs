@@ -8,12 +8,16 @@ use std::collections::HashMap;
 use tracing::{debug, error, warn};
 
 use crate::pipeline::retry::RetryConfig;
-use crate::pipeline::{PipelineSender, SendResult};
+use crate::pipeline::{FailureReason, PipelineSender, SendFailure, SendResult};
 
 const MAX_RECORDS_PER_BATCH: usize = 500; // Firehose limit
+/// Firehose limit on a `PutRecordBatch` request's total record size
+const MAX_BATCH_BYTES: usize = 4 * 1024 * 1024;
+/// Firehose limit on a single record
+const MAX_RECORD_BYTES: usize = 1000 * 1024;
 
 /// Default retry configuration for Firehose operations.
-/// Uses exponential backoff with jitter (100ms base, 10s max, 3 attempts).
+/// Uses exponential backoff with full jitter (100ms base, 10s max, 3 attempts).
 fn default_retry_config() -> RetryConfig {
     RetryConfig::exponential(3, 100, 10_000)
 }
@@ -55,44 +59,146 @@ impl StreamConfig {
     }
 }
 
+/// `PutRecordBatch` outcome: one entry per record, `Some(error)` for records Firehose rejected
+pub type PutRecordBatchResult = Result<Vec<Option<String>>, String>;
+
+/// The Firehose API calls the sender needs, so tests can stand in for AWS
+#[async_trait::async_trait]
+pub trait FirehoseApi: Send + Sync {
+    /// Put one batch of NDJSON records to `stream`
+    async fn put_record_batch(&self, stream: &str, records: Vec<Vec<u8>>) -> PutRecordBatchResult;
+}
+
+#[async_trait::async_trait]
+impl FirehoseApi for AwsClient {
+    async fn put_record_batch(&self, stream: &str, records: Vec<Vec<u8>>) -> PutRecordBatchResult {
+        let records = records
+            .into_iter()
+            .map(|data| {
+                Record::builder()
+                    .data(aws_sdk_firehose::primitives::Blob::new(data))
+                    .build()
+                    .map_err(|e| format!("Record build failed: {e}"))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let response = self
+            .put_record_batch()
+            .delivery_stream_name(stream)
+            .set_records(Some(records))
+            .send()
+            .await
+            .map_err(|e| {
+                // Log detailed error info including request ID for AWS support
+                let request_id = e.meta().request_id().unwrap_or("unknown");
+                error!(
+                    error = %e,
+                    request_id = request_id,
+                    stream = stream,
+                    "Firehose API call failed"
+                );
+                e.to_string()
+            })?;
+
+        Ok(response
+            .request_responses()
+            .iter()
+            .map(|resp| {
+                resp.error_code()
+                    .map(|code| format!("{}: {}", code, resp.error_message().unwrap_or("none")))
+            })
+            .collect())
+    }
+}
+
+/// Serialize records as NDJSON lines and split them into `PutRecordBatch`
+/// calls within Firehose's record-count and byte limits
+fn build_batches(records: &[Value]) -> Result<Vec<Vec<Vec<u8>>>, String> {
+    let mut batches = Vec::new();
+    let mut batch: Vec<Vec<u8>> = Vec::new();
+    let mut batch_bytes = 0;
+    for record in records {
+        let mut data =
+            serde_json::to_vec(record).map_err(|e| format!("JSON serialization failed: {e}"))?;
+        data.push(b'\n'); // NDJSON format
+        if data.len() > MAX_RECORD_BYTES {
+            return Err(format!(
+                "record of {} bytes exceeds Firehose's {} byte record limit",
+                data.len(),
+                MAX_RECORD_BYTES
+            ));
+        }
+        if batch.len() == MAX_RECORDS_PER_BATCH || batch_bytes + data.len() > MAX_BATCH_BYTES {
+            batches.push(std::mem::take(&mut batch));
+            batch_bytes = 0;
+        }
+        batch_bytes += data.len();
+        batch.push(data);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    Ok(batches)
+}
+
+/// Records delivered and lost for one table
+#[derive(Debug, Default, PartialEq)]
+struct StreamOutcome {
+    succeeded: usize,
+    failed: usize,
+    /// Last error seen for the failed records
+    error: Option<String>,
+}
+
 /// Firehose client that implements PipelineSender.
-pub struct FirehoseSender {
-    client: AwsClient,
+pub struct FirehoseSender<C = AwsClient> {
+    client: C,
     streams: StreamConfig,
+    retry: RetryConfig,
 }
 
 impl FirehoseSender {
     /// Create a new FirehoseSender from AWS config.
     pub async fn new(streams: StreamConfig) -> Self {
         let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        Self::with_client(AwsClient::new(&config), streams)
+    }
+}
+
+impl<C: FirehoseApi> FirehoseSender<C> {
+    /// Create a sender around any Firehose API implementation
+    pub fn with_client(client: C, streams: StreamConfig) -> Self {
         Self {
-            client: AwsClient::new(&config),
+            client,
             streams,
+            retry: default_retry_config(),
         }
     }
 
     /// Send records to a single Firehose stream with retry.
     /// Retries both API-level errors (throttling, network) and partial failures.
-    async fn send_to_stream(
-        &self,
-        stream_name: &str,
-        records: Vec<Value>,
-    ) -> Result<usize, String> {
-        let retry_config = default_retry_config();
-        let max_attempts = retry_config.max_attempts;
-        let mut total_succeeded = 0;
-        let mut final_failed: Vec<Value> = Vec::new();
-
-        for chunk in records.chunks(MAX_RECORDS_PER_BATCH) {
-            let mut pending: Vec<Value> = chunk.to_vec();
+    async fn send_to_stream(&self, stream_name: &str, records: &[Value]) -> StreamOutcome {
+        let batches = match build_batches(records) {
+            Ok(batches) => batches,
+            Err(e) => {
+                return StreamOutcome {
+                    failed: records.len(),
+                    error: Some(e),
+                    ..StreamOutcome::default()
+                }
+            }
+        };
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut outcome = StreamOutcome::default();
 
+        for batch in batches {
+            let mut pending = batch;
             for attempt in 0..max_attempts {
                 if pending.is_empty() {
                     break;
                 }
-
                 if attempt > 0 {
-                    let delay = retry_config.delay_for_attempt(attempt - 1);
+                    let delay = self.retry.delay_for_attempt(attempt - 1);
                     debug!(
                         attempt,
                         delay_ms = delay.as_millis() as u64,
@@ -101,115 +207,60 @@ impl FirehoseSender {
                     tokio::time::sleep(delay).await;
                 }
 
-                // Convert to Firehose records (one JSON object per record, newline-delimited)
-                // Fail fast if any record cannot be serialized to maintain 1:1 correspondence
-                let firehose_records: Vec<Record> = pending
-                    .iter()
-                    .map(|json_obj| {
-                        let mut data = serde_json::to_vec(json_obj)
-                            .map_err(|e| format!("JSON serialization failed: {e}"))?;
-                        data.push(b'\n'); // NDJSON format
-                        Record::builder()
-                            .data(aws_sdk_firehose::primitives::Blob::new(data))
-                            .build()
-                            .map_err(|e| format!("Record build failed: {e}"))
-                    })
-                    .collect::<Result<Vec<_>, String>>()?;
-
-                let response = match self
+                let statuses = match self
                     .client
-                    .put_record_batch()
-                    .delivery_stream_name(stream_name)
-                    .set_records(Some(firehose_records))
-                    .send()
+                    .put_record_batch(stream_name, pending.clone())
                     .await
                 {
-                    Ok(resp) => resp,
+                    Ok(statuses) => statuses,
                     Err(e) => {
-                        // Log detailed error info including request ID for AWS support
-                        let request_id = e.meta().request_id().unwrap_or("unknown");
-                        error!(
-                            error = %e,
-                            request_id = request_id,
-                            stream = stream_name,
-                            attempt = attempt,
-                            "Firehose API call failed"
-                        );
                         // Retry API-level errors (throttling, network issues)
-                        if attempt + 1 < max_attempts {
-                            warn!(
-                                attempt,
-                                stream = stream_name,
-                                "Firehose API error, will retry"
-                            );
-                            continue;
-                        }
-                        return Err(format!(
-                            "Firehose API error after {} attempts: {}",
-                            max_attempts, e
-                        ));
+                        warn!(attempt, stream = stream_name, error = %e, "Firehose API error");
+                        outcome.error = Some(format!("Firehose API error: {}", e));
+                        continue;
                     }
                 };
 
-                let failed_count = response.failed_put_count();
-                if failed_count == 0 {
-                    total_succeeded += pending.len();
-                    pending.clear();
-                } else {
-                    // Extract failed records for retry, logging first error for debugging
-                    let mut new_pending = Vec::new();
-                    let mut first_error_logged = false;
-                    for (resp, record) in response.request_responses().iter().zip(pending.drain(..))
-                    {
-                        if resp.error_code().is_some() {
-                            // Log first error code/message for debugging
-                            if !first_error_logged {
-                                warn!(
-                                    error_code = resp.error_code().unwrap_or("unknown"),
-                                    error_message = resp.error_message().unwrap_or("none"),
-                                    stream = stream_name,
-                                    "Firehose record failure"
-                                );
-                                first_error_logged = true;
+                // Keep failed records for the next attempt, logging the first error
+                let mut retry = Vec::new();
+                for (status, record) in statuses.into_iter().zip(pending.drain(..)) {
+                    match status {
+                        Some(e) => {
+                            if retry.is_empty() {
+                                warn!(stream = stream_name, error = %e, "Firehose record failure");
                             }
-                            new_pending.push(record);
-                        } else {
-                            total_succeeded += 1;
+                            outcome.error = Some(format!("Firehose record failure: {}", e));
+                            retry.push(record);
                         }
+                        None => outcome.succeeded += 1,
                     }
-                    pending = new_pending;
-
+                }
+                if !retry.is_empty() {
                     warn!(
                         attempt,
-                        failed = pending.len(),
+                        failed = retry.len(),
                         stream = stream_name,
                         "Firehose partial failure, retrying"
                     );
                 }
+                pending = retry;
             }
-
-            // Any remaining pending records go to final_failed
-            final_failed.extend(pending);
+            outcome.failed += pending.len();
         }
 
-        if !final_failed.is_empty() {
+        if outcome.failed > 0 {
             error!(
-                failed_count = final_failed.len(),
+                failed_count = outcome.failed,
                 stream = stream_name,
                 "Records failed after retry exhaustion"
             );
-            return Err(format!(
-                "{} records failed after retries",
-                final_failed.len()
-            ));
         }
-
-        Ok(total_succeeded)
+        outcome
     }
 }
 
 #[async_trait::async_trait]
-impl PipelineSender for FirehoseSender {
+impl<C: FirehoseApi> PipelineSender for FirehoseSender<C> {
     async fn send_all(&self, grouped: HashMap<String, Vec<Value>>) -> SendResult {
         let mut result = SendResult::default();
 
@@ -218,23 +269,148 @@ impl PipelineSender for FirehoseSender {
                 Some(name) => name,
                 None => {
                     warn!(table = %table, "no stream configured for table");
-                    result
-                        .failed
-                        .insert(table, "no stream configured".to_string());
+                    result.failed.insert(
+                        table,
+                        SendFailure::new(FailureReason::NoEndpoint, "no stream configured"),
+                    );
                     continue;
                 }
             };
 
-            match self.send_to_stream(stream_name, records).await {
-                Ok(count) => {
-                    result.succeeded.insert(table, count);
-                }
-                Err(e) => {
-                    result.failed.insert(table, e);
-                }
+            let outcome = self.send_to_stream(stream_name, &records).await;
+            if outcome.succeeded > 0 {
+                result.succeeded.insert(table.clone(), outcome.succeeded);
+            }
+            if outcome.failed > 0 {
+                let message = format!(
+                    "{} of {} records failed after retries: {}",
+                    outcome.failed,
+                    records.len(),
+                    outcome.error.unwrap_or_default()
+                );
+                result
+                    .failed
+                    .insert(table, SendFailure::new(FailureReason::Network, message));
             }
         }
 
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::retry::BackoffStrategy;
+    use serde_json::json;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    /// Records batch sizes; fails the record at `fail_index` of each call
+    /// while `failing_calls` remain, or every call with an API error when `api_down`
+    #[derive(Default)]
+    struct MockFirehose {
+        calls: Mutex<Vec<usize>>,
+        fail_index: Option<usize>,
+        failing_calls: Mutex<usize>,
+        api_down: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl FirehoseApi for MockFirehose {
+        async fn put_record_batch(&self, _: &str, records: Vec<Vec<u8>>) -> PutRecordBatchResult {
+            self.calls.lock().unwrap().push(records.len());
+            if self.api_down {
+                return Err("ServiceUnavailableException".to_string());
+            }
+            let mut failing = self.failing_calls.lock().unwrap();
+            let fail = self.fail_index.filter(|_| *failing > 0);
+            *failing = failing.saturating_sub(1);
+            Ok((0..records.len())
+                .map(|i| (Some(i) == fail).then(|| "ServiceUnavailableException: slow down".into()))
+                .collect())
+        }
+    }
+
+    fn streams() -> StreamConfig {
+        StreamConfig {
+            logs: "logs-stream".to_string(),
+            traces: "traces-stream".to_string(),
+            sum: "sum-stream".to_string(),
+            gauge: "gauge-stream".to_string(),
+        }
+    }
+
+    fn sender(mock: MockFirehose) -> FirehoseSender<MockFirehose> {
+        let mut sender = FirehoseSender::with_client(mock, streams());
+        sender.retry = RetryConfig {
+            delay: Duration::from_millis(1),
+            backoff: BackoffStrategy::Fixed,
+            ..RetryConfig::default()
+        };
+        sender
+    }
+
+    fn logs(count: usize) -> HashMap<String, Vec<Value>> {
+        let records = (0..count).map(|i| json!({"i": i})).collect();
+        HashMap::from([("logs".to_string(), records)])
+    }
+
+    #[tokio::test]
+    async fn test_batches_split_at_500_records() {
+        let sender = sender(MockFirehose::default());
+        let result = sender.send_all(logs(1_001)).await;
+
+        assert_eq!(result.succeeded["logs"], 1_001);
+        assert!(result.failed.is_empty());
+        assert_eq!(*sender.client.calls.lock().unwrap(), vec![500, 500, 1]);
+    }
+
+    #[test]
+    fn test_batches_split_at_4mb() {
+        let big = json!({"body": "x".repeat(900 * 1024)});
+        let batches = build_batches(&vec![big; 5]).unwrap();
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![4, 1]);
+
+        let huge = json!({"body": "x".repeat(MAX_RECORD_BYTES)});
+        assert!(build_batches(&[huge]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_partial_failure_retried_then_counted() {
+        let retried = sender(MockFirehose {
+            fail_index: Some(3),
+            failing_calls: Mutex::new(1),
+            ..MockFirehose::default()
+        });
+        let result = retried.send_all(logs(10)).await;
+        assert_eq!(result.succeeded["logs"], 10);
+        assert_eq!(*retried.client.calls.lock().unwrap(), vec![10, 1]);
+
+        let exhausted = sender(MockFirehose {
+            fail_index: Some(0),
+            failing_calls: Mutex::new(usize::MAX),
+            ..MockFirehose::default()
+        });
+        let result = exhausted.send_all(logs(10)).await;
+        assert_eq!(result.succeeded["logs"], 9);
+        let failure = &result.failed["logs"];
+        assert!(
+            failure.message.starts_with("1 of 10 records failed"),
+            "{}",
+            failure
+        );
+    }
+
+    #[tokio::test]
+    async fn test_api_errors_fail_whole_table() {
+        let sender = sender(MockFirehose {
+            api_down: true,
+            ..MockFirehose::default()
+        });
+        let result = sender.send_all(logs(3)).await;
+        assert!(!result.succeeded.contains_key("logs"));
+        assert_eq!(result.failed["logs"].reason, FailureReason::Network);
+        assert_eq!(sender.client.calls.lock().unwrap().len(), 3);
+    }
+}
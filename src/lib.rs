@@ -8,11 +8,18 @@ pub use bytes::Bytes;
 pub use otlp2records::decode::InputFormat;
 
 pub mod aggregator;
+#[cfg(not(target_arch = "wasm32"))]
+mod debug_events;
 mod handler;
 pub mod livetail;
 mod pipeline;
 pub mod registry;
+mod request_id;
 mod schema;
+#[cfg(not(target_arch = "wasm32"))]
+mod schema_json;
+#[cfg(not(target_arch = "wasm32"))]
+mod server_limits;
 mod signal;
 
 pub use signal::Signal;
@@ -34,17 +41,25 @@ pub mod azure;
 
 // Re-export for tests
 pub use handler::{
-    handle_signal, HandleError, HandleResponse, LogsHandler, MetricsHandler, SignalHandler,
-    SkippedMetricsWarning, TracesHandler,
+    decompress_body, handle_signal, run_stages, verbose_requested, BodyFormat, Compression,
+    HandleError, HandleResponse, HandlerConfig, LogsHandler, MetricsHandler, OutOfBoundsAction,
+    RedactionConfig, ResponseWarning, SeverityFilter, SignalHandler, SkippedMetricsWarning,
+    StageKind, TimestampBounds, TracesHandler, TransformStage,
 };
-pub use pipeline::{PipelineSender, SendResult};
-
-fn parse_content_metadata(mut header: impl FnMut(&str) -> Option<String>) -> (bool, InputFormat) {
-    let is_gzipped = header("content-encoding")
-        .map(|v| v.eq_ignore_ascii_case("gzip"))
-        .unwrap_or(false);
-    let decode_format = InputFormat::from_content_type(header("content-type").as_deref());
-    (is_gzipped, decode_format)
+pub use pipeline::{FailureReason, PipelineSender, SendFailure, SendResult};
+pub use request_id::{error_with_request_id, resolve_request_id, REQUEST_ID_HEADER};
+
+fn parse_content_metadata(
+    mut header: impl FnMut(&str) -> Option<String>,
+) -> (Compression, BodyFormat) {
+    let compression = Compression::from_content_encoding(header("content-encoding").as_deref());
+    let decode_format = BodyFormat::from_content_type(header("content-type").as_deref());
+    (compression, decode_format)
+}
+
+/// Parse a trimmed config value, falling back to `default` when unset or invalid
+pub(crate) fn parse_or<T: std::str::FromStr>(value: Option<String>, default: T) -> T {
+    value.and_then(|v| v.trim().parse().ok()).unwrap_or(default)
 }
 
 #[cfg(target_arch = "wasm32")]
@@ -56,5 +71,9 @@ mod wasm;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod native;
 
+#[cfg(all(unix, not(target_arch = "wasm32")))]
+pub use native::{bind_uds, serve_uds};
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::{build_router, serve_router};
 #[cfg(not(target_arch = "wasm32"))]
-pub use native::build_router;
+pub use server_limits::ServerLimits;
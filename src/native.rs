@@ -1,8 +1,8 @@
 use axum::{
     body::Bytes as AxumBytes,
-    extract::State,
-    http::{HeaderMap, StatusCode},
-    response::Json,
+    extract::{DefaultBodyLimit, RawQuery, State},
+    http::{HeaderMap, HeaderValue, Method, StatusCode, Uri},
+    response::{IntoResponse, Json, Response},
     routing::{get, post},
     Router,
 };
@@ -10,14 +10,25 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tracing_subscriber::{fmt, prelude::*, EnvFilter};
 
+use crate::debug_events::{debug_enabled, DebugEvents, RecordingSender, DEBUG_EVENTS_CAPACITY};
 use crate::handler::{
-    handle_signal, HandleResponse, LogsHandler, MetricsHandler, SignalHandler, TracesHandler,
+    handle_signal, verbose_requested, Compression, HandleError, HandlerConfig, LogsHandler,
+    MetricsHandler, SignalHandler, TracesHandler,
 };
 use crate::parse_content_metadata;
-use crate::pipeline::PipelineClient;
+use crate::pipeline::body_size::max_body_sizes_from_lookup;
+use crate::pipeline::compression::gzip_from;
+use crate::pipeline::send_guard::send_deadline_from;
+use crate::pipeline::sequence::sequence_headers_from;
+use crate::pipeline::signal_policy::SendPolicies;
+use crate::pipeline::{
+    headers_from_vars, reject_invalid_from, skip_validation_from, PipelineClient,
+};
+use crate::request_id::{error_with_request_id, resolve_request_id, REQUEST_ID_HEADER};
+use crate::server_limits::ServerLimits;
 use crate::signal::Signal;
+use crate::BodyFormat;
 use crate::Bytes;
-use crate::InputFormat;
 
 /// Initialize tracing subscriber for native (non-WASM) builds.
 /// Uses RUST_LOG env var for filtering (defaults to info).
@@ -35,13 +46,57 @@ pub fn build_router(pipeline_url: String) -> Router {
     endpoints.insert(Signal::Logs, pipeline_url.clone());
     endpoints.insert(Signal::Traces, pipeline_url.clone());
     endpoints.insert(Signal::Gauge, pipeline_url.clone());
-    endpoints.insert(Signal::Sum, pipeline_url);
+    endpoints.insert(Signal::Sum, pipeline_url.clone());
+
+    // Scope-routed and span link tables share the single pipeline URL
+    let config = HandlerConfig::from_env();
+    let table_endpoints = config
+        .extra_table_names()
+        .into_iter()
+        .map(|table| (table, pipeline_url.clone()))
+        .collect();
 
     let client = Arc::new(
         PipelineClient::new(endpoints, "test-token".to_string())
-            .expect("failed to create pipeline client"),
+            .expect("failed to create pipeline client")
+            .with_table_endpoints(table_endpoints)
+            .with_batch_mode(
+                std::env::var("PIPELINE_BATCH_MODE")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or_default(),
+            )
+            .with_auth_scheme(
+                std::env::var("PIPELINE_AUTH_SCHEME")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or_default(),
+            )
+            .with_send_deadline(send_deadline_from(
+                std::env::var("PIPELINE_SEND_DEADLINE_MS").ok(),
+            ))
+            .with_skip_schema_validation(skip_validation_from(
+                std::env::var("SKIP_SCHEMA_VALIDATION").ok(),
+            ))
+            .with_checksum_mode(
+                std::env::var("PIPELINE_CHECKSUM")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or_default(),
+            )
+            .with_send_policies(SendPolicies::from_lookup(|name| std::env::var(name).ok()))
+            .with_max_body_sizes(max_body_sizes_from_lookup(|name| std::env::var(name).ok()))
+            .with_reject_invalid_records(reject_invalid_from(
+                std::env::var("PIPELINE_REJECT_INVALID_RECORDS").ok(),
+            ))
+            .with_sequence_headers(sequence_headers_from(
+                std::env::var("PIPELINE_SEQUENCE_HEADERS").ok(),
+            ))
+            .with_gzip(gzip_from(std::env::var("PIPELINE_GZIP").ok()))
+            .with_extra_headers(headers_from_vars(std::env::vars()))
+            .expect("invalid PIPELINE_HEADER_* configuration"),
     );
-    build_router_with_client(client)
+    build_router_with_client(client, config)
 }
 
 pub fn build_router_multi(endpoints: std::collections::HashMap<Signal, String>) -> Router {
@@ -49,61 +104,219 @@ pub fn build_router_multi(endpoints: std::collections::HashMap<Signal, String>)
         PipelineClient::new(endpoints, "test-token".to_string())
             .expect("failed to create pipeline client"),
     );
-    build_router_with_client(client)
+    build_router_with_client(client, HandlerConfig::from_env())
+}
+
+/// Bind a Unix domain socket at `path`, replacing a stale socket file.
+/// Access is controlled by the socket file's permissions.
+#[cfg(unix)]
+pub fn bind_uds(path: impl AsRef<std::path::Path>) -> std::io::Result<tokio::net::UnixListener> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let path = path.as_ref();
+    // Only ever remove a socket, never a regular file at a mistyped path
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if metadata.file_type().is_socket() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    tokio::net::UnixListener::bind(path)
+}
+
+/// Serve `router` on a Unix domain socket, like `axum::serve` does for TCP.
+/// Runs until accepting a connection fails.
+#[cfg(unix)]
+pub async fn serve_uds(listener: tokio::net::UnixListener, router: Router) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        spawn_connection(stream, router.clone(), true);
+    }
+}
+
+/// Serve `router` over TCP like `axum::serve`, honoring `limits.keep_alive`.
+/// Runs until accepting a connection fails.
+pub async fn serve_router(
+    listener: tokio::net::TcpListener,
+    router: Router,
+    limits: &ServerLimits,
+) -> std::io::Result<()> {
+    loop {
+        let (stream, _) = listener.accept().await?;
+        spawn_connection(stream, router.clone(), limits.keep_alive);
+    }
+}
+
+/// Serve one accepted connection in the background
+fn spawn_connection<IO>(stream: IO, router: Router, keep_alive: bool)
+where
+    IO: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin + Send + 'static,
+{
+    use hyper_util::rt::{TokioExecutor, TokioIo};
+    use hyper_util::server::conn::auto::Builder;
+    use hyper_util::service::TowerToHyperService;
+
+    let service = TowerToHyperService::new(router);
+    tokio::spawn(async move {
+        let mut builder = Builder::new(TokioExecutor::new());
+        builder.http1().keep_alive(keep_alive);
+        if let Err(e) = builder
+            .serve_connection(TokioIo::new(stream), service)
+            .await
+        {
+            tracing::debug!(error = %e, "connection error");
+        }
+    });
+}
+
+/// Shared state for the native router
+#[derive(Clone)]
+struct RouterState {
+    client: Arc<PipelineClient>,
+    config: Arc<HandlerConfig>,
+    /// Recently sent records, when `OTLP2PIPELINE_DEBUG` is enabled
+    debug_events: Option<Arc<DebugEvents>>,
 }
 
-fn build_router_with_client(client: Arc<PipelineClient>) -> Router {
-    Router::new()
+fn build_router_with_client(client: Arc<PipelineClient>, config: HandlerConfig) -> Router {
+    let debug_events = debug_enabled(std::env::var("OTLP2PIPELINE_DEBUG").ok())
+        .then(|| Arc::new(DebugEvents::new(DEBUG_EVENTS_CAPACITY)));
+    // Oversized bodies get 413 from the extractor, before any decompression
+    let body_limit = DefaultBodyLimit::max(config.max_request_size);
+    let state = RouterState {
+        client,
+        config: Arc::new(config),
+        debug_events: debug_events.clone(),
+    };
+    let signal_routes = Router::new()
         .route("/v1/logs", post(handle_logs_axum))
         .route("/v1/traces", post(handle_traces_axum))
         .route("/v1/metrics", post(handle_metrics_axum))
-        .route("/health", get(|| async { "ok" }))
-        .with_state(client)
+        .fallback(handle_any_axum)
+        .layer(body_limit);
+
+    // Reverse proxies may mount ingest under a prefix (e.g. `/otlp/v1/logs`)
+    let router = match base_path(std::env::var("INGEST_BASE_PATH").ok().as_deref()) {
+        Some(prefix) => Router::new().nest(&prefix, signal_routes),
+        None => signal_routes,
+    };
+    let router = match debug_events {
+        Some(events) => router.route(
+            "/debug/events",
+            get(move || async move { Json(events.snapshot()) }),
+        ),
+        None => router,
+    };
+    let router = router.route("/health", get(|| async { "ok" })).route(
+        "/v1/pipelines/health",
+        get(|| async { Json(crate::pipeline::health::snapshot()) }),
+    );
+    ServerLimits::from_lookup(|name| std::env::var(name).ok())
+        .apply(router)
+        .with_state(state)
+}
+
+/// Normalize a configured base path to `/prefix`, or `None` for root
+fn base_path(configured: Option<&str>) -> Option<String> {
+    let trimmed = configured?.trim().trim_matches('/');
+    (!trimmed.is_empty()).then(|| format!("/{}", trimmed))
 }
 
 async fn handle_axum_signal<H: SignalHandler>(
     headers: HeaderMap,
+    query: Option<String>,
     body: AxumBytes,
-    client: &PipelineClient,
-) -> Result<Json<HandleResponse>, (StatusCode, String)> {
-    let (is_gzipped, decode_format) = parse_axum_headers(&headers);
+    state: &RouterState,
+) -> Response {
+    let (compression, decode_format) = parse_axum_headers(&headers);
+    let request_id =
+        resolve_request_id(headers.get(REQUEST_ID_HEADER).and_then(|v| v.to_str().ok()));
 
-    handle_signal::<H, _>(
+    let result = handle_signal::<H, _>(
         Bytes::from(body.to_vec()),
-        is_gzipped,
+        compression,
         decode_format,
-        client,
+        &state.config,
+        &request_id,
+        &RecordingSender {
+            inner: state.client.as_ref(),
+            events: state.debug_events.as_deref(),
+        },
     )
-    .await
-    .map(Json)
-    .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))
+    .await;
+
+    let mut response = match result {
+        Ok(resp) if resp.is_no_content(&state.config) => StatusCode::NO_CONTENT.into_response(),
+        Ok(resp) => Json(resp.for_verbosity(verbose_requested(query.as_deref()))).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            error_with_request_id(&e.to_string(), &request_id),
+        )
+            .into_response(),
+    };
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
 }
 
 async fn handle_logs_axum(
-    State(client): State<Arc<PipelineClient>>,
+    State(state): State<RouterState>,
+    RawQuery(query): RawQuery,
     headers: HeaderMap,
     body: AxumBytes,
-) -> Result<Json<HandleResponse>, (StatusCode, String)> {
-    handle_axum_signal::<LogsHandler>(headers, body, client.as_ref()).await
+) -> Response {
+    handle_axum_signal::<LogsHandler>(headers, query, body, &state).await
 }
 
 async fn handle_traces_axum(
-    State(client): State<Arc<PipelineClient>>,
+    State(state): State<RouterState>,
+    RawQuery(query): RawQuery,
     headers: HeaderMap,
     body: AxumBytes,
-) -> Result<Json<HandleResponse>, (StatusCode, String)> {
-    handle_axum_signal::<TracesHandler>(headers, body, client.as_ref()).await
+) -> Response {
+    handle_axum_signal::<TracesHandler>(headers, query, body, &state).await
 }
 
 async fn handle_metrics_axum(
-    State(client): State<Arc<PipelineClient>>,
+    State(state): State<RouterState>,
+    RawQuery(query): RawQuery,
     headers: HeaderMap,
     body: AxumBytes,
-) -> Result<Json<HandleResponse>, (StatusCode, String)> {
-    handle_axum_signal::<MetricsHandler>(headers, body, client.as_ref()).await
+) -> Response {
+    handle_axum_signal::<MetricsHandler>(headers, query, body, &state).await
 }
 
-fn parse_axum_headers(headers: &HeaderMap) -> (bool, InputFormat) {
+/// Any other POST is dispatched on the signal in its URL (`?signal=logs` or a
+/// path ending in `/v1/logs`); unknown signals get a 404
+async fn handle_any_axum(
+    State(state): State<RouterState>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: AxumBytes,
+) -> Response {
+    let query = uri.query().map(str::to_string);
+    let signal = uri
+        .path_and_query()
+        .and_then(|path| Signal::from_path(path.as_str()));
+    match (method, signal) {
+        (Method::POST, Some(Signal::Logs)) => {
+            handle_axum_signal::<LogsHandler>(headers, query, body, &state).await
+        }
+        (Method::POST, Some(Signal::Traces)) => {
+            handle_axum_signal::<TracesHandler>(headers, query, body, &state).await
+        }
+        (Method::POST, Some(_)) => {
+            handle_axum_signal::<MetricsHandler>(headers, query, body, &state).await
+        }
+        _ => {
+            let error = HandleError::Decode(format!("no signal for path {}", uri.path()));
+            (StatusCode::NOT_FOUND, error.to_string()).into_response()
+        }
+    }
+}
+
+fn parse_axum_headers(headers: &HeaderMap) -> (Compression, BodyFormat) {
     parse_content_metadata(|name| {
         headers
             .get(name)
@@ -111,3 +324,18 @@ fn parse_axum_headers(headers: &HeaderMap) -> (bool, InputFormat) {
             .map(|s| s.to_string())
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base_path_normalization() {
+        assert_eq!(base_path(None), None);
+        assert_eq!(base_path(Some("")), None);
+        assert_eq!(base_path(Some("/")), None);
+        assert_eq!(base_path(Some("otlp")), Some("/otlp".to_string()));
+        assert_eq!(base_path(Some(" /otlp/ ")), Some("/otlp".to_string()));
+        assert_eq!(base_path(Some("/a/b/")), Some("/a/b".to_string()));
+    }
+}
@@ -0,0 +1,92 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+
+/// How the pipeline auth token is sent with each request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AuthScheme {
+    /// `Authorization: Bearer {token}`
+    #[default]
+    Bearer,
+    /// `Authorization: Basic base64({token})`, with the token as `user:password`
+    Basic,
+    /// `{name}: {token}`, for pipelines that take an API key header
+    Header { name: String },
+}
+
+impl AuthScheme {
+    /// Header name and value carrying `token` under this scheme
+    pub(crate) fn header(&self, token: &str) -> (&str, String) {
+        match self {
+            AuthScheme::Bearer => ("Authorization", format!("Bearer {}", token)),
+            AuthScheme::Basic => ("Authorization", format!("Basic {}", BASE64.encode(token))),
+            AuthScheme::Header { name } => (name, token.to_string()),
+        }
+    }
+}
+
+impl std::str::FromStr for AuthScheme {
+    type Err = String;
+
+    /// Parse `bearer`, `basic`, or `header:<name>`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some((scheme, name)) = s.split_once(':') {
+            let name = name.trim();
+            if scheme.trim().eq_ignore_ascii_case("header") && !name.is_empty() {
+                return Ok(AuthScheme::Header {
+                    name: name.to_string(),
+                });
+            }
+        }
+        match s.to_ascii_lowercase().as_str() {
+            "bearer" => Ok(AuthScheme::Bearer),
+            "basic" => Ok(AuthScheme::Basic),
+            other => Err(format!(
+                "unknown auth scheme '{}' (expected bearer, basic, or header:<name>)",
+                other
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bearer_header() {
+        assert_eq!(
+            AuthScheme::Bearer.header("secret"),
+            ("Authorization", "Bearer secret".to_string())
+        );
+    }
+
+    #[test]
+    fn basic_header() {
+        assert_eq!(
+            AuthScheme::Basic.header("user:pass"),
+            ("Authorization", "Basic dXNlcjpwYXNz".to_string())
+        );
+    }
+
+    #[test]
+    fn custom_header() {
+        let scheme = AuthScheme::Header {
+            name: "X-Api-Key".to_string(),
+        };
+        assert_eq!(scheme.header("secret"), ("X-Api-Key", "secret".to_string()));
+    }
+
+    #[test]
+    fn parse_schemes() {
+        assert_eq!("Bearer".parse(), Ok(AuthScheme::Bearer));
+        assert_eq!("basic".parse(), Ok(AuthScheme::Basic));
+        assert_eq!(
+            "header: X-Api-Key".parse(),
+            Ok(AuthScheme::Header {
+                name: "X-Api-Key".to_string()
+            })
+        );
+        assert!("header:".parse::<AuthScheme>().is_err());
+        assert!("digest".parse::<AuthScheme>().is_err());
+    }
+}
@@ -0,0 +1,453 @@
+use crate::pipeline::error::SendError;
+use crate::schema::get_schema;
+use bytes::{BufMut, Bytes, BytesMut};
+use serde_json::Value as JsonValue;
+use std::collections::HashSet;
+use tracing::warn;
+
+/// How records are packed into pipeline requests
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BatchMode {
+    /// Newline-delimited records, split only to stay under the body size limit
+    #[default]
+    Ndjson,
+    /// One request per record, for pipelines with strict per-record semantics
+    PerRecord,
+}
+
+impl std::str::FromStr for BatchMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "ndjson" => Ok(BatchMode::Ndjson),
+            "per_record" => Ok(BatchMode::PerRecord),
+            other => Err(format!(
+                "unknown batch mode '{}' (expected ndjson or per_record)",
+                other
+            )),
+        }
+    }
+}
+
+/// Validate a record against its schema before sending.
+/// Uses centralized schema definitions from crate::schema.
+pub(crate) fn validate_record_schema(
+    json: &JsonValue,
+    table: &str,
+    idx: usize,
+) -> Result<(), SendError> {
+    if let Some(schema) = get_schema(table) {
+        schema.validate(json, idx).map_err(SendError::Serialize)?;
+    }
+    Ok(())
+}
+
+/// Parse `SKIP_SCHEMA_VALIDATION` (comma-separated table names)
+pub(crate) fn skip_validation_from(value: Option<String>) -> HashSet<String> {
+    let tables: HashSet<String> = value
+        .unwrap_or_default()
+        .split(',')
+        .map(|table| table.trim().to_string())
+        .filter(|table| !table.is_empty())
+        .collect();
+    if !tables.is_empty() {
+        warn!(
+            ?tables,
+            "SKIP_SCHEMA_VALIDATION is set: records for these tables are sent unvalidated"
+        );
+    }
+    tables
+}
+
+/// Parse `PIPELINE_REJECT_INVALID_RECORDS`; unset or invalid fails whole tables instead
+pub(crate) fn reject_invalid_from(value: Option<String>) -> bool {
+    value.and_then(|v| v.trim().parse().ok()).unwrap_or(false)
+}
+
+/// Split out records that fail the table schema, keeping the rest in order.
+/// Returns the valid records and one reason per rejected record.
+pub(crate) fn partition_by_schema(
+    records: Vec<JsonValue>,
+    table: &str,
+) -> (Vec<JsonValue>, Vec<String>) {
+    let Some(schema) = get_schema(table) else {
+        return (records, Vec::new());
+    };
+    let mut reasons = Vec::new();
+    let valid = records
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, record)| match schema.validate(&record, idx) {
+            Ok(()) => Some(record),
+            Err(reason) => {
+                reasons.push(reason);
+                None
+            }
+        })
+        .collect();
+    (valid, reasons)
+}
+
+/// Validate every record against the table schema, unless `validate` is false.
+/// Runs before any body is built so a bad record fails the table before anything is sent.
+pub(crate) fn validate_records(
+    records: &[JsonValue],
+    table: &str,
+    validate: bool,
+) -> Result<(), SendError> {
+    if !validate {
+        if get_schema(table).is_some() {
+            warn!(
+                table,
+                records = records.len(),
+                "schema validation skipped (SKIP_SCHEMA_VALIDATION)"
+            );
+        }
+        return Ok(());
+    }
+    records
+        .iter()
+        .enumerate()
+        .try_for_each(|(idx, record)| validate_record_schema(record, table, idx))
+}
+
+/// Lazily packs records into NDJSON bodies, one body per `next()`
+pub(crate) struct NdjsonBatches<'a> {
+    records: std::slice::Iter<'a, JsonValue>,
+    max_size: usize,
+    /// Serialized record that didn't fit in the previous body
+    pending: Option<Vec<u8>>,
+}
+
+/// Yield NDJSON bodies of at most `max_size` bytes as they fill
+/// (a body always holds at least one record, even an oversized one)
+pub(crate) fn ndjson_batches_iter(records: &[JsonValue], max_size: usize) -> NdjsonBatches<'_> {
+    NdjsonBatches {
+        records: records.iter(),
+        max_size,
+        pending: None,
+    }
+}
+
+impl Iterator for NdjsonBatches<'_> {
+    type Item = Result<Bytes, SendError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let serialize =
+            |record| serde_json::to_vec(record).map_err(|e| SendError::Serialize(e.to_string()));
+
+        let first = match self.pending.take() {
+            Some(json_bytes) => json_bytes,
+            None => match serialize(self.records.next()?) {
+                Ok(json_bytes) => json_bytes,
+                Err(e) => return Some(Err(e)),
+            },
+        };
+        let mut current_buf = BytesMut::from(&first[..]);
+
+        for record in self.records.by_ref() {
+            let json_bytes = match serialize(record) {
+                Ok(json_bytes) => json_bytes,
+                Err(e) => return Some(Err(e)),
+            };
+            // +1 for the newline separator; a record that doesn't fit starts the next body
+            if current_buf.len() + json_bytes.len() + 1 > self.max_size {
+                self.pending = Some(json_bytes);
+                break;
+            }
+            current_buf.put_slice(b"\n");
+            current_buf.extend_from_slice(&json_bytes);
+        }
+        Some(Ok(current_buf.freeze()))
+    }
+}
+
+/// Build NDJSON batches, splitting into multiple batches if total size exceeds max_size.
+/// Records are validated against the table schema unless `validate` is false.
+fn build_ndjson_batches(
+    records: &[JsonValue],
+    max_size: usize,
+    table: &str,
+    validate: bool,
+) -> Result<Vec<Bytes>, SendError> {
+    validate_records(records, table, validate)?;
+    ndjson_batches_iter(records, max_size).collect()
+}
+
+/// Build request bodies for the given batch mode
+pub(crate) fn build_batches(
+    records: &[JsonValue],
+    mode: BatchMode,
+    max_size: usize,
+    table: &str,
+    validate: bool,
+) -> Result<Vec<Bytes>, SendError> {
+    match mode {
+        BatchMode::Ndjson => build_ndjson_batches(records, max_size, table, validate),
+        // A zero size limit closes every batch after its first record
+        BatchMode::PerRecord => build_ndjson_batches(records, 0, table, validate),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::pipeline::{PipelineClient, PipelineSender};
+    use std::collections::HashMap;
+
+    #[test]
+    fn build_ndjson_batches_single_batch() {
+        let records = vec![
+            JsonValue::from("record1"),
+            JsonValue::from("record2"),
+            JsonValue::from("record3"),
+        ];
+
+        // Use "_test" to skip schema validation (no schema defined for this table)
+        let batches = build_ndjson_batches(&records, 1024, "_test", true).unwrap();
+        assert_eq!(batches.len(), 1);
+
+        let body = String::from_utf8_lossy(&batches[0]);
+        assert!(body.contains("record1"));
+        assert!(body.contains("record2"));
+        assert!(body.contains("record3"));
+        // Verify NDJSON format (newline separated)
+        assert_eq!(body.matches('\n').count(), 2);
+    }
+
+    #[test]
+    fn build_ndjson_batches_splits_on_size() {
+        let records = vec![
+            JsonValue::from("aaaaaaaaaa"), // ~12 bytes with quotes
+            JsonValue::from("bbbbbbbbbb"),
+            JsonValue::from("cccccccccc"),
+        ];
+
+        // Force split with a small max size, use "_test" to skip schema validation
+        let batches = build_ndjson_batches(&records, 30, "_test", true).unwrap();
+        assert!(batches.len() > 1, "expected multiple batches");
+
+        // Verify all records are present across batches
+        let all_content: String = batches
+            .iter()
+            .map(|b| String::from_utf8_lossy(b).to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(all_content.contains("aaaaaaaaaa"));
+        assert!(all_content.contains("bbbbbbbbbb"));
+        assert!(all_content.contains("cccccccccc"));
+    }
+
+    #[test]
+    fn build_ndjson_batches_always_includes_one_record() {
+        // Even if a single record exceeds max size, it should still be included
+        let records = vec![JsonValue::from(
+            "this_is_a_very_long_record_that_exceeds_max",
+        )];
+
+        // Use "_test" to skip schema validation
+        let batches = build_ndjson_batches(&records, 10, "_test", true).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert!(String::from_utf8_lossy(&batches[0]).contains("this_is_a_very_long_record"));
+    }
+
+    #[test]
+    fn build_batches_per_record_mode() {
+        let records = vec![JsonValue::from("a"), JsonValue::from("b")];
+
+        let batches = build_batches(&records, BatchMode::PerRecord, 1024, "_test", true).unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(&batches[0][..], b"\"a\"");
+
+        let batches = build_batches(&records, BatchMode::Ndjson, 1024, "_test", true).unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!("ndjson".parse(), Ok(BatchMode::Ndjson));
+        assert_eq!("per_record".parse(), Ok(BatchMode::PerRecord));
+        assert!("csv".parse::<BatchMode>().is_err());
+    }
+
+    #[test]
+    fn ndjson_batches_iter_matches_collected_batches() {
+        // ~5MB of records, split at the pipeline body limit
+        let records: Vec<JsonValue> = (0..50_000)
+            .map(|i| serde_json::json!({"i": i, "pad": "x".repeat(80 + i % 40)}))
+            .collect();
+        let max_size = 900 * 1024;
+
+        let collected = build_ndjson_batches(&records, max_size, "_test", true).unwrap();
+        let lazy: Vec<Bytes> = ndjson_batches_iter(&records, max_size)
+            .map(Result::unwrap)
+            .collect();
+        assert!(
+            collected.len() > 5,
+            "expected a split, got {}",
+            collected.len()
+        );
+        assert_eq!(lazy, collected);
+
+        // Chunks stay under the limit and rejoin to the unsplit body
+        assert!(lazy.iter().all(|b| b.len() <= max_size));
+        let whole = build_ndjson_batches(&records, usize::MAX, "_test", true).unwrap();
+        assert_eq!(whole.len(), 1);
+        assert_eq!(lazy.join(&b'\n'), whole[0].to_vec());
+        assert_eq!(ndjson_batches_iter(&[], max_size).count(), 0);
+    }
+
+    // Schema validation tests are in crate::schema::tests
+
+    #[test]
+    fn validate_record_schema_catches_missing_field() {
+        let json: JsonValue = serde_json::json!({
+            "timestamp": 1234567890,
+            "metric_name": "test.metric",
+            "service_name": "test-service"
+            // missing "value" field
+        });
+
+        let result = validate_record_schema(&json, "gauge", 0);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn validate_record_schema_passes_valid_record() {
+        let json: JsonValue = serde_json::json!({
+            "timestamp": 1234567890,
+            "value": 42.5,
+            "metric_name": "test.metric",
+            "service_name": "test-service"
+        });
+
+        let result = validate_record_schema(&json, "gauge", 0);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn validate_record_schema_skips_unknown_tables() {
+        // Unknown table names should pass through without validation
+        let json: JsonValue = serde_json::json!({"anything": "goes"});
+        let result = validate_record_schema(&json, "unknown_table", 0);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn skip_validation_only_for_listed_tables() {
+        let skipped = skip_validation_from(Some(" gauge, sum,".to_string()));
+        assert_eq!(skipped.len(), 2);
+
+        // A gauge record missing required fields
+        let records = vec![serde_json::json!({"metric_name": "test.metric"})];
+        for table in ["gauge", "logs"] {
+            let validate = !skipped.contains(table);
+            let result = build_batches(&records, BatchMode::Ndjson, 1024, table, validate);
+            assert_eq!(result.is_ok(), table == "gauge", "{}", table);
+        }
+        assert!(skip_validation_from(None).is_empty());
+    }
+
+    /// Spawn a pipeline mock that counts requests, returning (endpoint, counter)
+    pub(crate) async fn counting_pipeline(
+    ) -> (String, std::sync::Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(move || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    "ok"
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (format!("http://{}/", addr), count)
+    }
+
+    #[tokio::test]
+    async fn per_record_mode_sends_one_request_per_record() {
+        use std::sync::atomic::Ordering;
+
+        let (endpoint, count) = counting_pipeline().await;
+        let records: Vec<JsonValue> = (0..5).map(JsonValue::from).collect();
+        let grouped = HashMap::from([("_test".to_string(), records)]);
+        let client = PipelineClient::new(HashMap::new(), "token".to_string())
+            .expect("failed to create client")
+            .with_table_endpoints(HashMap::from([("_test".to_string(), endpoint)]));
+
+        let result = client.send_all(grouped.clone()).await;
+        assert_eq!(result.succeeded["_test"], 5);
+        assert_eq!(count.swap(0, Ordering::SeqCst), 1);
+
+        let client = client.with_batch_mode(BatchMode::PerRecord);
+        let result = client.send_all(grouped).await;
+        assert_eq!(result.succeeded["_test"], 5);
+        assert_eq!(count.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn reject_mode_sends_valid_records_and_reports_invalid_ones() {
+        use crate::signal::Signal;
+        use std::sync::atomic::Ordering;
+
+        let log = |service: Option<&str>| {
+            let mut record = serde_json::json!({
+                "timestamp": 1234567890,
+                "observed_timestamp": 1234567890,
+                "severity_number": 9,
+                "severity_text": "INFO"
+            });
+            if let Some(service) = service {
+                record["service_name"] = service.into();
+            }
+            record
+        };
+        let grouped = HashMap::from([(
+            "logs".to_string(),
+            vec![log(Some("a")), log(None), log(Some("b"))],
+        )]);
+        let (endpoint, count) = counting_pipeline().await;
+        let client = PipelineClient::new(HashMap::from([(Signal::Logs, endpoint)]), "t".into())
+            .expect("failed to create client");
+
+        // Default: one bad record fails the whole table before anything is sent
+        let result = client.send_all(grouped.clone()).await;
+        assert!(result.failed.contains_key("logs"));
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        let result = client
+            .with_reject_invalid_records(true)
+            .send_all(grouped)
+            .await;
+        assert_eq!(result.succeeded["logs"], 2);
+        assert_eq!(result.rejected["logs"], 1);
+        assert!(result.rejection_reasons["logs"][0].starts_with("record 1 (logs): missing"));
+        assert!(result.failed.is_empty());
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn partition_by_schema_keeps_order_and_skips_unknown_tables() {
+        let records = vec![
+            JsonValue::from(1),
+            serde_json::json!({}),
+            JsonValue::from(3),
+        ];
+        let (valid, reasons) = partition_by_schema(records.clone(), "logs");
+        assert!(valid.is_empty());
+        assert_eq!(reasons.len(), 3);
+
+        let (valid, reasons) = partition_by_schema(records.clone(), "_test");
+        assert_eq!(valid, records);
+        assert!(reasons.is_empty());
+        assert!(reject_invalid_from(Some("true".to_string())));
+        assert!(!reject_invalid_from(None));
+    }
+}
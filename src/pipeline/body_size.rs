@@ -0,0 +1,101 @@
+//! Per-signal request body ceilings.
+//!
+//! Cloudflare Pipelines cap requests at 1MB, so bodies default to 900KB.
+//! Destinations that accept more (e.g. Firehose) can raise the ceiling per
+//! signal with `PIPELINE_MAX_BODY_<TABLE>` in bytes, e.g.
+//! `PIPELINE_MAX_BODY_LOGS=4194304`, to avoid over-splitting.
+
+use std::collections::HashMap;
+
+use crate::signal::Signal;
+
+/// Default body ceiling (Cloudflare limit is 1MB, 900KB leaves a safety margin)
+pub const DEFAULT_MAX_BODY_SIZE: usize = 900 * 1024;
+
+/// Body ceilings keyed by signal, with the default for everything else
+#[derive(Debug, Default)]
+pub(crate) struct BodySizeLimits {
+    per_signal: HashMap<Signal, usize>,
+}
+
+impl BodySizeLimits {
+    pub(crate) fn new(per_signal: HashMap<Signal, usize>) -> Self {
+        Self { per_signal }
+    }
+
+    /// Ceiling for a table; routed tables without a signal use the default
+    pub(crate) fn for_table(&self, table: &str) -> usize {
+        Signal::from_table_name(table)
+            .and_then(|signal| self.per_signal.get(&signal).copied())
+            .unwrap_or(DEFAULT_MAX_BODY_SIZE)
+    }
+}
+
+/// Read `PIPELINE_MAX_BODY_<TABLE>` for every signal; unset, invalid or zero values are skipped
+pub fn max_body_sizes_from_lookup(
+    mut var: impl FnMut(&str) -> Option<String>,
+) -> HashMap<Signal, usize> {
+    Signal::all()
+        .iter()
+        .filter_map(|signal| {
+            let name = format!("PIPELINE_MAX_BODY_{}", signal.table_name().to_uppercase());
+            var(&name)
+                .and_then(|v| v.trim().parse::<usize>().ok())
+                .filter(|size| *size > 0)
+                .map(|size| (*signal, size))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::batch::tests::counting_pipeline;
+    use crate::pipeline::{PipelineClient, PipelineSender};
+    use serde_json::Value as JsonValue;
+    use std::sync::atomic::Ordering;
+
+    #[test]
+    fn test_from_lookup_and_defaults() {
+        let sizes = max_body_sizes_from_lookup(|name| match name {
+            "PIPELINE_MAX_BODY_LOGS" => Some("4194304".to_string()),
+            "PIPELINE_MAX_BODY_GAUGE" => Some("0".to_string()),
+            "PIPELINE_MAX_BODY_SUM" => Some("big".to_string()),
+            _ => None,
+        });
+        assert_eq!(sizes, HashMap::from([(Signal::Logs, 4 * 1024 * 1024)]));
+
+        let limits = BodySizeLimits::new(sizes);
+        assert_eq!(limits.for_table("logs"), 4 * 1024 * 1024);
+        assert_eq!(limits.for_table("gauge"), DEFAULT_MAX_BODY_SIZE);
+        assert_eq!(limits.for_table("gauge_runtime"), DEFAULT_MAX_BODY_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_larger_limit_sends_fewer_batches() {
+        // ~3MB of records for one signal
+        let records: Vec<JsonValue> = (0..3_000)
+            .map(|i| serde_json::json!({"i": i, "pad": "x".repeat(1_000)}))
+            .collect();
+        let grouped = HashMap::from([("gauge".to_string(), records)]);
+
+        let mut counts = Vec::new();
+        for limits in [
+            HashMap::new(),
+            HashMap::from([(Signal::Gauge, 4 * 1024 * 1024)]),
+        ] {
+            let (endpoint, count) = counting_pipeline().await;
+            let client =
+                PipelineClient::new(HashMap::from([(Signal::Gauge, endpoint)]), "token".into())
+                    .expect("failed to create client")
+                    .with_skip_schema_validation(["gauge".to_string()].into())
+                    .with_max_body_sizes(limits);
+            let result = client.send_all(grouped.clone()).await;
+            assert_eq!(result.succeeded["gauge"], 3_000);
+            counts.push(count.load(Ordering::SeqCst));
+        }
+
+        assert!(counts[0] > 1, "900KB should split, got {}", counts[0]);
+        assert_eq!(counts[1], 1);
+    }
+}
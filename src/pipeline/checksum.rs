@@ -0,0 +1,204 @@
+//! Batch checksums for end-to-end integrity checks.
+//!
+//! With `PIPELINE_CHECKSUM=send` every request carries `X-Content-Checksum`:
+//! the CRC32 (IEEE) of the uncompressed NDJSON body as 8 lowercase hex
+//! digits, so it stays the same whether or not the body is gzipped. With
+//! `verify`, native sends also require a cooperating downstream to echo the
+//! same header back, and fail the table without retrying when it doesn't
+//! match.
+
+use super::error::SendError;
+
+/// Header carrying the body checksum, on the request and on echoed responses.
+/// The checksum covers the NDJSON before any `Content-Encoding` is applied.
+pub const CHECKSUM_HEADER: &str = "X-Content-Checksum";
+
+/// Whether batch checksums are sent and checked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChecksumMode {
+    #[default]
+    Off,
+    /// Set the checksum header on every request
+    Send,
+    /// Also require the response to echo a matching checksum (native only;
+    /// workers send without checking)
+    Verify,
+}
+
+impl std::str::FromStr for ChecksumMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "off" | "false" => Ok(ChecksumMode::Off),
+            "send" | "true" => Ok(ChecksumMode::Send),
+            "verify" => Ok(ChecksumMode::Verify),
+            other => Err(format!(
+                "unknown checksum mode '{}' (expected off, send, or verify)",
+                other
+            )),
+        }
+    }
+}
+
+/// CRC32 of an NDJSON body, formatted for `X-Content-Checksum`
+pub fn batch_checksum(body: &[u8]) -> String {
+    let mut crc = flate2::Crc::new();
+    crc.update(body);
+    format!("{:08x}", crc.sum())
+}
+
+/// Check the checksum echoed by the downstream against the one sent
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+pub(crate) fn verify_echo(sent: &str, echoed: Option<&str>) -> Result<(), SendError> {
+    match echoed {
+        Some(echoed) if echoed.trim().eq_ignore_ascii_case(sent) => Ok(()),
+        echoed => Err(SendError::ChecksumMismatch {
+            sent: sent.to_string(),
+            echoed: echoed.map(str::to_string),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::{FailureReason, PipelineClient, PipelineSender};
+    use serde_json::Value as JsonValue;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    /// Pipeline mock that records each request's checksum header and echoes
+    /// back `echo(received)`, returning (endpoint, received checksums)
+    async fn checksum_pipeline(
+        echo: fn(&str) -> String,
+    ) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let captured = seen.clone();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(move |headers: axum::http::HeaderMap| {
+                let captured = captured.clone();
+                async move {
+                    let received = headers
+                        .get(CHECKSUM_HEADER)
+                        .and_then(|v| v.to_str().ok())
+                        .map(str::to_string);
+                    let reply = echo(received.as_deref().unwrap_or(""));
+                    captured.lock().unwrap().push(received);
+                    ([(CHECKSUM_HEADER, reply)], "ok")
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (endpoint, seen)
+    }
+
+    fn client(endpoint: String, mode: ChecksumMode) -> PipelineClient {
+        PipelineClient::new(HashMap::new(), "token".to_string())
+            .expect("failed to create client")
+            .with_table_endpoints(HashMap::from([("_test".to_string(), endpoint)]))
+            .with_checksum_mode(mode)
+    }
+
+    fn batch(values: &[i64]) -> HashMap<String, Vec<JsonValue>> {
+        let records = values.iter().map(|v| JsonValue::from(*v)).collect();
+        HashMap::from([("_test".to_string(), records)])
+    }
+
+    #[tokio::test]
+    async fn test_checksum_header_matches_body_and_tracks_content() {
+        let (endpoint, seen) = checksum_pipeline(str::to_string).await;
+
+        let sender = client(endpoint.clone(), ChecksumMode::Send);
+        sender.send_all(batch(&[1, 2])).await;
+        sender.send_all(batch(&[1, 3])).await;
+        client(endpoint, ChecksumMode::Off)
+            .send_all(batch(&[1, 2]))
+            .await;
+
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(seen[0].as_deref(), Some(batch_checksum(b"1\n2").as_str()));
+        assert_eq!(seen[1].as_deref(), Some(batch_checksum(b"1\n3").as_str()));
+        assert_ne!(seen[0], seen[1]);
+        assert_eq!(seen[2], None);
+    }
+
+    #[tokio::test]
+    async fn test_verify_fails_on_wrong_echo() {
+        let (matching, _) = checksum_pipeline(str::to_string).await;
+        let result = client(matching, ChecksumMode::Verify)
+            .send_all(batch(&[1]))
+            .await;
+        assert_eq!(result.succeeded["_test"], 1);
+
+        let (corrupting, seen) = checksum_pipeline(|_| "00000000".to_string()).await;
+        let result = client(corrupting, ChecksumMode::Verify)
+            .send_all(batch(&[1]))
+            .await;
+        assert!(result.failed["_test"]
+            .message
+            .starts_with("checksum mismatch"));
+        assert_eq!(
+            result.failed["_test"].reason,
+            FailureReason::ChecksumMismatch
+        );
+        // The batch may have landed, so a mismatch is not resent
+        assert_eq!(seen.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_verify_checksum_covers_uncompressed_body_when_gzipped() {
+        let (endpoint, seen) = checksum_pipeline(str::to_string).await;
+        let values: Vec<i64> = (0..20_000).collect();
+        let result = client(endpoint, ChecksumMode::Verify)
+            .with_gzip(true)
+            .send_all(batch(&values))
+            .await;
+        assert_eq!(result.succeeded["_test"], values.len());
+
+        let ndjson = values
+            .iter()
+            .map(i64::to_string)
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(ndjson.len() > crate::pipeline::compression::GZIP_THRESHOLD_BYTES);
+        let seen = seen.lock().unwrap();
+        assert_eq!(
+            seen[0].as_deref(),
+            Some(batch_checksum(ndjson.as_bytes()).as_str())
+        );
+    }
+
+    #[test]
+    fn test_checksum_is_stable_and_content_sensitive() {
+        let body = b"{\"a\":1}\n{\"a\":2}";
+        assert_eq!(batch_checksum(body), batch_checksum(body));
+        assert_eq!(batch_checksum(body).len(), 8);
+        assert_ne!(
+            batch_checksum(body),
+            batch_checksum(b"{\"a\":1}\n{\"a\":3}")
+        );
+        // Standard CRC32 check value
+        assert_eq!(batch_checksum(b"123456789"), "cbf43926");
+    }
+
+    #[test]
+    fn test_verify_echo() {
+        assert!(verify_echo("cbf43926", Some("CBF43926")).is_ok());
+        assert!(verify_echo("cbf43926", Some("00000000")).is_err());
+        assert!(verify_echo("cbf43926", None).is_err());
+    }
+
+    #[test]
+    fn test_parse_mode() {
+        assert_eq!("".parse(), Ok(ChecksumMode::Off));
+        assert_eq!("Send".parse(), Ok(ChecksumMode::Send));
+        assert_eq!("verify".parse(), Ok(ChecksumMode::Verify));
+        assert!("sha256".parse::<ChecksumMode>().is_err());
+    }
+}
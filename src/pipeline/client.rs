@@ -1,104 +1,177 @@
-use crate::pipeline::retry::{with_retry, IsRetryable, RetryConfig};
-use crate::pipeline::sender::{PipelineSender, SendResult};
-use crate::schema::get_schema;
+use crate::pipeline::auth::AuthScheme;
+use crate::pipeline::batch::{
+    build_batches, ndjson_batches_iter, partition_by_schema, validate_records, BatchMode,
+};
+use crate::pipeline::body_size::BodySizeLimits;
+use crate::pipeline::checksum::{batch_checksum, ChecksumMode, CHECKSUM_HEADER};
+use crate::pipeline::compression::encode_body;
+use crate::pipeline::error::SendError;
+use crate::pipeline::headers::validate_headers;
+use crate::pipeline::retry::{with_retry, RetryConfig};
+use crate::pipeline::send_guard::{guarded_send, DEFAULT_SEND_DEADLINE};
+use crate::pipeline::sender::{FailureReason, PipelineSender, SendFailure, SendResult};
+use crate::pipeline::sequence::SEQUENCE_HEADER;
+use crate::pipeline::signal_policy::SendPolicies;
 use crate::signal::Signal;
-use bytes::{BufMut, Bytes, BytesMut};
+use bytes::Bytes;
 use futures::future::join_all;
+use futures::stream::{self, StreamExt};
+use reqwest::header::{HeaderName, HeaderValue};
 use reqwest::Client;
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
-#[cfg(not(target_arch = "wasm32"))]
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
-#[cfg(target_arch = "wasm32")]
-use tracing::info;
 use tracing::{debug, error, warn};
 
 #[cfg(not(target_arch = "wasm32"))]
 const SEND_TIMEOUT: Duration = Duration::from_secs(5);
 
-/// Maximum body size for pipeline requests (Cloudflare limit is 1MB, use 900KB for safety margin)
-const MAX_BODY_SIZE: usize = 900 * 1024;
-
-/// Errors that can occur when sending to a pipeline
-#[derive(Debug)]
-pub enum SendError {
-    Timeout,
-    Http { status: u16, endpoint: String },
-    Network(String),
-    Serialize(String),
-}
-
-impl std::fmt::Display for SendError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            SendError::Timeout => write!(f, "request timed out"),
-            SendError::Http { status, endpoint } => {
-                write!(f, "HTTP {} from {}", status, endpoint)
-            }
-            SendError::Network(msg) => write!(f, "network error: {}", msg),
-            SendError::Serialize(msg) => write!(f, "serialization error: {}", msg),
-        }
-    }
-}
-
-impl IsRetryable for SendError {
-    fn is_retryable(&self) -> bool {
-        match self {
-            SendError::Timeout => true,
-            SendError::Http { status, .. } => matches!(status, 502..=504),
-            SendError::Network(_) => true,
-            SendError::Serialize(_) => false,
-        }
-    }
-}
+/// Maximum in-flight requests per table in `BatchMode::PerRecord`
+const PER_RECORD_CONCURRENCY: usize = 16;
 
 /// Unified pipeline client for both WASM and native targets
 pub struct PipelineClient {
     client: Client,
     endpoints: HashMap<Signal, String>,
+    /// Endpoints for routed tables that don't map to a base signal (e.g. `gauge_runtime`)
+    table_endpoints: HashMap<String, String>,
     token: String,
+    auth_scheme: AuthScheme,
+    batch_mode: BatchMode,
+    /// Overall deadline for `send_all`; unfinished tables fail as timeouts
+    send_deadline: Option<Duration>,
+    /// Tables sent without schema validation, for schema migrations
+    skip_schema_validation: HashSet<String>,
+    /// Headers added to every send (from `PIPELINE_HEADER_<NAME>`)
+    extra_headers: Vec<(HeaderName, HeaderValue)>,
+    /// Whether batch checksums are sent (and checked)
+    checksum_mode: ChecksumMode,
+    /// Retry and circuit-breaker settings per signal
+    policies: SendPolicies,
+    /// Number each table's chunks with `X-Batch-Sequence`
+    sequence_headers: bool,
+    /// Request body ceiling per signal's destination
+    body_limits: BodySizeLimits,
+    /// Drop records that fail schema validation instead of failing their whole table
+    reject_invalid_records: bool,
+    /// Gzip bodies above `GZIP_THRESHOLD_BYTES`
+    gzip: bool,
 }
 
 impl PipelineClient {
     /// Create a new client with the given endpoints and auth token.
     /// Returns an error if the HTTP client fails to build (e.g., TLS configuration issues).
     pub fn new(endpoints: HashMap<Signal, String>, token: String) -> Result<Self, String> {
+        let builder = Client::builder();
         #[cfg(not(target_arch = "wasm32"))]
-        let client = Client::builder()
-            .timeout(SEND_TIMEOUT)
-            .build()
-            .map_err(|e| format!("failed to build HTTP client: {}", e))?;
-        #[cfg(target_arch = "wasm32")]
-        let client = Client::builder()
+        let builder = builder.timeout(SEND_TIMEOUT);
+        let client = builder
             .build()
             .map_err(|e| format!("failed to build HTTP client: {}", e))?;
         Ok(Self {
             client,
             endpoints,
+            table_endpoints: HashMap::new(),
             token,
+            auth_scheme: AuthScheme::default(),
+            batch_mode: BatchMode::default(),
+            send_deadline: Some(DEFAULT_SEND_DEADLINE),
+            skip_schema_validation: HashSet::new(),
+            extra_headers: Vec::new(),
+            checksum_mode: ChecksumMode::default(),
+            policies: SendPolicies::default(),
+            sequence_headers: false,
+            body_limits: BodySizeLimits::default(),
+            reject_invalid_records: false,
+            gzip: false,
         })
     }
 
-    /// Build from Cloudflare Worker environment
-    #[cfg(target_arch = "wasm32")]
-    pub fn from_worker_env(env: &worker::Env) -> worker::Result<Self> {
-        let token = env.secret("PIPELINE_AUTH_TOKEN")?.to_string();
-        let mut endpoints = HashMap::new();
-
-        for signal in Signal::all() {
-            if let Ok(v) = env.var(signal.env_var_name()) {
-                let url = v.to_string();
-                if !url.is_empty() {
-                    endpoints.insert(*signal, url);
-                }
-            }
+    /// Set how records are packed into requests (NDJSON batches by default)
+    pub fn with_batch_mode(mut self, batch_mode: BatchMode) -> Self {
+        self.batch_mode = batch_mode;
+        self
+    }
+
+    /// Set how the auth token is sent (`Authorization: Bearer` by default)
+    pub fn with_auth_scheme(mut self, auth_scheme: AuthScheme) -> Self {
+        self.auth_scheme = auth_scheme;
+        self
+    }
+
+    /// Set the overall `send_all` deadline (`None` waits for every table)
+    pub fn with_send_deadline(mut self, send_deadline: Option<Duration>) -> Self {
+        self.send_deadline = send_deadline;
+        self
+    }
+
+    /// Send records for these tables without validating them against their schema
+    pub fn with_skip_schema_validation(mut self, tables: HashSet<String>) -> Self {
+        self.skip_schema_validation = tables;
+        self
+    }
+
+    /// Add headers to every send, keyed by header name.
+    /// Returns an error for invalid names or values, or for headers the client sets itself.
+    pub fn with_extra_headers(mut self, headers: HashMap<String, String>) -> Result<Self, String> {
+        self.extra_headers = validate_headers(headers)?;
+        Ok(self)
+    }
+
+    /// Set whether batch checksums are sent and verified (off by default)
+    pub fn with_checksum_mode(mut self, checksum_mode: ChecksumMode) -> Self {
+        self.checksum_mode = checksum_mode;
+        self
+    }
+
+    /// Set per-signal retry and circuit-breaker settings
+    pub fn with_send_policies(mut self, policies: SendPolicies) -> Self {
+        self.policies = policies;
+        self
+    }
+
+    /// Set whether chunks carry their per-table sequence number (off by default)
+    pub fn with_sequence_headers(mut self, enabled: bool) -> Self {
+        self.sequence_headers = enabled;
+        self
+    }
+
+    /// Set per-signal request body ceilings; other signals keep the 900KB default
+    pub fn with_max_body_sizes(mut self, limits: HashMap<Signal, usize>) -> Self {
+        self.body_limits = BodySizeLimits::new(limits);
+        self
+    }
+
+    /// Set whether invalid records are rejected individually (reported in
+    /// `SendResult::rejected`) rather than failing their table (off by default)
+    pub fn with_reject_invalid_records(mut self, enabled: bool) -> Self {
+        self.reject_invalid_records = enabled;
+        self
+    }
+
+    /// Gzip large request bodies (off by default); checksums still cover the uncompressed body
+    pub fn with_gzip(mut self, enabled: bool) -> Self {
+        self.gzip = enabled;
+        self
+    }
+
+    /// Split out records failing the table schema when per-record rejection is on
+    fn split_rejected(
+        &self,
+        table: &str,
+        records: Vec<JsonValue>,
+    ) -> (Vec<JsonValue>, Vec<String>) {
+        if self.reject_invalid_records && !self.skip_schema_validation.contains(table) {
+            partition_by_schema(records, table)
+        } else {
+            (records, Vec::new())
         }
+    }
 
-        info!(
-            endpoint_count = endpoints.len(),
-            "PipelineClient initialized"
-        );
-        Self::new(endpoints, token).map_err(|e| worker::Error::RustError(e))
+    /// Add endpoints for routed tables, keyed by table name
+    pub fn with_table_endpoints(mut self, table_endpoints: HashMap<String, String>) -> Self {
+        self.table_endpoints.extend(table_endpoints);
+        self
     }
 
     /// Send records to a pipeline endpoint, automatically chunking if needed to stay under size limit
@@ -120,50 +193,82 @@ impl PipelineClient {
         debug!(endpoint, total_records, "sending batch to pipeline");
 
         // Build size-limited batches with schema validation for metrics
-        let batches = build_ndjson_batches(&records, MAX_BODY_SIZE, table)?;
-        let batch_count = batches.len();
+        // (already done by `split_rejected` when rejecting per record)
+        let validate = !self.reject_invalid_records && !self.skip_schema_validation.contains(table);
+        let retry_config = &self.policies.for_table(table).retry;
+        let max_body_size = self.body_limits.for_table(table);
+        if self.batch_mode == BatchMode::PerRecord {
+            let batches = build_batches(&records, self.batch_mode, max_body_size, table, validate)?;
+            // Send every record, then fail the table if any request failed
+            let results: Vec<_> = stream::iter(batches.into_iter().enumerate())
+                .map(|(seq, body)| self.send_single_batch(endpoint, body, seq, retry_config))
+                .buffer_unordered(PER_RECORD_CONCURRENCY)
+                .collect()
+                .await;
+            let sent_count = results.into_iter().sum::<Result<usize, SendError>>()?;
+            debug!(endpoint, sent_count, "all records sent individually");
+            return Ok(sent_count);
+        }
+
+        // Bodies are built one at a time, each sent before the next is serialized
+        validate_records(&records, table, validate)?;
+        let mut sent_count = 0;
+        let mut batch_count = 0;
+        for (batch_idx, body) in ndjson_batches_iter(&records, max_body_size).enumerate() {
+            let body = body?;
+            debug!(batch_idx, batch_size = body.len(), "sending batch chunk");
+            sent_count += self
+                .send_single_batch(endpoint, body, batch_idx, retry_config)
+                .await?;
+            batch_count += 1;
+        }
 
         if batch_count > 1 {
             debug!(
                 batch_count,
-                total_records, "splitting into multiple batches due to size limit"
+                total_records, "split into multiple batches due to size limit"
             );
         }
-
-        let mut sent_count = 0;
-        for (batch_idx, body) in batches.into_iter().enumerate() {
-            let batch_size = body.len();
-            debug!(batch_idx, batch_size, batch_count, "sending batch chunk");
-
-            sent_count += self.send_single_batch(endpoint, body).await?;
-        }
-
         debug!(endpoint, sent_count, "all batches sent successfully");
         Ok(sent_count)
     }
 
-    /// Send a single pre-built NDJSON body to the pipeline
-    async fn send_single_batch(&self, endpoint: &str, body: Bytes) -> Result<usize, SendError> {
-        let retry_config = RetryConfig::default();
+    /// Send a single pre-built NDJSON body, the `sequence`th chunk of its table
+    async fn send_single_batch(
+        &self,
+        endpoint: &str,
+        body: Bytes,
+        sequence: usize,
+        retry_config: &RetryConfig,
+    ) -> Result<usize, SendError> {
         // Count records by counting newlines + 1 (NDJSON format)
         let record_count = body.iter().filter(|&&b| b == b'\n').count() + 1;
+        let (auth_name, auth_value) = self.auth_scheme.header(&self.token);
+        let checksum = (self.checksum_mode != ChecksumMode::Off).then(|| batch_checksum(&body));
+        let (body, content_encoding) = encode_body(body, self.gzip);
 
-        with_retry(&retry_config, || async {
-            let response = self
+        with_retry(retry_config, || async {
+            let mut request = self
                 .client
                 .post(endpoint)
                 .header("Content-Type", "application/x-ndjson")
-                .header("Authorization", format!("Bearer {}", self.token))
-                .body(body.clone())
-                .send()
-                .await
-                .map_err(|e| {
-                    if e.is_timeout() {
-                        SendError::Timeout
-                    } else {
-                        SendError::Network(e.to_string())
-                    }
-                })?;
+                .header(auth_name, &auth_value);
+            for (name, value) in self.extra_headers.iter().chain(&content_encoding) {
+                request = request.header(name, value);
+            }
+            if let Some(checksum) = &checksum {
+                request = request.header(CHECKSUM_HEADER, checksum);
+            }
+            if self.sequence_headers {
+                request = request.header(SEQUENCE_HEADER, sequence);
+            }
+            let response = request.body(body.clone()).send().await.map_err(|e| {
+                if e.is_timeout() {
+                    SendError::Timeout
+                } else {
+                    SendError::Network(e.to_string())
+                }
+            })?;
 
             let status = response.status().as_u16();
             if !(200..300).contains(&status) {
@@ -184,6 +289,17 @@ impl PipelineClient {
                 });
             }
 
+            #[cfg(not(target_arch = "wasm32"))]
+            if let (ChecksumMode::Verify, Some(sent)) = (self.checksum_mode, &checksum) {
+                let echoed = response
+                    .headers()
+                    .get(CHECKSUM_HEADER)
+                    .and_then(|v| v.to_str().ok());
+                crate::pipeline::checksum::verify_echo(sent, echoed).inspect_err(|e| {
+                    warn!(endpoint, error = %e, "pipeline batch checksum not confirmed");
+                })?;
+            }
+
             Ok(record_count)
         })
         .await
@@ -198,238 +314,74 @@ impl PipelineSender for PipelineClient {
         let mut futures = Vec::new();
 
         for (table_name, records) in grouped.into_iter().filter(|(_, r)| !r.is_empty()) {
-            let signal = match Signal::from_table_name(&table_name) {
-                Some(s) => s,
-                None => {
-                    warn!(table = %table_name, "unknown signal type");
-                    send_result
-                        .failed
-                        .insert(table_name, "unknown signal type".to_string());
-                    continue;
-                }
+            let endpoint = match self.table_endpoints.get(&table_name) {
+                Some(endpoint) => Some(endpoint),
+                None => match Signal::from_table_name(&table_name) {
+                    Some(signal) => self.endpoints.get(&signal),
+                    None => {
+                        warn!(table = %table_name, "unknown signal type");
+                        send_result.failed.insert(
+                            table_name,
+                            SendFailure::new(FailureReason::NoEndpoint, "unknown signal type"),
+                        );
+                        continue;
+                    }
+                },
             };
 
-            if let Some(endpoint) = self.endpoints.get(&signal) {
+            if let Some(endpoint) = endpoint {
                 let endpoint = endpoint.clone();
                 let table = table_name.clone();
+                let deadline = self.send_deadline;
                 futures.push(async move {
-                    let result = self.send_batch(&table, &endpoint, records).await;
-                    (table, result)
+                    let (records, rejections) = self.split_rejected(&table, records);
+                    if records.is_empty() {
+                        warn!(table = %table, "every record failed schema validation");
+                        return (table, Ok(0), rejections);
+                    }
+                    let policy = self.policies.for_table(&table);
+                    let send = self.send_batch(&table, &endpoint, records);
+                    let result = guarded_send(&table, policy, deadline, send).await;
+                    (table, result, rejections)
                 });
             } else {
                 warn!(table = %table_name, "no pipeline endpoint configured");
                 let message = format!("no pipeline endpoint configured for {}", table_name);
-                send_result.failed.insert(table_name, message);
+                send_result.failed.insert(
+                    table_name,
+                    SendFailure::new(FailureReason::NoEndpoint, message),
+                );
             }
         }
 
         let results = join_all(futures).await;
 
-        for (table, result) in results {
+        for (table, result, rejections) in results {
+            if !rejections.is_empty() {
+                warn!(table = %table, rejected = rejections.len(), "records rejected by schema validation");
+            }
+            send_result.add_rejections(&table, rejections);
             match result {
                 Ok(count) => {
                     send_result.succeeded.insert(table, count);
                 }
                 Err(e) => {
-                    send_result.failed.insert(table, e.to_string());
+                    send_result
+                        .failed
+                        .insert(table, SendFailure::new(e.reason(), e.to_string()));
                 }
             }
         }
 
+        super::health::record_result(&send_result);
         send_result
     }
 }
 
-/// Validate a record against its schema before sending.
-/// Uses centralized schema definitions from crate::schema.
-fn validate_record_schema(json: &JsonValue, table: &str, idx: usize) -> Result<(), SendError> {
-    if let Some(schema) = get_schema(table) {
-        schema.validate(json, idx).map_err(SendError::Serialize)?;
-    }
-    Ok(())
-}
-
-/// Build NDJSON batches, splitting into multiple batches if total size exceeds max_size
-fn build_ndjson_batches(
-    records: &[JsonValue],
-    max_size: usize,
-    table: &str,
-) -> Result<Vec<Bytes>, SendError> {
-    let mut batches = Vec::new();
-    let mut current_buf = BytesMut::new();
-    let mut first_in_batch = true;
-
-    for (idx, record) in records.iter().enumerate() {
-        // Validate record against schema before serialization
-        validate_record_schema(record, table, idx)?;
-
-        let json_bytes =
-            serde_json::to_vec(record).map_err(|e| SendError::Serialize(e.to_string()))?;
-
-        // Calculate size this record would add (including newline separator)
-        let record_size = if first_in_batch {
-            json_bytes.len()
-        } else {
-            json_bytes.len() + 1 // +1 for newline
-        };
-
-        // If adding this record would exceed max size, start a new batch
-        // (but always include at least one record per batch)
-        if !first_in_batch && current_buf.len() + record_size > max_size {
-            batches.push(current_buf.freeze());
-            current_buf = BytesMut::new();
-            first_in_batch = true;
-        }
-
-        if !first_in_batch {
-            current_buf.put_slice(b"\n");
-        } else {
-            first_in_batch = false;
-        }
-        current_buf.extend_from_slice(&json_bytes);
-    }
-
-    // Don't forget the last batch
-    if !current_buf.is_empty() {
-        batches.push(current_buf.freeze());
-    }
-
-    Ok(batches)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn build_ndjson_batches_single_batch() {
-        let records = vec![
-            JsonValue::from("record1"),
-            JsonValue::from("record2"),
-            JsonValue::from("record3"),
-        ];
-
-        // Use "_test" to skip schema validation (no schema defined for this table)
-        let batches = build_ndjson_batches(&records, 1024, "_test").unwrap();
-        assert_eq!(batches.len(), 1);
-
-        let body = String::from_utf8_lossy(&batches[0]);
-        assert!(body.contains("record1"));
-        assert!(body.contains("record2"));
-        assert!(body.contains("record3"));
-        // Verify NDJSON format (newline separated)
-        assert_eq!(body.matches('\n').count(), 2);
-    }
-
-    #[test]
-    fn build_ndjson_batches_splits_on_size() {
-        let records = vec![
-            JsonValue::from("aaaaaaaaaa"), // ~12 bytes with quotes
-            JsonValue::from("bbbbbbbbbb"),
-            JsonValue::from("cccccccccc"),
-        ];
-
-        // Force split with a small max size, use "_test" to skip schema validation
-        let batches = build_ndjson_batches(&records, 30, "_test").unwrap();
-        assert!(batches.len() > 1, "expected multiple batches");
-
-        // Verify all records are present across batches
-        let all_content: String = batches
-            .iter()
-            .map(|b| String::from_utf8_lossy(b).to_string())
-            .collect::<Vec<_>>()
-            .join("\n");
-        assert!(all_content.contains("aaaaaaaaaa"));
-        assert!(all_content.contains("bbbbbbbbbb"));
-        assert!(all_content.contains("cccccccccc"));
-    }
-
-    #[test]
-    fn build_ndjson_batches_always_includes_one_record() {
-        // Even if a single record exceeds max size, it should still be included
-        let records = vec![JsonValue::from(
-            "this_is_a_very_long_record_that_exceeds_max",
-        )];
-
-        // Use "_test" to skip schema validation
-        let batches = build_ndjson_batches(&records, 10, "_test").unwrap();
-        assert_eq!(batches.len(), 1);
-        assert!(String::from_utf8_lossy(&batches[0]).contains("this_is_a_very_long_record"));
-    }
-
-    // Schema validation tests are in crate::schema::tests
-
-    #[test]
-    fn validate_record_schema_catches_missing_field() {
-        let json: JsonValue = serde_json::json!({
-            "timestamp": 1234567890,
-            "metric_name": "test.metric",
-            "service_name": "test-service"
-            // missing "value" field
-        });
-
-        let result = validate_record_schema(&json, "gauge", 0);
-        assert!(result.is_err());
-    }
-
-    #[test]
-    fn validate_record_schema_passes_valid_record() {
-        let json: JsonValue = serde_json::json!({
-            "timestamp": 1234567890,
-            "value": 42.5,
-            "metric_name": "test.metric",
-            "service_name": "test-service"
-        });
-
-        let result = validate_record_schema(&json, "gauge", 0);
-        assert!(result.is_ok());
-    }
-
-    #[test]
-    fn validate_record_schema_skips_unknown_tables() {
-        // Unknown table names should pass through without validation
-        let json: JsonValue = serde_json::json!({"anything": "goes"});
-        let result = validate_record_schema(&json, "unknown_table", 0);
-        assert!(result.is_ok());
-    }
-
-    #[test]
-    fn send_error_retryable_classification() {
-        assert!(SendError::Timeout.is_retryable());
-        assert!(SendError::Network("conn reset".into()).is_retryable());
-        assert!(SendError::Http {
-            status: 502,
-            endpoint: "x".into()
-        }
-        .is_retryable());
-        assert!(SendError::Http {
-            status: 503,
-            endpoint: "x".into()
-        }
-        .is_retryable());
-        assert!(SendError::Http {
-            status: 504,
-            endpoint: "x".into()
-        }
-        .is_retryable());
-        assert!(!SendError::Serialize("bad json".into()).is_retryable());
-        assert!(!SendError::Http {
-            status: 400,
-            endpoint: "x".into()
-        }
-        .is_retryable());
-        assert!(!SendError::Http {
-            status: 401,
-            endpoint: "x".into()
-        }
-        .is_retryable());
-        assert!(!SendError::Http {
-            status: 500,
-            endpoint: "x".into()
-        }
-        .is_retryable());
-    }
-
     #[tokio::test]
     async fn missing_endpoint_reports_failure() {
         let client = PipelineClient::new(HashMap::new(), "token".to_string())
@@ -441,5 +393,32 @@ mod tests {
 
         assert!(result.succeeded.is_empty());
         assert!(result.failed.contains_key("logs"));
+        assert_eq!(result.failed["logs"].reason, FailureReason::NoEndpoint);
+    }
+
+    #[tokio::test]
+    async fn routed_table_uses_table_endpoint() {
+        let client = PipelineClient::new(HashMap::new(), "token".to_string())
+            .expect("failed to create client")
+            .with_table_endpoints(HashMap::from([(
+                "gauge_runtime".to_string(),
+                "http://127.0.0.1:1".to_string(),
+            )]));
+        let grouped = HashMap::from([
+            ("gauge_runtime".to_string(), vec![JsonValue::from("a")]),
+            ("gauge_other".to_string(), vec![JsonValue::from("b")]),
+        ]);
+
+        let result = client.send_all(grouped).await;
+
+        // Routed table resolves its endpoint (and fails connecting), unrouted one has none
+        assert_eq!(
+            result.failed["gauge_runtime"].reason,
+            FailureReason::Network
+        );
+        assert_eq!(
+            result.failed["gauge_other"].reason,
+            FailureReason::NoEndpoint
+        );
     }
 }
@@ -0,0 +1,118 @@
+//! Gzip compression of outgoing request bodies.
+//!
+//! With `PIPELINE_GZIP=true`, NDJSON bodies larger than
+//! `GZIP_THRESHOLD_BYTES` are gzipped and sent with `Content-Encoding: gzip`.
+//! Batches are still sized by their uncompressed length, so the pipeline's
+//! limit on the decompressed body holds either way.
+
+use bytes::Bytes;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use reqwest::header::{HeaderName, HeaderValue, CONTENT_ENCODING};
+use std::io::Write;
+
+/// Bodies at or below this size are sent uncompressed
+pub const GZIP_THRESHOLD_BYTES: usize = 64 * 1024;
+
+/// Parse `PIPELINE_GZIP`; unset or invalid disables compression
+pub fn gzip_from(value: Option<String>) -> bool {
+    value.and_then(|v| v.trim().parse().ok()).unwrap_or(false)
+}
+
+/// Gzip `body` when enabled and above the threshold, returning the body to
+/// send and its `Content-Encoding` header, if any
+pub(crate) fn encode_body(body: Bytes, gzip: bool) -> (Bytes, Option<(HeaderName, HeaderValue)>) {
+    if !gzip || body.len() <= GZIP_THRESHOLD_BYTES {
+        return (body, None);
+    }
+    let mut encoder = GzEncoder::new(Vec::with_capacity(body.len() / 4), Compression::fast());
+    match encoder.write_all(&body).and_then(|_| encoder.finish()) {
+        Ok(compressed) => (
+            Bytes::from(compressed),
+            Some((CONTENT_ENCODING, HeaderValue::from_static("gzip"))),
+        ),
+        // Writing to a Vec can't fail in practice; fall back to the raw body
+        Err(_) => (body, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::{PipelineClient, PipelineSender};
+    use flate2::read::GzDecoder;
+    use serde_json::{json, Value as JsonValue};
+    use std::collections::HashMap;
+    use std::io::Read;
+    use std::sync::{Arc, Mutex};
+
+    fn gunzip(body: &[u8]) -> Vec<u8> {
+        let mut decoded = Vec::new();
+        GzDecoder::new(body).read_to_end(&mut decoded).unwrap();
+        decoded
+    }
+
+    #[test]
+    fn test_large_body_round_trips_through_gzip() {
+        let body = Bytes::from("{\"body\":\"hello\"}\n".repeat(10_000));
+        let (encoded, header) = encode_body(body.clone(), true);
+
+        assert_eq!(header.unwrap().1, "gzip");
+        assert!(encoded.len() < body.len());
+        assert_eq!(gunzip(&encoded), body);
+    }
+
+    #[test]
+    fn test_small_or_disabled_bodies_are_untouched() {
+        let small = Bytes::from_static(b"{\"a\":1}");
+        assert_eq!(encode_body(small.clone(), true), (small, None));
+
+        let large = Bytes::from("x".repeat(GZIP_THRESHOLD_BYTES + 1));
+        assert_eq!(encode_body(large.clone(), false), (large, None));
+    }
+
+    #[test]
+    fn test_gzip_from() {
+        assert!(gzip_from(Some(" true".to_string())));
+        assert!(!gzip_from(Some("yes".to_string())));
+        assert!(!gzip_from(None));
+    }
+
+    #[tokio::test]
+    async fn test_client_sends_gzipped_ndjson() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(move |headers: axum::http::HeaderMap, body: Bytes| {
+                let encoding = headers
+                    .get(CONTENT_ENCODING)
+                    .map(|v| v.to_str().unwrap().to_string());
+                recorder.lock().unwrap().push((encoding, body));
+                async { "ok" }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let records: Vec<JsonValue> = (0..1_000)
+            .map(|i| json!({"i": i, "pad": "x".repeat(100)}))
+            .collect();
+        let result = PipelineClient::new(HashMap::new(), "token".to_string())
+            .unwrap()
+            .with_table_endpoints(HashMap::from([("_test".to_string(), endpoint)]))
+            .with_gzip(true)
+            .send_all(HashMap::from([("_test".to_string(), records)]))
+            .await;
+        assert_eq!(result.succeeded["_test"], 1_000);
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].0.as_deref(), Some("gzip"));
+        let decoded = String::from_utf8(gunzip(&seen[0].1)).unwrap();
+        assert_eq!(decoded.lines().count(), 1_000);
+    }
+}
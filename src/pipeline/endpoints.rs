@@ -0,0 +1,117 @@
+//! Pipeline endpoint resolution from `PIPELINE_*` variables.
+//!
+//! `PIPELINE_URL_TEMPLATE` (e.g. `https://pipe.example.com/{signal}`) gives every
+//! signal and extra table an endpoint by substituting its table name for
+//! `{signal}`. Explicit `PIPELINE_{TABLE}` variables override the template.
+
+// Only the worker builds its client from env
+#![cfg_attr(not(target_arch = "wasm32"), allow(dead_code))]
+
+use std::collections::HashMap;
+
+use crate::signal::Signal;
+
+pub const PIPELINE_URL_TEMPLATE: &str = "PIPELINE_URL_TEMPLATE";
+
+const SIGNAL_PLACEHOLDER: &str = "{signal}";
+
+/// Signal endpoints and extra table endpoints, in that order.
+/// Tables with neither an explicit variable nor a template get no endpoint.
+pub fn endpoints_from_lookup(
+    mut var: impl FnMut(&str) -> Option<String>,
+    extra_tables: &[String],
+) -> (HashMap<Signal, String>, HashMap<String, String>) {
+    let template = var(PIPELINE_URL_TEMPLATE).filter(|t| {
+        let valid = t.contains(SIGNAL_PLACEHOLDER);
+        if !valid {
+            tracing::warn!(template = %t, "ignoring PIPELINE_URL_TEMPLATE without {{signal}}");
+        }
+        valid
+    });
+    let mut resolve = |env_var: &str, table: &str| {
+        var(env_var).filter(|url| !url.is_empty()).or_else(|| {
+            template
+                .as_ref()
+                .map(|t| t.replace(SIGNAL_PLACEHOLDER, table))
+        })
+    };
+
+    let endpoints = Signal::all()
+        .iter()
+        .filter_map(|signal| {
+            resolve(signal.env_var_name(), signal.table_name()).map(|url| (*signal, url))
+        })
+        .collect();
+    let table_endpoints = extra_tables
+        .iter()
+        .filter_map(|table| {
+            let env_var = format!("PIPELINE_{}", table.to_uppercase());
+            resolve(&env_var, table).map(|url| (table.clone(), url))
+        })
+        .collect();
+    (endpoints, table_endpoints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(pairs: &[(&str, &str)]) -> impl FnMut(&str) -> Option<String> {
+        let vars: HashMap<String, String> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_template_expands_for_each_signal() {
+        let (endpoints, tables) = endpoints_from_lookup(
+            lookup(&[(PIPELINE_URL_TEMPLATE, "https://pipe.example.com/{signal}")]),
+            &["gauge_runtime".to_string()],
+        );
+        assert_eq!(endpoints.len(), Signal::all().len());
+        for signal in Signal::all() {
+            assert_eq!(
+                endpoints[signal],
+                format!("https://pipe.example.com/{}", signal.table_name())
+            );
+        }
+        assert_eq!(
+            tables["gauge_runtime"],
+            "https://pipe.example.com/gauge_runtime"
+        );
+    }
+
+    #[test]
+    fn test_explicit_vars_override_template() {
+        let (endpoints, _) = endpoints_from_lookup(
+            lookup(&[
+                (PIPELINE_URL_TEMPLATE, "https://pipe.example.com/{signal}"),
+                ("PIPELINE_LOGS", "https://logs.example.com"),
+                ("PIPELINE_TRACES", ""),
+            ]),
+            &[],
+        );
+        assert_eq!(endpoints[&Signal::Logs], "https://logs.example.com");
+        // An empty explicit var falls back to the template
+        assert_eq!(
+            endpoints[&Signal::Traces],
+            "https://pipe.example.com/traces"
+        );
+        assert_eq!(endpoints[&Signal::Gauge], "https://pipe.example.com/gauge");
+    }
+
+    #[test]
+    fn test_without_template_only_explicit_vars() {
+        let (endpoints, tables) = endpoints_from_lookup(
+            lookup(&[
+                ("PIPELINE_LOGS", "https://logs.example.com"),
+                (PIPELINE_URL_TEMPLATE, "https://no-placeholder.example.com"),
+            ]),
+            &["span_links".to_string()],
+        );
+        assert_eq!(endpoints.len(), 1);
+        assert!(tables.is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+use crate::pipeline::retry::IsRetryable;
+use crate::pipeline::sender::FailureReason;
+
+/// Errors that can occur when sending to a pipeline
+#[derive(Debug)]
+pub enum SendError {
+    Timeout,
+    /// The overall `send_all` deadline passed before this table finished
+    DeadlineExceeded,
+    Http {
+        status: u16,
+        endpoint: String,
+    },
+    Network(String),
+    Serialize(String),
+    /// The table's circuit breaker is open; nothing was sent
+    CircuitOpen,
+    /// The downstream echoed a different (or no) batch checksum
+    ChecksumMismatch {
+        sent: String,
+        echoed: Option<String>,
+    },
+}
+
+impl std::fmt::Display for SendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SendError::Timeout => write!(f, "request timed out"),
+            SendError::DeadlineExceeded => write!(f, "send deadline exceeded"),
+            SendError::Http { status, endpoint } => {
+                write!(f, "HTTP {} from {}", status, endpoint)
+            }
+            SendError::Network(msg) => write!(f, "network error: {}", msg),
+            SendError::Serialize(msg) => write!(f, "serialization error: {}", msg),
+            SendError::CircuitOpen => write!(f, "circuit breaker open, send skipped"),
+            SendError::ChecksumMismatch { sent, echoed } => write!(
+                f,
+                "checksum mismatch: sent {}, echoed {}",
+                sent,
+                echoed.as_deref().unwrap_or("none")
+            ),
+        }
+    }
+}
+
+impl SendError {
+    /// Classify this error for aggregation in metrics and logs
+    pub fn reason(&self) -> FailureReason {
+        match self {
+            SendError::Timeout | SendError::DeadlineExceeded => FailureReason::Timeout,
+            SendError::Http { status, .. } => FailureReason::from_status(*status),
+            SendError::Network(_) => FailureReason::Network,
+            SendError::Serialize(_) => FailureReason::Serialize,
+            SendError::CircuitOpen => FailureReason::CircuitOpen,
+            SendError::ChecksumMismatch { .. } => FailureReason::ChecksumMismatch,
+        }
+    }
+}
+
+impl IsRetryable for SendError {
+    fn is_retryable(&self) -> bool {
+        match self {
+            SendError::Timeout => true,
+            SendError::DeadlineExceeded => false,
+            SendError::Http { status, .. } => matches!(status, 502..=504),
+            SendError::Network(_) => true,
+            // The downstream got the batch; resending it may duplicate records
+            SendError::Serialize(_)
+            | SendError::CircuitOpen
+            | SendError::ChecksumMismatch { .. } => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn send_error_retryable_classification() {
+        assert!(SendError::Timeout.is_retryable());
+        assert!(SendError::Network("conn reset".into()).is_retryable());
+        assert!(SendError::Http {
+            status: 502,
+            endpoint: "x".into()
+        }
+        .is_retryable());
+        assert!(SendError::Http {
+            status: 503,
+            endpoint: "x".into()
+        }
+        .is_retryable());
+        assert!(SendError::Http {
+            status: 504,
+            endpoint: "x".into()
+        }
+        .is_retryable());
+        assert!(!SendError::Serialize("bad json".into()).is_retryable());
+        let mismatch = SendError::ChecksumMismatch {
+            sent: "cbf43926".into(),
+            echoed: None,
+        };
+        assert!(!mismatch.is_retryable());
+        assert_eq!(mismatch.reason(), FailureReason::ChecksumMismatch);
+        assert!(!SendError::Http {
+            status: 400,
+            endpoint: "x".into()
+        }
+        .is_retryable());
+        assert!(!SendError::Http {
+            status: 401,
+            endpoint: "x".into()
+        }
+        .is_retryable());
+        assert!(!SendError::Http {
+            status: 500,
+            endpoint: "x".into()
+        }
+        .is_retryable());
+    }
+
+    #[test]
+    fn send_error_reason_classification() {
+        let http = |status| SendError::Http {
+            status,
+            endpoint: "x".into(),
+        };
+        assert_eq!(SendError::Timeout.reason(), FailureReason::Timeout);
+        assert_eq!(http(400).reason(), FailureReason::Http4xx);
+        assert_eq!(http(403).reason(), FailureReason::Http4xx);
+        assert_eq!(http(500).reason(), FailureReason::Http5xx);
+        assert_eq!(http(503).reason(), FailureReason::Http5xx);
+        assert_eq!(
+            SendError::Network("conn reset".into()).reason(),
+            FailureReason::Network
+        );
+        assert_eq!(
+            SendError::Serialize("bad json".into()).reason(),
+            FailureReason::Serialize
+        );
+    }
+}
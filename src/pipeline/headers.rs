@@ -0,0 +1,141 @@
+//! Extra headers sent with every pipeline request.
+//!
+//! Configured as `PIPELINE_HEADER_<NAME>=value`; the name is lowercased with
+//! underscores turned into hyphens, so `PIPELINE_HEADER_X_TENANT_ID=acme`
+//! sends `x-tenant-id: acme`.
+
+use reqwest::header::{HeaderName, HeaderValue};
+use std::collections::HashMap;
+
+/// Env var prefix for extra pipeline headers
+pub const PIPELINE_HEADER_PREFIX: &str = "PIPELINE_HEADER_";
+
+/// Headers the client always sets itself
+const RESERVED_HEADERS: &[&str] = &["content-type", "authorization"];
+
+/// Collect `PIPELINE_HEADER_<NAME>` variables as header name -> value
+pub fn headers_from_vars(
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> HashMap<String, String> {
+    vars.into_iter()
+        .filter_map(|(key, value)| {
+            let name = key.strip_prefix(PIPELINE_HEADER_PREFIX)?;
+            Some((name.to_ascii_lowercase().replace('_', "-"), value))
+        })
+        .collect()
+}
+
+/// Check header names and values, rejecting invalid or reserved ones
+pub(crate) fn validate_headers(
+    headers: HashMap<String, String>,
+) -> Result<Vec<(HeaderName, HeaderValue)>, String> {
+    let mut validated = Vec::with_capacity(headers.len());
+    for (name, value) in headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| format!("invalid pipeline header name '{}'", name))?;
+        if RESERVED_HEADERS.contains(&header_name.as_str()) {
+            return Err(format!(
+                "pipeline header '{}' is set by the client and cannot be overridden",
+                name
+            ));
+        }
+        let header_value = HeaderValue::from_str(&value)
+            .map_err(|_| format!("invalid value for pipeline header '{}'", name))?;
+        validated.push((header_name, header_value));
+    }
+    validated.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+    Ok(validated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::batch::BatchMode;
+    use crate::pipeline::{PipelineClient, PipelineSender};
+    use serde_json::Value as JsonValue;
+
+    fn vars(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_headers_from_vars() {
+        let headers = headers_from_vars(vars(&[
+            ("PIPELINE_HEADER_X_TENANT_ID", "acme"),
+            ("PIPELINE_HEADER_X_API_KEY", "k1"),
+            ("PIPELINE_LOGS", "https://example.com"),
+        ]));
+        assert_eq!(headers.len(), 2);
+        assert_eq!(headers["x-tenant-id"], "acme");
+        assert_eq!(headers["x-api-key"], "k1");
+    }
+
+    #[test]
+    fn test_invalid_and_reserved_headers_rejected() {
+        let invalid = |name: &str, value: &str| {
+            validate_headers(HashMap::from([(name.to_string(), value.to_string())])).is_err()
+        };
+        assert!(invalid("bad header", "v"));
+        assert!(invalid("", "v"));
+        assert!(invalid("x-ok", "line\nbreak"));
+        assert!(invalid("Authorization", "Bearer other"));
+        assert!(invalid("content-type", "text/plain"));
+        assert!(!invalid("x-ok", "v"));
+    }
+
+    #[tokio::test]
+    async fn extra_headers_sent_with_every_request() {
+        use std::sync::{Arc, Mutex};
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let captured = seen.clone();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(move |headers: axum::http::HeaderMap| {
+                let captured = captured.clone();
+                async move {
+                    let tenant = headers.get("x-tenant-id").map(|v| v.as_bytes().to_vec());
+                    captured.lock().unwrap().push(tenant);
+                    "ok"
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let headers = headers_from_vars([(
+            "PIPELINE_HEADER_X_TENANT_ID".to_string(),
+            "acme".to_string(),
+        )]);
+        let client = PipelineClient::new(HashMap::new(), "token".to_string())
+            .expect("failed to create client")
+            .with_table_endpoints(HashMap::from([("_test".to_string(), endpoint)]))
+            .with_batch_mode(BatchMode::PerRecord)
+            .with_extra_headers(headers)
+            .expect("valid headers");
+        let records: Vec<JsonValue> = (0..2).map(JsonValue::from).collect();
+        let result = client
+            .send_all(HashMap::from([("_test".to_string(), records)]))
+            .await;
+
+        assert_eq!(result.succeeded["_test"], 2);
+        assert_eq!(*seen.lock().unwrap(), vec![Some(b"acme".to_vec()); 2]);
+    }
+
+    #[test]
+    fn invalid_extra_header_rejected_at_construction() {
+        let client = PipelineClient::new(HashMap::new(), "token".to_string())
+            .expect("failed to create client");
+        let err = client
+            .with_extra_headers(HashMap::from([("bad name".to_string(), "v".to_string())]))
+            .err()
+            .expect("invalid header name should be rejected");
+        assert!(err.contains("bad name"), "{}", err);
+    }
+}
@@ -0,0 +1,230 @@
+//! Rolling per-table send health.
+//!
+//! Each process (a worker isolate or the native server) counts its own
+//! pipeline sends over the last five minutes in one-minute buckets.
+//! `GET /v1/pipelines/health` returns that view, and `status` displays it.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+
+use super::sender::SendResult;
+
+/// How far back health counts reach
+pub const HEALTH_WINDOW_MS: u64 = 5 * 60 * 1000;
+const BUCKET_MS: u64 = 60 * 1000;
+
+/// Send outcomes for one table within the health window
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableHealth {
+    pub table: String,
+    pub successes: u64,
+    pub failures: u64,
+    /// Most recent failure message, if any failure is in the window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+impl TableHealth {
+    /// One status line, e.g. `logs pipeline: 2 failures in last 5m`
+    #[cfg(not(target_arch = "wasm32"))] // Used by the CLI `status` command
+    pub fn describe(&self) -> String {
+        let minutes = HEALTH_WINDOW_MS / BUCKET_MS;
+        match (self.failures, &self.last_error) {
+            (0, _) => format!(
+                "{} pipeline: healthy ({} sends in last {}m)",
+                self.table, self.successes, minutes
+            ),
+            (failures, error) => {
+                let plural = if failures == 1 { "" } else { "s" };
+                let mut line = format!(
+                    "{} pipeline: {} failure{} in last {}m ({} ok)",
+                    self.table, failures, plural, minutes, self.successes
+                );
+                if let Some(error) = error {
+                    line.push_str(&format!(", last error: {}", error));
+                }
+                line
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    start_ms: u64,
+    successes: u64,
+    failures: u64,
+}
+
+impl Bucket {
+    /// Whether the bucket is one of the last `HEALTH_WINDOW_MS / BUCKET_MS` minutes
+    fn in_window(&self, now_ms: u64) -> bool {
+        self.start_ms + HEALTH_WINDOW_MS > now_ms
+    }
+}
+
+#[derive(Debug, Default)]
+struct TableCounts {
+    buckets: VecDeque<Bucket>,
+    last_error: Option<String>,
+}
+
+/// Per-table rolling success/failure counts
+#[derive(Debug, Default)]
+pub(crate) struct HealthTracker {
+    tables: HashMap<String, TableCounts>,
+}
+
+impl HealthTracker {
+    /// Count one send for `table`; `error` is the failure message, if it failed
+    pub(crate) fn record(&mut self, table: &str, error: Option<&str>, now_ms: u64) {
+        let counts = self.tables.entry(table.to_string()).or_default();
+        let start_ms = now_ms - now_ms % BUCKET_MS;
+        if counts.buckets.back().is_none_or(|b| b.start_ms != start_ms) {
+            counts.buckets.push_back(Bucket {
+                start_ms,
+                successes: 0,
+                failures: 0,
+            });
+        }
+        while counts.buckets.front().is_some_and(|b| !b.in_window(now_ms)) {
+            counts.buckets.pop_front();
+        }
+
+        let bucket = counts.buckets.back_mut().expect("bucket just pushed");
+        match error {
+            Some(error) => {
+                bucket.failures += 1;
+                counts.last_error = Some(error.to_string());
+            }
+            None => bucket.successes += 1,
+        }
+    }
+
+    /// Counts per table within the window, sorted by table; idle tables are omitted
+    pub(crate) fn snapshot(&self, now_ms: u64) -> Vec<TableHealth> {
+        let mut report: Vec<TableHealth> = self
+            .tables
+            .iter()
+            .filter_map(|(table, counts)| {
+                let live = counts.buckets.iter().filter(|b| b.in_window(now_ms));
+                let (successes, failures) =
+                    live.fold((0, 0), |(s, f), b| (s + b.successes, f + b.failures));
+                (successes + failures > 0).then(|| TableHealth {
+                    table: table.clone(),
+                    successes,
+                    failures,
+                    last_error: (failures > 0).then(|| counts.last_error.clone()).flatten(),
+                })
+            })
+            .collect();
+        report.sort_by(|a, b| a.table.cmp(&b.table));
+        report
+    }
+}
+
+fn tracker() -> std::sync::MutexGuard<'static, HealthTracker> {
+    static TRACKER: OnceLock<Mutex<HealthTracker>> = OnceLock::new();
+    TRACKER
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Count every table in a `send_all` result
+pub(crate) fn record_result(result: &SendResult) {
+    let now_ms = current_time_ms();
+    let mut tracker = tracker();
+    for table in result.succeeded.keys() {
+        tracker.record(table, None, now_ms);
+    }
+    for (table, failure) in &result.failed {
+        tracker.record(table, Some(&failure.message), now_ms);
+    }
+}
+
+/// This process's view of recent send health
+pub fn snapshot() -> Vec<TableHealth> {
+    tracker().snapshot(current_time_ms())
+}
+
+#[cfg(target_arch = "wasm32")]
+pub(super) fn current_time_ms() -> u64 {
+    worker::Date::now().as_millis()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) fn current_time_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: u64 = 1_705_327_800_000;
+
+    #[test]
+    fn test_counts_roll_out_of_the_window() {
+        let mut tracker = HealthTracker::default();
+        tracker.record("logs", Some("HTTP 503"), T0);
+        tracker.record("logs", None, T0 + 1_000);
+        tracker.record("logs", Some("timeout"), T0 + 2 * BUCKET_MS);
+        tracker.record("traces", None, T0);
+
+        let report = tracker.snapshot(T0 + 2 * BUCKET_MS);
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].table, "logs");
+        assert_eq!((report[0].successes, report[0].failures), (1, 2));
+        assert_eq!(report[0].last_error.as_deref(), Some("timeout"));
+
+        // The first minute has aged out; the traces table is idle and dropped
+        let report = tracker.snapshot(T0 + HEALTH_WINDOW_MS + BUCKET_MS);
+        assert_eq!(report.len(), 1);
+        assert_eq!((report[0].successes, report[0].failures), (0, 1));
+
+        assert!(tracker.snapshot(T0 + 10 * HEALTH_WINDOW_MS).is_empty());
+    }
+
+    #[test]
+    fn test_old_buckets_are_pruned() {
+        let mut tracker = HealthTracker::default();
+        for minute in 0..30 {
+            tracker.record("logs", None, T0 + minute * BUCKET_MS);
+        }
+        assert_eq!(tracker.tables["logs"].buckets.len(), 5);
+        assert_eq!(tracker.snapshot(T0 + 29 * BUCKET_MS)[0].successes, 5);
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut health = TableHealth {
+            table: "logs".to_string(),
+            successes: 12,
+            failures: 0,
+            last_error: None,
+        };
+        assert_eq!(
+            health.describe(),
+            "logs pipeline: healthy (12 sends in last 5m)"
+        );
+
+        health.failures = 2;
+        health.last_error = Some("HTTP 503".to_string());
+        assert_eq!(
+            health.describe(),
+            "logs pipeline: 2 failures in last 5m (12 ok), last error: HTTP 503"
+        );
+
+        health.failures = 1;
+        health.last_error = None;
+        assert_eq!(
+            health.describe(),
+            "logs pipeline: 1 failure in last 5m (12 ok)"
+        );
+    }
+}
@@ -1,7 +1,24 @@
 // src/pipeline/mod.rs
+pub mod auth;
+mod batch;
+pub mod body_size;
+pub mod checksum;
 pub mod client;
+pub mod compression;
+mod endpoints;
+mod error;
+mod headers;
+pub mod health;
 pub mod retry;
+pub mod send_guard;
 pub mod sender;
+pub mod sequence;
+pub mod signal_policy;
 
+#[cfg(target_arch = "wasm32")]
+mod worker_env;
+
+pub(crate) use batch::{reject_invalid_from, skip_validation_from};
 pub use client::PipelineClient;
-pub use sender::{PipelineSender, SendResult};
+pub use headers::headers_from_vars;
+pub use sender::{FailureReason, PipelineSender, SendFailure, SendResult};
@@ -1,22 +1,38 @@
+use futures::future::{select, Either};
 use std::future::Future;
+use std::pin::pin;
 use std::time::Duration;
 
+/// Default jitter fraction for exponential backoff (full jitter)
+pub const DEFAULT_JITTER: f64 = 1.0;
+
+/// Default cap for exponential backoff
+pub const DEFAULT_MAX_DELAY_MS: u64 = 10_000;
+
 /// Backoff strategy for retries
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub enum BackoffStrategy {
     /// Fixed delay between retries
-    #[default]
+    #[allow(dead_code)] // Opt-in for callers that build a RetryConfig directly
     Fixed,
-    /// Exponential backoff with jitter: delay = min(base * 2^attempt + jitter, max)
-    /// Used by Lambda for AWS API compatibility.
-    #[allow(dead_code)] // Used with lambda feature
-    ExponentialWithJitter { base_ms: u64, max_ms: u64 },
+    /// Exponential backoff with jitter, capped before jittering:
+    /// cap = min(base * 2^attempt, max),
+    /// delay = cap * (1 - jitter) + cap * jitter * random,
+    /// where `jitter` (0.0-1.0) is the fraction of the delay drawn at random.
+    /// The default full jitter (1.0) picks uniformly from `[0, cap)`, as
+    /// recommended for AWS APIs.
+    ExponentialWithJitter {
+        base_ms: u64,
+        max_ms: u64,
+        jitter: f64,
+    },
 }
 
 /// Retry configuration
 #[derive(Clone, Debug)]
 pub struct RetryConfig {
     pub max_attempts: u32,
+    /// Delay between retries with `Fixed` backoff
     pub delay: Duration,
     pub backoff: BackoffStrategy,
 }
@@ -26,7 +42,11 @@ impl Default for RetryConfig {
         Self {
             max_attempts: 3, // 1 initial + 2 retries
             delay: Duration::from_millis(500),
-            backoff: BackoffStrategy::Fixed,
+            backoff: BackoffStrategy::ExponentialWithJitter {
+                base_ms: 500,
+                max_ms: DEFAULT_MAX_DELAY_MS,
+                jitter: DEFAULT_JITTER,
+            },
         }
     }
 }
@@ -37,47 +57,81 @@ impl RetryConfig {
     pub fn exponential(max_attempts: u32, base_ms: u64, max_ms: u64) -> Self {
         Self {
             max_attempts,
-            delay: Duration::from_millis(base_ms), // Used as base for exponential
-            backoff: BackoffStrategy::ExponentialWithJitter { base_ms, max_ms },
+            delay: Duration::from_millis(base_ms),
+            backoff: BackoffStrategy::ExponentialWithJitter {
+                base_ms,
+                max_ms,
+                jitter: DEFAULT_JITTER,
+            },
         }
     }
 
-    /// Calculate delay for a given attempt number
-    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
-        match &self.backoff {
+    /// Delay before the first retry
+    pub fn base_delay(&self) -> Duration {
+        match self.backoff {
             BackoffStrategy::Fixed => self.delay,
-            BackoffStrategy::ExponentialWithJitter { base_ms, max_ms } => {
-                let base = base_ms.saturating_mul(2_u64.saturating_pow(attempt));
-                let jitter = random_jitter(base / 2);
-                let total = base.saturating_add(jitter).min(*max_ms);
-                Duration::from_millis(total)
+            BackoffStrategy::ExponentialWithJitter { base_ms, .. } => {
+                Duration::from_millis(base_ms)
             }
         }
     }
-}
 
-/// Generate random jitter up to max_jitter
-fn random_jitter(max_jitter: u64) -> u64 {
-    if max_jitter == 0 {
-        return 0;
+    /// Longest delay between retries, before jitter
+    pub fn max_delay(&self) -> Duration {
+        match self.backoff {
+            BackoffStrategy::Fixed => self.delay,
+            BackoffStrategy::ExponentialWithJitter { max_ms, .. } => Duration::from_millis(max_ms),
+        }
     }
-    #[cfg(target_arch = "wasm32")]
-    {
-        // Use js_sys for WASM randomness
-        (js_sys::Math::random() * max_jitter as f64) as u64
+
+    /// Fraction of each delay drawn at random
+    pub fn jitter(&self) -> f64 {
+        match self.backoff {
+            BackoffStrategy::Fixed => 0.0,
+            BackoffStrategy::ExponentialWithJitter { jitter, .. } => jitter.clamp(0.0, 1.0),
+        }
     }
-    #[cfg(all(not(target_arch = "wasm32"), feature = "lambda"))]
-    {
-        use rand::Rng;
-        rand::thread_rng().gen_range(0..=max_jitter)
+
+    /// Calculate delay for a given attempt number
+    pub fn delay_for_attempt(&self, attempt: u32) -> Duration {
+        self.delay_for_attempt_with(attempt, random_unit)
     }
-    // For native builds without lambda feature (tests), use deterministic half of max
-    #[cfg(all(not(target_arch = "wasm32"), not(feature = "lambda")))]
-    {
-        max_jitter / 2
+
+    /// Calculate delay for a given attempt number, drawing jitter from
+    /// `random` (uniform in `[0, 1)`)
+    pub fn delay_for_attempt_with(&self, attempt: u32, random: impl FnOnce() -> f64) -> Duration {
+        match self.backoff {
+            BackoffStrategy::Fixed => self.delay,
+            BackoffStrategy::ExponentialWithJitter {
+                base_ms, max_ms, ..
+            } => {
+                let cap = base_ms
+                    .saturating_mul(2_u64.saturating_pow(attempt))
+                    .min(max_ms) as f64;
+                let jitter = self.jitter();
+                let random = random().clamp(0.0, 1.0);
+                Duration::from_millis((cap * (1.0 - jitter) + cap * jitter * random) as u64)
+            }
+        }
     }
 }
 
+/// Uniform random number in `[0, 1)` for backoff jitter
+#[cfg(target_arch = "wasm32")]
+fn random_unit() -> f64 {
+    js_sys::Math::random()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn random_unit() -> f64 {
+    use std::hash::{BuildHasher, Hasher};
+    // RandomState is randomly keyed per instance, which is plenty for jitter
+    let bits = std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish();
+    (bits >> 11) as f64 / (1_u64 << 53) as f64
+}
+
 /// Trait for errors that may be retryable
 pub trait IsRetryable {
     fn is_retryable(&self) -> bool;
@@ -85,11 +139,27 @@ pub trait IsRetryable {
 
 /// Execute an async operation with retries.
 /// Only retries on transient errors (as determined by IsRetryable trait).
-pub async fn with_retry<F, Fut, T, E>(config: &RetryConfig, mut operation: F) -> Result<T, E>
+pub async fn with_retry<F, Fut, T, E>(config: &RetryConfig, operation: F) -> Result<T, E>
 where
     F: FnMut() -> Fut,
     Fut: Future<Output = Result<T, E>>,
     E: IsRetryable,
+{
+    with_retry_using(config, operation, sleep).await
+}
+
+/// `with_retry` with an injectable sleep, so retry timing can be observed in tests
+pub async fn with_retry_using<F, Fut, T, E, S, SFut>(
+    config: &RetryConfig,
+    mut operation: F,
+    mut sleep: S,
+) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+    E: IsRetryable,
+    S: FnMut(Duration) -> SFut,
+    SFut: Future<Output = ()>,
 {
     let attempts = config.max_attempts.max(1);
     let mut last_error: Option<E> = None;
@@ -115,6 +185,18 @@ where
     Err(last_error.expect("retry loop should have returned an error"))
 }
 
+/// Race `future` against `deadline`, returning `None` if the deadline passes first.
+/// With no deadline the future runs to completion.
+pub async fn with_deadline<F: Future>(deadline: Option<Duration>, future: F) -> Option<F::Output> {
+    let Some(deadline) = deadline else {
+        return Some(future.await);
+    };
+    match select(pin!(future), pin!(sleep(deadline))).await {
+        Either::Left((output, _)) => Some(output),
+        Either::Right(_) => None,
+    }
+}
+
 // Platform-specific sleep implementation
 #[cfg(target_arch = "wasm32")]
 async fn sleep(duration: Duration) {
@@ -220,4 +302,95 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(call_count.load(Ordering::SeqCst), 1);
     }
+
+    #[test]
+    fn exponential_backoff_is_capped_and_jittered() {
+        let config = RetryConfig {
+            max_attempts: 5,
+            backoff: BackoffStrategy::ExponentialWithJitter {
+                base_ms: 100,
+                max_ms: 500,
+                jitter: 0.5,
+            },
+            ..RetryConfig::default()
+        };
+        let delay = |attempt, random| config.delay_for_attempt_with(attempt, || random);
+
+        // Half jitter randomizes the upper half of the capped delay
+        assert_eq!(delay(0, 0.0), Duration::from_millis(50));
+        assert_eq!(delay(2, 0.0), Duration::from_millis(200));
+        assert_eq!(delay(3, 0.0), Duration::from_millis(250));
+        assert_eq!(delay(1, 1.0), Duration::from_millis(200));
+        assert_eq!(delay(1, 0.5), Duration::from_millis(150));
+        assert_eq!(delay(30, 1.0), Duration::from_millis(500));
+
+        assert_eq!(config.base_delay(), Duration::from_millis(100));
+        assert_eq!(config.max_delay(), Duration::from_millis(500));
+        assert_eq!(config.jitter(), 0.5);
+    }
+
+    #[test]
+    fn default_backoff_is_capped_full_jitter() {
+        let config = RetryConfig::default();
+        let delay = |attempt, random| config.delay_for_attempt_with(attempt, || random);
+
+        assert_eq!(config.jitter(), 1.0);
+        assert_eq!(delay(0, 0.0), Duration::ZERO);
+        assert_eq!(delay(0, 0.5), Duration::from_millis(250));
+        assert_eq!(delay(1, 0.5), Duration::from_millis(500));
+        // The cap applies before jitter, so large attempts still spread out
+        assert_eq!(
+            delay(30, 0.5),
+            Duration::from_millis(DEFAULT_MAX_DELAY_MS / 2)
+        );
+        assert_eq!(delay(30, 1.0), Duration::from_millis(DEFAULT_MAX_DELAY_MS));
+    }
+
+    #[tokio::test]
+    async fn retry_sleeps_follow_backoff() {
+        let config = RetryConfig {
+            max_attempts: 4,
+            delay: Duration::from_millis(10),
+            backoff: BackoffStrategy::ExponentialWithJitter {
+                base_ms: 10,
+                max_ms: 25,
+                jitter: 0.0,
+            },
+        };
+        let slept = std::sync::Mutex::new(Vec::new());
+
+        let result: Result<(), TestError> = with_retry_using(
+            &config,
+            || async { Err(TestError { retryable: true }) },
+            |delay| {
+                slept.lock().unwrap().push(delay.as_millis());
+                async {}
+            },
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(*slept.lock().unwrap(), vec![10, 20, 25]);
+    }
+
+    #[test]
+    fn random_unit_is_in_range() {
+        for _ in 0..100 {
+            assert!((0.0..1.0).contains(&random_unit()));
+        }
+    }
+
+    #[tokio::test]
+    async fn deadline_cancels_slow_future() {
+        let slow = tokio::time::sleep(Duration::from_secs(10));
+        let result = with_deadline(Some(Duration::from_millis(10)), slow).await;
+        assert!(result.is_none());
+
+        let fast = async { 42 };
+        assert_eq!(
+            with_deadline(Some(Duration::from_secs(10)), fast).await,
+            Some(42)
+        );
+        assert_eq!(with_deadline(None, async { 7 }).await, Some(7));
+    }
 }
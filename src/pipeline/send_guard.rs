@@ -0,0 +1,113 @@
+//! Per-table guards around a pipeline send.
+//!
+//! Each table's send first asks its circuit breaker for admission, then runs
+//! under the overall `send_all` deadline (`PIPELINE_SEND_DEADLINE_MS`,
+//! default 25s, `0` disables), and finally reports its outcome back to the
+//! breaker.
+
+use std::future::Future;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use super::error::SendError;
+use super::retry::with_deadline;
+use super::signal_policy::SendPolicy;
+
+/// Default overall deadline for `send_all`, retries included
+pub(crate) const DEFAULT_SEND_DEADLINE: Duration = Duration::from_secs(25);
+
+/// Parse `PIPELINE_SEND_DEADLINE_MS`; unset or invalid uses the default, `0` disables
+pub(crate) fn send_deadline_from(value: Option<String>) -> Option<Duration> {
+    match value.and_then(|v| v.trim().parse::<u64>().ok()) {
+        Some(0) => None,
+        Some(ms) => Some(Duration::from_millis(ms)),
+        None => Some(DEFAULT_SEND_DEADLINE),
+    }
+}
+
+/// Run `send` for `table` if its breaker admits it, failing with
+/// `DeadlineExceeded` once `deadline` passes, and record the outcome
+pub(crate) async fn guarded_send<F>(
+    table: &str,
+    policy: &SendPolicy,
+    deadline: Option<Duration>,
+    send: F,
+) -> Result<usize, SendError>
+where
+    F: Future<Output = Result<usize, SendError>>,
+{
+    if let Err(e) = policy.admit() {
+        debug!(table, "circuit breaker open, skipping send");
+        return Err(e);
+    }
+    let result = with_deadline(deadline, send).await.unwrap_or_else(|| {
+        warn!(table, ?deadline, "send deadline exceeded");
+        Err(SendError::DeadlineExceeded)
+    });
+    policy.record(&result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::{FailureReason, PipelineClient, PipelineSender};
+    use serde_json::Value as JsonValue;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn send_deadline_fails_slow_tables_promptly() {
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(|| async {
+                tokio::time::sleep(Duration::from_secs(3)).await;
+                "ok"
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let slow = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        let (fast, _) = crate::pipeline::batch::tests::counting_pipeline().await;
+
+        let tables = ["slow_a", "slow_b", "slow_c"];
+        let mut table_endpoints: HashMap<String, String> = tables
+            .iter()
+            .map(|t| (t.to_string(), slow.clone()))
+            .collect();
+        table_endpoints.insert("fast".to_string(), fast);
+        let client = PipelineClient::new(HashMap::new(), "token".to_string())
+            .expect("failed to create client")
+            .with_table_endpoints(table_endpoints.clone())
+            .with_send_deadline(Some(Duration::from_millis(300)));
+        let grouped = table_endpoints
+            .into_keys()
+            .map(|t| (t, vec![JsonValue::from(1)]))
+            .collect();
+
+        let started = std::time::Instant::now();
+        let result = client.send_all(grouped).await;
+
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(result.succeeded["fast"], 1);
+        for table in tables {
+            assert_eq!(result.failed[table].reason, FailureReason::Timeout);
+            assert_eq!(result.failed[table].message, "send deadline exceeded");
+        }
+    }
+
+    #[test]
+    fn send_deadline_from_env_value() {
+        assert_eq!(send_deadline_from(None), Some(DEFAULT_SEND_DEADLINE));
+        assert_eq!(send_deadline_from(Some("0".into())), None);
+        assert_eq!(
+            send_deadline_from(Some("1500".into())),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            send_deadline_from(Some("soon".into())),
+            Some(DEFAULT_SEND_DEADLINE)
+        );
+    }
+}
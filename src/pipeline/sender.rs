@@ -2,11 +2,104 @@
 use serde_json::Value;
 use std::collections::HashMap;
 
+/// Classified reason for a failed pipeline send
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailureReason {
+    Timeout,
+    Http4xx,
+    Http5xx,
+    Network,
+    NoEndpoint,
+    Serialize,
+    /// Skipped because the table's circuit breaker is open
+    CircuitOpen,
+    /// The downstream did not echo the batch checksum that was sent
+    ChecksumMismatch,
+}
+
+impl FailureReason {
+    /// Classify a non-success HTTP status (5xx vs everything else)
+    pub fn from_status(status: u16) -> Self {
+        if status >= 500 {
+            FailureReason::Http5xx
+        } else {
+            FailureReason::Http4xx
+        }
+    }
+}
+
+impl std::fmt::Display for FailureReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            FailureReason::Timeout => "timeout",
+            FailureReason::Http4xx => "http_4xx",
+            FailureReason::Http5xx => "http_5xx",
+            FailureReason::Network => "network",
+            FailureReason::NoEndpoint => "no_endpoint",
+            FailureReason::Serialize => "serialize",
+            FailureReason::CircuitOpen => "circuit_open",
+            FailureReason::ChecksumMismatch => "checksum_mismatch",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A failed send: classified reason plus the human-readable message
+#[derive(Debug, Clone, PartialEq)]
+pub struct SendFailure {
+    pub reason: FailureReason,
+    pub message: String,
+}
+
+impl SendFailure {
+    pub fn new(reason: FailureReason, message: impl Into<String>) -> Self {
+        Self {
+            reason,
+            message: message.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for SendFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Rejection reasons kept per table; the count covers every rejected record
+pub const MAX_REJECTION_REASONS: usize = 5;
+
 /// Result of sending to multiple pipelines
 #[derive(Debug, Default)]
 pub struct SendResult {
     pub succeeded: HashMap<String, usize>,
-    pub failed: HashMap<String, String>,
+    pub failed: HashMap<String, SendFailure>,
+    /// Records dropped for failing schema validation while the rest of the table was sent
+    pub rejected: HashMap<String, usize>,
+    /// The first `MAX_REJECTION_REASONS` rejection reasons per table
+    pub rejection_reasons: HashMap<String, Vec<String>>,
+}
+
+impl SendResult {
+    /// Count failed tables per failure reason
+    pub fn failure_counts(&self) -> HashMap<FailureReason, usize> {
+        let mut counts = HashMap::new();
+        for failure in self.failed.values() {
+            *counts.entry(failure.reason).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Record per-record rejections for a table, keeping a sample of reasons
+    pub fn add_rejections(&mut self, table: &str, reasons: Vec<String>) {
+        if reasons.is_empty() {
+            return;
+        }
+        *self.rejected.entry(table.to_string()).or_insert(0) += reasons.len();
+        let kept = self.rejection_reasons.entry(table.to_string()).or_default();
+        let room = MAX_REJECTION_REASONS.saturating_sub(kept.len());
+        kept.extend(reasons.into_iter().take(room));
+    }
 }
 
 /// Trait for sending batches to pipelines (abstracts HTTP client)
@@ -15,3 +108,54 @@ pub struct SendResult {
 pub trait PipelineSender {
     async fn send_all(&self, grouped: HashMap<String, Vec<Value>>) -> SendResult;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_status_classification() {
+        assert_eq!(FailureReason::from_status(401), FailureReason::Http4xx);
+        assert_eq!(FailureReason::from_status(429), FailureReason::Http4xx);
+        assert_eq!(FailureReason::from_status(500), FailureReason::Http5xx);
+        assert_eq!(FailureReason::from_status(503), FailureReason::Http5xx);
+    }
+
+    #[test]
+    fn test_failure_counts_by_reason() {
+        let mut result = SendResult::default();
+        result.failed.insert(
+            "logs".to_string(),
+            SendFailure::new(FailureReason::Timeout, "request timed out"),
+        );
+        result.failed.insert(
+            "gauge".to_string(),
+            SendFailure::new(FailureReason::Timeout, "request timed out"),
+        );
+        result.failed.insert(
+            "sum".to_string(),
+            SendFailure::new(FailureReason::NoEndpoint, "no pipeline endpoint"),
+        );
+
+        let counts = result.failure_counts();
+        assert_eq!(counts[&FailureReason::Timeout], 2);
+        assert_eq!(counts[&FailureReason::NoEndpoint], 1);
+        assert!(!counts.contains_key(&FailureReason::Http5xx));
+    }
+
+    #[test]
+    fn test_add_rejections_counts_all_but_keeps_sample() {
+        let mut result = SendResult::default();
+        result.add_rejections("logs", Vec::new());
+        assert!(result.rejected.is_empty());
+
+        let reasons = (0..8).map(|i| format!("record {}", i)).collect();
+        result.add_rejections("logs", reasons);
+        result.add_rejections("logs", vec!["record 9".to_string()]);
+        assert_eq!(result.rejected["logs"], 9);
+        assert_eq!(
+            result.rejection_reasons["logs"].len(),
+            MAX_REJECTION_REASONS
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Per-table chunk sequence numbers.
+//!
+//! With `PIPELINE_SEQUENCE_HEADERS=true` every request carries
+//! `X-Batch-Sequence`: the chunk's position within its table's send,
+//! starting at 0. A table that fits in one body is sent as sequence 0, so
+//! consumers can reorder chunks that arrive out of order after concurrent sends.
+
+/// Header carrying the chunk's sequence number within its table's send
+pub const SEQUENCE_HEADER: &str = "X-Batch-Sequence";
+
+/// Parse `PIPELINE_SEQUENCE_HEADERS`; unset or invalid disables the header
+pub fn sequence_headers_from(value: Option<String>) -> bool {
+    value.and_then(|v| v.trim().parse().ok()).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::batch::BatchMode;
+    use crate::pipeline::{PipelineClient, PipelineSender};
+    use serde_json::Value as JsonValue;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    /// Pipeline mock recording (sequence header, record count) per request
+    async fn sequence_pipeline() -> (String, Arc<Mutex<Vec<(Option<String>, usize)>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = seen.clone();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(move |headers: axum::http::HeaderMap, body: String| {
+                let sequence = headers
+                    .get(SEQUENCE_HEADER)
+                    .and_then(|v| v.to_str().ok())
+                    .map(str::to_string);
+                recorder
+                    .lock()
+                    .unwrap()
+                    .push((sequence, body.lines().count()));
+                async { "ok" }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (endpoint, seen)
+    }
+
+    fn client(endpoint: String, enabled: bool) -> PipelineClient {
+        PipelineClient::new(HashMap::new(), "token".to_string())
+            .expect("failed to create client")
+            .with_table_endpoints(HashMap::from([("_test".to_string(), endpoint)]))
+            .with_sequence_headers(enabled)
+    }
+
+    fn records(count: usize, pad: usize) -> HashMap<String, Vec<JsonValue>> {
+        let records = (0..count)
+            .map(|i| serde_json::json!({"i": i, "pad": "x".repeat(pad)}))
+            .collect();
+        HashMap::from([("_test".to_string(), records)])
+    }
+
+    #[tokio::test]
+    async fn test_chunked_batches_carry_increasing_sequence_numbers() {
+        let (endpoint, seen) = sequence_pipeline().await;
+
+        // ~3MB of records splits into several 900KB chunks
+        let result = client(endpoint, true).send_all(records(3_000, 1_000)).await;
+        assert_eq!(result.succeeded["_test"], 3_000);
+
+        let seen = seen.lock().unwrap().clone();
+        assert!(seen.len() > 2, "expected chunking, got {}", seen.len());
+        let sequences: Vec<_> = seen.iter().map(|(s, _)| s.clone().unwrap()).collect();
+        let expected: Vec<_> = (0..seen.len()).map(|i| i.to_string()).collect();
+        assert_eq!(sequences, expected);
+        assert_eq!(seen.iter().map(|(_, n)| n).sum::<usize>(), 3_000);
+    }
+
+    #[tokio::test]
+    async fn test_single_batch_is_sequence_zero_and_off_by_default() {
+        let (endpoint, seen) = sequence_pipeline().await;
+
+        client(endpoint.clone(), true)
+            .send_all(records(2, 10))
+            .await;
+        client(endpoint.clone(), true)
+            .send_all(records(2, 10))
+            .await;
+        client(endpoint, false).send_all(records(2, 10)).await;
+
+        let seen = seen.lock().unwrap().clone();
+        assert_eq!(seen[0], (Some("0".to_string()), 2));
+        assert_eq!(seen[1], (Some("0".to_string()), 2));
+        assert_eq!(seen[2], (None, 2));
+    }
+
+    #[tokio::test]
+    async fn test_per_record_mode_numbers_each_record() {
+        let (endpoint, seen) = sequence_pipeline().await;
+
+        client(endpoint, true)
+            .with_batch_mode(BatchMode::PerRecord)
+            .send_all(records(3, 10))
+            .await;
+
+        let mut sequences: Vec<_> = seen
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(s, _)| s.clone().unwrap())
+            .collect();
+        sequences.sort();
+        assert_eq!(sequences, ["0", "1", "2"]);
+    }
+
+    #[test]
+    fn test_sequence_headers_from() {
+        assert!(sequence_headers_from(Some(" true".to_string())));
+        assert!(!sequence_headers_from(Some("yes".to_string())));
+        assert!(!sequence_headers_from(None));
+    }
+}
@@ -0,0 +1,401 @@
+//! Per-signal retry and circuit-breaker settings for pipeline sends.
+//!
+//! Each signal's table can be tuned on its own, so a failing metrics
+//! pipeline doesn't change how logs are retried. Retries default to
+//! `PIPELINE_RETRY_MAX` (attempts, including the first),
+//! `PIPELINE_RETRY_DELAY_MS`, `PIPELINE_RETRY_MAX_DELAY_MS` and
+//! `PIPELINE_RETRY_JITTER`; `PIPELINE_RETRY_<TABLE>_*` variables override
+//! them for one table, e.g. `PIPELINE_RETRY_LOGS_MAX=5`. Retries back off
+//! exponentially from `DELAY_MS` up to `MAX_DELAY_MS` (default 10s) with
+//! jitter (0.0-1.0, default 1.0 for full jitter), so concurrent retries
+//! spread out instead of hitting a recovering pipeline together.
+//! `PIPELINE_BREAKER_<TABLE>_THRESHOLD` (consecutive failed sends; 0, the
+//! default, disables the breaker) and `PIPELINE_BREAKER_<TABLE>_COOLDOWN_MS`
+//! configure each table's circuit breaker. Breaker state is kept per process
+//! (or isolate) and table, so it survives clients rebuilt for each request.
+//! Tables without a signal (routed tables) use the defaults.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use super::error::SendError;
+use super::health::current_time_ms;
+use super::retry::{BackoffStrategy, RetryConfig, DEFAULT_JITTER, DEFAULT_MAX_DELAY_MS};
+use crate::aggregator::CircuitBreaker;
+use crate::parse_or;
+use crate::signal::Signal;
+
+/// Retry and breaker settings for one signal's sends
+#[derive(Debug)]
+pub struct SendPolicy {
+    pub retry: RetryConfig,
+    breaker: Arc<CircuitBreaker>,
+}
+
+impl Default for SendPolicy {
+    fn default() -> Self {
+        Self::new("default", RetryConfig::default(), 0, 0)
+    }
+}
+
+impl SendPolicy {
+    /// A breaker threshold of 0 disables the breaker
+    pub fn new(
+        table: &str,
+        retry: RetryConfig,
+        breaker_threshold: u32,
+        breaker_cooldown_ms: u64,
+    ) -> Self {
+        Self {
+            retry,
+            breaker: shared_breaker(table, breaker_threshold, breaker_cooldown_ms),
+        }
+    }
+
+    /// Fail fast while the breaker is open
+    pub(crate) fn admit(&self) -> Result<(), SendError> {
+        if self.breaker.allow(current_time_ms()) {
+            Ok(())
+        } else {
+            Err(SendError::CircuitOpen)
+        }
+    }
+
+    /// Count a finished send towards the breaker
+    pub(crate) fn record<T>(&self, result: &Result<T, SendError>) {
+        if !matches!(result, Err(SendError::CircuitOpen)) {
+            self.breaker.record(result.is_ok(), current_time_ms());
+        }
+    }
+}
+
+/// Send policies keyed by signal, with a default for everything else
+#[derive(Debug, Default)]
+pub struct SendPolicies {
+    default: SendPolicy,
+    per_signal: HashMap<Signal, SendPolicy>,
+}
+
+impl SendPolicies {
+    /// Build from `PIPELINE_RETRY_*` / `PIPELINE_BREAKER_*` variables.
+    /// Signals with none of their own variables set use the default policy.
+    pub fn from_lookup(mut var: impl FnMut(&str) -> Option<String>) -> Self {
+        let global = RetryVars::read(|suffix| var(&format!("PIPELINE_RETRY_{suffix}")));
+        let mut per_signal = HashMap::new();
+        for signal in Signal::all() {
+            let table = signal.table_name().to_uppercase();
+            let retry = RetryVars::read(|suffix| var(&format!("PIPELINE_RETRY_{table}_{suffix}")));
+            let threshold = var(&format!("PIPELINE_BREAKER_{table}_THRESHOLD"));
+            let cooldown_ms = var(&format!("PIPELINE_BREAKER_{table}_COOLDOWN_MS"));
+            if retry.is_empty() && threshold.is_none() && cooldown_ms.is_none() {
+                continue;
+            }
+            let retry = retry.or(&global).config();
+            tracing::debug!(
+                table = signal.table_name(),
+                max_attempts = retry.max_attempts,
+                base_delay_ms = retry.base_delay().as_millis() as u64,
+                max_delay_ms = retry.max_delay().as_millis() as u64,
+                jitter = retry.jitter(),
+                "pipeline retry policy"
+            );
+            let policy = SendPolicy::new(
+                signal.table_name(),
+                retry,
+                parse_or(threshold, 0),
+                parse_or(cooldown_ms, DEFAULT_COOLDOWN_MS),
+            );
+            per_signal.insert(*signal, policy);
+        }
+        Self {
+            default: SendPolicy::new("default", global.config(), 0, 0),
+            per_signal,
+        }
+    }
+
+    /// Set the policy for one signal
+    #[cfg(test)]
+    pub fn with_policy(mut self, signal: Signal, policy: SendPolicy) -> Self {
+        self.per_signal.insert(signal, policy);
+        self
+    }
+
+    /// The policy for a table, falling back to the default
+    pub fn for_table(&self, table: &str) -> &SendPolicy {
+        Signal::from_table_name(table)
+            .and_then(|signal| self.per_signal.get(&signal))
+            .unwrap_or(&self.default)
+    }
+}
+
+/// Raw `PIPELINE_RETRY_*` values for one scope (global or a table)
+#[derive(Debug, Clone, Default)]
+struct RetryVars {
+    max: Option<String>,
+    delay_ms: Option<String>,
+    max_delay_ms: Option<String>,
+    jitter: Option<String>,
+}
+
+impl RetryVars {
+    /// Read `MAX`, `DELAY_MS`, `MAX_DELAY_MS` and `JITTER` via `var(suffix)`
+    fn read(mut var: impl FnMut(&str) -> Option<String>) -> Self {
+        Self {
+            max: var("MAX"),
+            delay_ms: var("DELAY_MS"),
+            max_delay_ms: var("MAX_DELAY_MS"),
+            jitter: var("JITTER"),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.max.is_none()
+            && self.delay_ms.is_none()
+            && self.max_delay_ms.is_none()
+            && self.jitter.is_none()
+    }
+
+    /// Fill unset values from `fallback`
+    fn or(self, fallback: &Self) -> Self {
+        Self {
+            max: self.max.or_else(|| fallback.max.clone()),
+            delay_ms: self.delay_ms.or_else(|| fallback.delay_ms.clone()),
+            max_delay_ms: self.max_delay_ms.or_else(|| fallback.max_delay_ms.clone()),
+            jitter: self.jitter.or_else(|| fallback.jitter.clone()),
+        }
+    }
+
+    fn config(&self) -> RetryConfig {
+        let defaults = RetryConfig::default();
+        let delay_ms = parse_or(self.delay_ms.clone(), defaults.delay.as_millis() as u64);
+        let backoff = BackoffStrategy::ExponentialWithJitter {
+            base_ms: delay_ms,
+            max_ms: parse_or(self.max_delay_ms.clone(), DEFAULT_MAX_DELAY_MS),
+            jitter: parse_or(self.jitter.clone(), DEFAULT_JITTER).clamp(0.0, 1.0),
+        };
+        RetryConfig {
+            max_attempts: parse_or(self.max.clone(), defaults.max_attempts).max(1),
+            delay: Duration::from_millis(delay_ms),
+            backoff,
+        }
+    }
+}
+
+/// The breaker for `table` shared by every client in this process (or
+/// isolate); clients are rebuilt per request, so their own state would be lost
+fn shared_breaker(table: &str, threshold: u32, cooldown_ms: u64) -> Arc<CircuitBreaker> {
+    type Breakers = HashMap<(String, u32, u64), Arc<CircuitBreaker>>;
+    static BREAKERS: OnceLock<Mutex<Breakers>> = OnceLock::new();
+    let label = format!("pipeline sends to {table}");
+    if threshold == 0 {
+        return Arc::new(CircuitBreaker::new(label, 0, cooldown_ms));
+    }
+    BREAKERS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .entry((table.to_string(), threshold, cooldown_ms))
+        .or_insert_with(|| Arc::new(CircuitBreaker::new(label, threshold, cooldown_ms)))
+        .clone()
+}
+
+/// Breaker cooldown when only a threshold is configured
+const DEFAULT_COOLDOWN_MS: u64 = 30_000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pipeline::{FailureReason, PipelineClient, PipelineSender};
+    use serde_json::Value as JsonValue;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Pipeline mock that always answers 503, returning (endpoint, request count)
+    async fn failing_pipeline() -> (String, Arc<AtomicUsize>) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        let app = axum::Router::new().route(
+            "/",
+            axum::routing::post(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                async { (axum::http::StatusCode::SERVICE_UNAVAILABLE, "down") }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+        (endpoint, count)
+    }
+
+    fn vars(pairs: &'static [(&'static str, &'static str)]) -> impl FnMut(&str) -> Option<String> {
+        |name| {
+            pairs
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    /// Client for `endpoints`; test records are bare numbers, so skip validation
+    fn client(endpoints: HashMap<Signal, String>, policies: SendPolicies) -> PipelineClient {
+        PipelineClient::new(endpoints, "token".to_string())
+            .expect("failed to create client")
+            .with_send_policies(policies)
+            .with_skip_schema_validation(["logs", "gauge", "sum"].map(String::from).into())
+    }
+
+    #[test]
+    fn test_from_lookup_is_per_signal() {
+        let policies = SendPolicies::from_lookup(vars(&[
+            ("PIPELINE_RETRY_LOGS_MAX", "5"),
+            ("PIPELINE_RETRY_GAUGE_MAX", "0"),
+            ("PIPELINE_BREAKER_GAUGE_THRESHOLD", "2"),
+        ]));
+        assert_eq!(policies.for_table("logs").retry.max_attempts, 5);
+        assert_eq!(policies.for_table("gauge").retry.max_attempts, 1);
+        assert_eq!(policies.for_table("traces").retry.max_attempts, 3);
+        assert_eq!(policies.for_table("gauge_runtime").retry.max_attempts, 3);
+    }
+
+    #[test]
+    fn test_retries_default_to_capped_full_jitter() {
+        let policies = SendPolicies::from_lookup(vars(&[
+            ("PIPELINE_RETRY_LOGS_DELAY_MS", "100"),
+            ("PIPELINE_RETRY_LOGS_MAX_DELAY_MS", "300"),
+            ("PIPELINE_RETRY_SUM_JITTER", "0.5"),
+        ]));
+        let logs = &policies.for_table("logs").retry;
+        assert_eq!(logs.jitter(), DEFAULT_JITTER);
+        assert_eq!(logs.delay_for_attempt_with(1, || 0.0).as_millis(), 0);
+        assert_eq!(logs.delay_for_attempt_with(1, || 0.5).as_millis(), 100);
+        assert_eq!(logs.delay_for_attempt_with(4, || 0.5).as_millis(), 150);
+
+        let sum = &policies.for_table("sum").retry;
+        assert_eq!(sum.jitter(), 0.5);
+        assert_eq!(sum.max_delay(), Duration::from_millis(DEFAULT_MAX_DELAY_MS));
+        assert_eq!(sum.delay_for_attempt_with(0, || 0.0).as_millis(), 250);
+
+        let traces = &policies.for_table("traces").retry;
+        assert!(matches!(
+            traces.backoff,
+            BackoffStrategy::ExponentialWithJitter { .. }
+        ));
+        assert_eq!(traces.jitter(), DEFAULT_JITTER);
+        assert_eq!(traces.base_delay(), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_table_values_override_global_defaults() {
+        let policies = SendPolicies::from_lookup(vars(&[
+            ("PIPELINE_RETRY_MAX", "6"),
+            ("PIPELINE_RETRY_DELAY_MS", "50"),
+            ("PIPELINE_RETRY_JITTER", "0.2"),
+            ("PIPELINE_RETRY_LOGS_DELAY_MS", "200"),
+            ("PIPELINE_RETRY_GAUGE_MAX", "2"),
+        ]));
+        let logs = &policies.for_table("logs").retry;
+        assert_eq!(logs.max_attempts, 6);
+        assert_eq!(logs.base_delay(), Duration::from_millis(200));
+        assert_eq!(logs.jitter(), 0.2);
+
+        let gauge = &policies.for_table("gauge").retry;
+        assert_eq!(gauge.max_attempts, 2);
+        assert_eq!(gauge.base_delay(), Duration::from_millis(50));
+
+        // Signals without their own variables and routed tables get the globals
+        for table in ["traces", "gauge_runtime"] {
+            let retry = &policies.for_table(table).retry;
+            assert_eq!(retry.max_attempts, 6);
+            assert_eq!(retry.base_delay(), Duration::from_millis(50));
+            assert_eq!(retry.jitter(), 0.2);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_logs_and_metrics_use_their_own_retry_counts() {
+        let (logs_endpoint, logs_count) = failing_pipeline().await;
+        let (gauge_endpoint, gauge_count) = failing_pipeline().await;
+        let retry = |max_attempts| RetryConfig {
+            max_attempts,
+            delay: Duration::from_millis(1),
+            backoff: BackoffStrategy::Fixed,
+        };
+        let policies = SendPolicies::default()
+            .with_policy(Signal::Logs, SendPolicy::new("logs", retry(4), 0, 0))
+            .with_policy(Signal::Gauge, SendPolicy::new("gauge", retry(1), 0, 0));
+        let client = client(
+            HashMap::from([
+                (Signal::Logs, logs_endpoint),
+                (Signal::Gauge, gauge_endpoint),
+            ]),
+            policies,
+        );
+
+        let grouped = HashMap::from([
+            ("logs".to_string(), vec![JsonValue::from(1)]),
+            ("gauge".to_string(), vec![JsonValue::from(1)]),
+        ]);
+        let result = client.send_all(grouped).await;
+
+        assert_eq!(result.failed.len(), 2);
+        assert_eq!(logs_count.load(Ordering::SeqCst), 4);
+        assert_eq!(gauge_count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_breaker_opens_for_one_signal_only() {
+        let (endpoint, count) = failing_pipeline().await;
+        let once = RetryConfig {
+            max_attempts: 1,
+            ..RetryConfig::default()
+        };
+        let policies = SendPolicies::default()
+            .with_policy(
+                Signal::Gauge,
+                SendPolicy::new("gauge", once.clone(), 1, 60_000),
+            )
+            .with_policy(Signal::Logs, SendPolicy::new("logs", once, 0, 0));
+        let client = client(
+            HashMap::from([(Signal::Logs, endpoint.clone()), (Signal::Gauge, endpoint)]),
+            policies,
+        );
+        let grouped = || {
+            HashMap::from([
+                ("logs".to_string(), vec![JsonValue::from(1)]),
+                ("gauge".to_string(), vec![JsonValue::from(1)]),
+            ])
+        };
+
+        client.send_all(grouped()).await;
+        let result = client.send_all(grouped()).await;
+
+        assert_eq!(result.failed["gauge"].reason, FailureReason::CircuitOpen);
+        assert_eq!(result.failed["logs"].reason, FailureReason::Http5xx);
+        // Two log sends, one gauge send before its breaker opened
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_breaker_state_survives_rebuilt_clients() {
+        let (endpoint, count) = failing_pipeline().await;
+        // Each request builds a fresh client from the same settings
+        let build = || {
+            let policies = SendPolicies::from_lookup(vars(&[
+                ("PIPELINE_RETRY_SUM_MAX", "1"),
+                ("PIPELINE_BREAKER_SUM_THRESHOLD", "1"),
+                ("PIPELINE_BREAKER_SUM_COOLDOWN_MS", "45000"),
+            ]));
+            client(HashMap::from([(Signal::Sum, endpoint.clone())]), policies)
+        };
+        let grouped = || HashMap::from([("sum".to_string(), vec![JsonValue::from(1)])]);
+
+        let first = build().send_all(grouped()).await;
+        assert_eq!(first.failed["sum"].reason, FailureReason::Http5xx);
+        let second = build().send_all(grouped()).await;
+        assert_eq!(second.failed["sum"].reason, FailureReason::CircuitOpen);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,117 @@
+//! Building a `PipelineClient` from the Cloudflare Worker environment.
+
+use tracing::{info, warn};
+
+use super::auth::AuthScheme;
+use super::batch::BatchMode;
+use super::body_size::max_body_sizes_from_lookup;
+use super::checksum::ChecksumMode;
+use super::client::PipelineClient;
+use super::compression::gzip_from;
+use super::endpoints::endpoints_from_lookup;
+use super::headers::PIPELINE_HEADER_PREFIX;
+use super::send_guard::send_deadline_from;
+use super::sequence::sequence_headers_from;
+use super::signal_policy::SendPolicies;
+use super::{headers_from_vars, reject_invalid_from, skip_validation_from};
+
+impl PipelineClient {
+    /// Build from Cloudflare Worker environment.
+    /// Extra tables read their endpoint from `PIPELINE_{TABLE}` (e.g. `PIPELINE_GAUGE_RUNTIME`);
+    /// `PIPELINE_URL_TEMPLATE` fills in any table without one.
+    pub fn from_worker_env(env: &worker::Env, extra_tables: &[String]) -> worker::Result<Self> {
+        let token = env.secret("PIPELINE_AUTH_TOKEN")?.to_string();
+        let (endpoints, table_endpoints) = endpoints_from_lookup(
+            |name| env.var(name).ok().map(|v| v.to_string()),
+            extra_tables,
+        );
+
+        let batch_mode = match env.var("PIPELINE_BATCH_MODE") {
+            Ok(v) => v.to_string().parse().unwrap_or_else(|e: String| {
+                warn!(error = %e, "invalid PIPELINE_BATCH_MODE, using ndjson");
+                BatchMode::default()
+            }),
+            Err(_) => BatchMode::default(),
+        };
+
+        let auth_scheme = match env.var("PIPELINE_AUTH_SCHEME") {
+            Ok(v) => v.to_string().parse().unwrap_or_else(|e: String| {
+                warn!(error = %e, "invalid PIPELINE_AUTH_SCHEME, using bearer");
+                AuthScheme::default()
+            }),
+            Err(_) => AuthScheme::default(),
+        };
+
+        let checksum_mode = match env.var("PIPELINE_CHECKSUM") {
+            Ok(v) => v.to_string().parse().unwrap_or_else(|e: String| {
+                warn!(error = %e, "invalid PIPELINE_CHECKSUM, sending without checksums");
+                ChecksumMode::default()
+            }),
+            Err(_) => ChecksumMode::default(),
+        };
+
+        let send_deadline = send_deadline_from(
+            env.var("PIPELINE_SEND_DEADLINE_MS")
+                .ok()
+                .map(|v| v.to_string()),
+        );
+
+        info!(
+            endpoint_count = endpoints.len(),
+            table_endpoint_count = table_endpoints.len(),
+            ?batch_mode,
+            ?auth_scheme,
+            ?send_deadline,
+            "PipelineClient initialized"
+        );
+        Self::new(endpoints, token)
+            .map(|client| {
+                client
+                    .with_table_endpoints(table_endpoints)
+                    .with_batch_mode(batch_mode)
+                    .with_auth_scheme(auth_scheme)
+                    .with_send_deadline(send_deadline)
+                    .with_checksum_mode(checksum_mode)
+                    .with_send_policies(SendPolicies::from_lookup(|name| {
+                        env.var(name).ok().map(|v| v.to_string())
+                    }))
+                    .with_max_body_sizes(max_body_sizes_from_lookup(|name| {
+                        env.var(name).ok().map(|v| v.to_string())
+                    }))
+                    .with_reject_invalid_records(reject_invalid_from(
+                        env.var("PIPELINE_REJECT_INVALID_RECORDS")
+                            .ok()
+                            .map(|v| v.to_string()),
+                    ))
+                    .with_sequence_headers(sequence_headers_from(
+                        env.var("PIPELINE_SEQUENCE_HEADERS")
+                            .ok()
+                            .map(|v| v.to_string()),
+                    ))
+                    .with_gzip(gzip_from(
+                        env.var("PIPELINE_GZIP").ok().map(|v| v.to_string()),
+                    ))
+                    .with_skip_schema_validation(skip_validation_from(
+                        env.var("SKIP_SCHEMA_VALIDATION")
+                            .ok()
+                            .map(|v| v.to_string()),
+                    ))
+                    .with_extra_headers(headers_from_vars(worker_header_vars(env)))
+            })
+            .and_then(|client| client)
+            .map_err(worker::Error::RustError)
+    }
+}
+
+/// `PIPELINE_HEADER_*` variables; worker env can only be listed via its JS keys
+fn worker_header_vars(env: &worker::Env) -> Vec<(String, String)> {
+    let keys = js_sys::Reflect::own_keys(env).unwrap_or_default();
+    keys.iter()
+        .filter_map(|key| key.as_string())
+        .filter(|key| key.starts_with(PIPELINE_HEADER_PREFIX))
+        .filter_map(|key| {
+            let value = env.var(&key).ok()?.to_string();
+            Some((key, value))
+        })
+        .collect()
+}
@@ -5,6 +5,9 @@ use serde::{Deserialize, Serialize};
 #[cfg(target_arch = "wasm32")]
 use worker::*;
 
+#[cfg(target_arch = "wasm32")]
+use super::prune::{stale_service_names, PruneRequest, PruneResponse};
+
 /// Service registration request.
 #[cfg(target_arch = "wasm32")]
 #[derive(Debug, Serialize, Deserialize)]
@@ -26,6 +29,8 @@ pub struct ServiceRegistration {
 pub struct ServiceRecord {
     pub name: String,
     pub first_seen_at: i64,
+    #[serde(default)]
+    pub last_seen_at: i64,
     pub has_logs: i64,
     pub has_traces: i64,
     pub has_metrics: i64,
@@ -90,6 +95,7 @@ impl DurableObject for RegistryDO {
             (Method::Get, "/list") => self.handle_list().await,
             (Method::Post, "/register-metrics") => self.handle_register_metrics(req).await,
             (Method::Get, "/list-metrics") => self.handle_list_metrics().await,
+            (Method::Post, "/prune") => self.handle_prune(req).await,
             _ => Response::error("Not found", 404),
         }
     }
@@ -107,6 +113,7 @@ impl RegistryDO {
     const DDL: &'static str = "CREATE TABLE IF NOT EXISTS services (
         name TEXT PRIMARY KEY,
         first_seen_at INTEGER NOT NULL,
+        last_seen_at INTEGER NOT NULL DEFAULT 0,
         has_logs INTEGER DEFAULT 0,
         has_traces INTEGER DEFAULT 0,
         has_metrics INTEGER DEFAULT 0
@@ -119,9 +126,19 @@ impl RegistryDO {
         PRIMARY KEY (name, metric_type)
     )";
 
+    /// Adds `last_seen_at` to tables created before it existed
+    const LAST_SEEN_MIGRATION: &'static str =
+        "ALTER TABLE services ADD COLUMN last_seen_at INTEGER NOT NULL DEFAULT 0";
+
     fn ensure_schema(&self) -> Result<()> {
         self.state.storage().sql().exec(Self::DDL, None)?;
         self.state.storage().sql().exec(Self::METRICS_DDL, None)?;
+        // Fails with "duplicate column" once applied
+        let _ = self
+            .state
+            .storage()
+            .sql()
+            .exec(Self::LAST_SEEN_MIGRATION, None);
         Ok(())
     }
 
@@ -297,7 +314,7 @@ impl RegistryDO {
         Response::ok(format!("{}", registered))
     }
 
-    fn upsert_service(&self, name: &str, signal: &str, first_seen_at: i64) -> Result<()> {
+    fn upsert_service(&self, name: &str, signal: &str, now: i64) -> Result<()> {
         let (has_logs, has_traces, has_metrics) = match signal {
             "logs" => (1, 0, 0),
             "traces" => (0, 1, 0),
@@ -307,15 +324,17 @@ impl RegistryDO {
 
         let sql = self.state.storage().sql();
         sql.exec(
-            "INSERT INTO services (name, first_seen_at, has_logs, has_traces, has_metrics)
-             VALUES (?, ?, ?, ?, ?)
+            "INSERT INTO services (name, first_seen_at, last_seen_at, has_logs, has_traces, has_metrics)
+             VALUES (?, ?, ?, ?, ?, ?)
              ON CONFLICT(name) DO UPDATE SET
+               last_seen_at = excluded.last_seen_at,
                has_logs = MAX(has_logs, excluded.has_logs),
                has_traces = MAX(has_traces, excluded.has_traces),
                has_metrics = MAX(has_metrics, excluded.has_metrics)",
             vec![
                 SqlStorageValue::String(name.to_string()),
-                SqlStorageValue::Integer(first_seen_at),
+                SqlStorageValue::Integer(now),
+                SqlStorageValue::Integer(now),
                 SqlStorageValue::Integer(has_logs),
                 SqlStorageValue::Integer(has_traces),
                 SqlStorageValue::Integer(has_metrics),
@@ -350,6 +369,32 @@ impl RegistryDO {
         Response::from_json(&services)
     }
 
+    /// Delete services not seen within `older_than_ms`, returning the count.
+    async fn handle_prune(&self, mut req: Request) -> Result<Response> {
+        let body = req.text().await?;
+        let request: PruneRequest = serde_json::from_str(&body)
+            .map_err(|e| worker::Error::RustError(format!("Invalid JSON: {}", e)))?;
+        let sql = self.state.storage().sql();
+        let services: Vec<ServiceRecord> = sql
+            .exec("SELECT * FROM services", None)?
+            .to_array()
+            .map_err(|e| {
+                worker::Error::RustError(format!("Failed to deserialize service records: {}", e))
+            })?;
+
+        let stale = stale_service_names(&services, Self::now_ms(), request.older_than_ms);
+        for name in &stale {
+            sql.exec(
+                "DELETE FROM services WHERE name = ?",
+                vec![SqlStorageValue::String(name.clone())],
+            )?;
+        }
+
+        Response::from_json(&PruneResponse {
+            pruned: stale.len(),
+        })
+    }
+
     async fn handle_list_metrics(&self) -> Result<Response> {
         let sql = self.state.storage().sql();
         let result = sql.exec(
@@ -406,6 +451,7 @@ mod tests {
         let record = ServiceRecord {
             name: "test-service".to_string(),
             first_seen_at: 1234567890,
+            last_seen_at: 1234567899,
             has_logs: 1,
             has_traces: 0,
             has_metrics: 1,
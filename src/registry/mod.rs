@@ -2,6 +2,7 @@
 //! Service registry using Durable Objects for tracking known services.
 
 pub mod cache;
+pub mod prune;
 pub mod sender;
 
 #[cfg(target_arch = "wasm32")]
@@ -26,6 +27,8 @@ pub use sender::NativeRegistrySender;
 pub struct ServiceRecord {
     pub name: String,
     pub first_seen_at: i64,
+    #[serde(default)]
+    pub last_seen_at: i64,
     pub has_logs: i64,
     pub has_traces: i64,
     pub has_metrics: i64,
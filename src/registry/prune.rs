@@ -0,0 +1,117 @@
+//! Pruning services that stopped sending data.
+//!
+//! `last_seen_at` is refreshed whenever a worker isolate first sees a service,
+//! so it lags real traffic by at most an isolate lifetime. Prune windows
+//! should be hours or days, not minutes.
+
+use serde::{Deserialize, Serialize};
+
+use super::ServiceRecord;
+
+/// Body of `POST /v1/services/prune` (and the RegistryDO `/prune` request).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruneRequest {
+    /// Remove services not seen within this many milliseconds
+    pub older_than_ms: i64,
+}
+
+/// Response to a prune request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PruneResponse {
+    pub pruned: usize,
+}
+
+/// When a service was last seen. Records written before `last_seen_at`
+/// existed have it as 0 and fall back to `first_seen_at`.
+pub fn last_seen(record: &ServiceRecord) -> i64 {
+    if record.last_seen_at > 0 {
+        record.last_seen_at
+    } else {
+        record.first_seen_at
+    }
+}
+
+/// Names of services last seen before `now_ms - older_than_ms`.
+pub fn stale_service_names(
+    records: &[ServiceRecord],
+    now_ms: i64,
+    older_than_ms: i64,
+) -> Vec<String> {
+    let cutoff = now_ms.saturating_sub(older_than_ms);
+    records
+        .iter()
+        .filter(|record| last_seen(record) < cutoff)
+        .map(|record| record.name.clone())
+        .collect()
+}
+
+/// Worker handler for `POST /v1/services/prune`.
+#[cfg(target_arch = "wasm32")]
+pub async fn handle_prune_request(
+    mut req: worker::Request,
+    env: worker::Env,
+) -> worker::Result<worker::Response> {
+    use super::{RegistrySender, WasmRegistrySender};
+
+    let request: PruneRequest = match req.json().await {
+        Ok(request) => request,
+        Err(e) => return worker::Response::error(format!("Invalid prune request: {}", e), 400),
+    };
+    match WasmRegistrySender::new(env)
+        .prune_services(request.older_than_ms)
+        .await
+    {
+        Ok(pruned) => worker::Response::from_json(&PruneResponse { pruned }),
+        Err(e) => worker::Response::error(format!("Failed to prune services: {}", e), 500),
+    }
+}
+
+#[cfg(all(test, not(target_arch = "wasm32")))]
+mod tests {
+    use super::*;
+
+    const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+    const NOW_MS: i64 = 1_705_327_800_000;
+
+    fn record(name: &str, first_seen_at: i64, last_seen_at: i64) -> ServiceRecord {
+        ServiceRecord {
+            name: name.to_string(),
+            first_seen_at,
+            last_seen_at,
+            has_logs: 1,
+            has_traces: 0,
+            has_metrics: 0,
+        }
+    }
+
+    #[test]
+    fn test_prunes_services_not_seen_within_window() {
+        let records = vec![
+            record("active", NOW_MS - 30 * DAY_MS, NOW_MS - 1000),
+            record("stale", NOW_MS - 30 * DAY_MS, NOW_MS - 8 * DAY_MS),
+            record("edge", NOW_MS - 30 * DAY_MS, NOW_MS - 7 * DAY_MS),
+            record("legacy-old", NOW_MS - 10 * DAY_MS, 0),
+            record("legacy-new", NOW_MS - DAY_MS, 0),
+        ];
+
+        assert_eq!(
+            stale_service_names(&records, NOW_MS, 7 * DAY_MS),
+            vec!["stale".to_string(), "legacy-old".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_nothing_pruned_for_recent_services() {
+        let records = vec![record("a", NOW_MS, NOW_MS), record("b", NOW_MS - DAY_MS, 0)];
+        assert!(stale_service_names(&records, NOW_MS, 2 * DAY_MS).is_empty());
+    }
+
+    #[test]
+    fn test_record_without_last_seen_deserializes() {
+        let json =
+            r#"{"name":"svc","first_seen_at":5,"has_logs":1,"has_traces":0,"has_metrics":0}"#;
+        let record: ServiceRecord = serde_json::from_str(json).unwrap();
+        assert_eq!(record.last_seen_at, 0);
+        assert_eq!(last_seen(&record), 5);
+    }
+}
@@ -9,6 +9,8 @@ use super::durable_object::{
     MetricRecord, MetricRegistration, RegisterMetricsRequest, RegisterRequest, ServiceRecord,
     ServiceRegistration,
 };
+#[cfg(target_arch = "wasm32")]
+use super::prune::{PruneRequest, PruneResponse};
 
 #[cfg(not(target_arch = "wasm32"))]
 use super::{MetricRecord, ServiceRecord};
@@ -28,6 +30,9 @@ pub trait RegistrySender {
 
     /// Get all metrics (for API endpoint).
     async fn get_all_metrics(&self) -> Result<Vec<MetricRecord>, String>;
+
+    /// Remove services not seen within `older_than_ms`, returning how many were removed.
+    async fn prune_services(&self, older_than_ms: i64) -> Result<usize, String>;
 }
 
 /// WASM implementation that uses local cache and sends to RegistryDO.
@@ -276,6 +281,42 @@ impl RegistrySender for WasmRegistrySender {
 
         Ok(metrics)
     }
+
+    async fn prune_services(&self, older_than_ms: i64) -> Result<usize, String> {
+        let stub = self
+            .get_stub()
+            .map_err(|e| format!("Failed to get RegistryDO stub: {}", e))?;
+
+        let body = serde_json::to_string(&PruneRequest { older_than_ms })
+            .map_err(|e| format!("Failed to serialize prune request: {}", e))?;
+        let request = worker::Request::new_with_init(
+            "http://do/prune",
+            worker::RequestInit::new()
+                .with_method(worker::Method::Post)
+                .with_body(Some(body.into())),
+        )
+        .map_err(|e| format!("Failed to create request: {}", e))?;
+
+        let mut response = stub
+            .fetch_with_request(request)
+            .await
+            .map_err(|e| format!("Failed to send to RegistryDO: {}", e))?;
+
+        if response.status_code() >= 400 {
+            return Err(format!(
+                "RegistryDO returned status {}",
+                response.status_code()
+            ));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read response body: {}", e))?;
+        let pruned: PruneResponse = serde_json::from_str(&text)
+            .map_err(|e| format!("Failed to parse prune response: {}", e))?;
+        Ok(pruned.pruned)
+    }
 }
 
 /// NoOp implementation for native builds (testing).
@@ -322,6 +363,11 @@ impl RegistrySender for NativeRegistrySender {
         // NoOp for native - return empty list
         Ok(vec![])
     }
+
+    async fn prune_services(&self, _older_than_ms: i64) -> Result<usize, String> {
+        // NoOp for native - nothing to prune
+        Ok(0)
+    }
 }
 
 #[cfg(test)]
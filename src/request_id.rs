@@ -0,0 +1,56 @@
+//! Request ID propagation for ingest requests.
+//!
+//! Each router reads `X-Request-Id` (or generates a UUID), passes it to
+//! `handle_signal`, and echoes it back on the response.
+
+/// Header carrying the request ID (lowercase for HTTP/2 compatibility)
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest caller-supplied request ID accepted before generating a new one
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Use the caller's request ID if it is a safe header value, else generate one.
+pub fn resolve_request_id(header: Option<&str>) -> String {
+    match header.map(str::trim) {
+        Some(id) if is_valid_request_id(id) => id.to_string(),
+        _ => uuid::Uuid::new_v4().to_string(),
+    }
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LEN && id.bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// Append the request ID to an error message returned to the client
+pub fn error_with_request_id(message: &str, request_id: &str) -> String {
+    format!("{} (request_id: {})", message, request_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uses_provided_request_id() {
+        assert_eq!(resolve_request_id(Some("req-123")), "req-123");
+        assert_eq!(resolve_request_id(Some("  req-123 ")), "req-123");
+    }
+
+    #[test]
+    fn test_generates_request_id_when_missing_or_invalid() {
+        for header in [None, Some(""), Some("has space"), Some("bad\nid")] {
+            let id = resolve_request_id(header);
+            assert!(uuid::Uuid::parse_str(&id).is_ok(), "not a uuid: {}", id);
+        }
+        let too_long = "a".repeat(MAX_REQUEST_ID_LEN + 1);
+        assert_ne!(resolve_request_id(Some(&too_long)), too_long);
+    }
+
+    #[test]
+    fn test_error_with_request_id() {
+        assert_eq!(
+            error_with_request_id("decode error: bad", "req-1"),
+            "decode error: bad (request_id: req-1)"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! Cloudflare pipeline schema rendering, shared by `build.rs` and `schema export`.
+
+/// Schemas for tables this crate produces itself (not from otlp2records)
+pub const LOCAL_SCHEMAS: &[otlp2records::SchemaDef] = &[
+    otlp2records::SchemaDef {
+        name: "span_links",
+        fields: &[
+            field("timestamp", "timestamp", true),
+            field("trace_id", "string", true),
+            field("span_id", "string", true),
+            field("service_name", "string", false),
+            field("linked_trace_id", "string", true),
+            field("linked_span_id", "string", true),
+            field("linked_trace_state", "string", false),
+            field("attributes", "json", false),
+        ],
+    },
+    otlp2records::SchemaDef {
+        name: "summary",
+        fields: &[
+            field("timestamp", "timestamp", true),
+            field("start_timestamp", "int64", false),
+            field("metric_name", "string", true),
+            field("metric_description", "string", false),
+            field("metric_unit", "string", false),
+            field("count", "int64", true),
+            field("sum", "float64", false),
+            field("quantile_values", "json", true),
+            field("service_name", "string", true),
+            field("service_namespace", "string", false),
+            field("service_instance_id", "string", false),
+            field("resource_attributes", "json", false),
+            field("scope_name", "string", false),
+            field("scope_version", "string", false),
+            field("scope_attributes", "json", false),
+            field("metric_attributes", "json", false),
+            field("flags", "int32", false),
+        ],
+    },
+];
+
+const fn field(
+    name: &'static str,
+    field_type: &'static str,
+    required: bool,
+) -> otlp2records::SchemaField {
+    otlp2records::SchemaField {
+        name,
+        field_type,
+        required,
+    }
+}
+
+/// Optional `(name, type)` columns this crate adds on top of otlp2records schemas
+pub fn extra_fields(schema_name: &str) -> &'static [(&'static str, &'static str)] {
+    match schema_name {
+        "logs" => &[("log_id", "string"), ("log_uid", "string")],
+        "spans" => &[("is_root", "bool")],
+        "gauge" => &[("series_id", "string")],
+        "sum" => &[("series_id", "string"), ("reset", "bool")],
+        _ => &[],
+    }
+}
+
+/// Render a schema definition as Cloudflare pipeline schema JSON
+pub fn generate_cloudflare_schema(schema: &otlp2records::SchemaDef) -> String {
+    let mut fields_json = Vec::new();
+
+    for field in schema.fields {
+        let field_obj = format!(
+            r#"    {{ "name": "{}", "type": "{}", "required": {} }}"#,
+            field.name, field.field_type, field.required
+        );
+        fields_json.push(field_obj);
+    }
+    for (name, field_type) in extra_fields(schema.name) {
+        fields_json.push(format!(
+            r#"    {{ "name": "{}", "type": "{}", "required": false }}"#,
+            name, field_type
+        ));
+    }
+
+    format!(
+        "{{\n  \"fields\": [\n{}\n  ]\n}}\n",
+        fields_json.join(",\n")
+    )
+}
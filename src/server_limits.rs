@@ -0,0 +1,87 @@
+//! Connection and request limits for the native server.
+//!
+//! `SERVER_MAX_CONCURRENCY` caps requests handled at once (extra requests
+//! wait for a slot), `SERVER_REQUEST_TIMEOUT_MS` answers requests still
+//! running after that long with 408, and `SERVER_KEEP_ALIVE=false` closes
+//! HTTP/1 connections after each response when served with `serve_router`.
+//! Unset values leave the server unlimited, as before.
+
+use axum::http::StatusCode;
+use axum::Router;
+use std::time::Duration;
+use tower::limit::GlobalConcurrencyLimitLayer;
+use tower_http::timeout::TimeoutLayer;
+
+/// Native server limits; the default is unlimited with keep-alive on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerLimits {
+    /// Maximum requests in flight across all routes
+    pub max_concurrency: Option<usize>,
+    /// Requests running longer than this get a 408
+    pub request_timeout: Option<Duration>,
+    /// Keep HTTP/1 connections open between requests
+    pub keep_alive: bool,
+}
+
+impl Default for ServerLimits {
+    fn default() -> Self {
+        Self {
+            max_concurrency: None,
+            request_timeout: None,
+            keep_alive: true,
+        }
+    }
+}
+
+impl ServerLimits {
+    /// Build from `SERVER_*` variables; zero or unparseable values are ignored
+    pub fn from_lookup(mut var: impl FnMut(&str) -> Option<String>) -> Self {
+        let positive = |value: Option<String>| {
+            value
+                .and_then(|v| v.trim().parse::<u64>().ok())
+                .filter(|&n| n > 0)
+        };
+        Self {
+            max_concurrency: positive(var("SERVER_MAX_CONCURRENCY")).map(|n| n as usize),
+            request_timeout: positive(var("SERVER_REQUEST_TIMEOUT_MS")).map(Duration::from_millis),
+            keep_alive: var("SERVER_KEEP_ALIVE")
+                .and_then(|v| v.trim().parse().ok())
+                .unwrap_or(true),
+        }
+    }
+
+    /// Wrap every route of `router` in the configured middleware
+    pub fn apply<S: Clone + Send + Sync + 'static>(&self, router: Router<S>) -> Router<S> {
+        let router = match self.request_timeout {
+            Some(timeout) => router.layer(TimeoutLayer::with_status_code(
+                StatusCode::REQUEST_TIMEOUT,
+                timeout,
+            )),
+            None => router,
+        };
+        match self.max_concurrency {
+            Some(max) => router.layer(GlobalConcurrencyLimitLayer::new(max)),
+            None => router,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_lookup() {
+        assert_eq!(ServerLimits::from_lookup(|_| None), ServerLimits::default());
+
+        let limits = ServerLimits::from_lookup(|name| match name {
+            "SERVER_MAX_CONCURRENCY" => Some("64".to_string()),
+            "SERVER_REQUEST_TIMEOUT_MS" => Some("0".to_string()),
+            "SERVER_KEEP_ALIVE" => Some("false".to_string()),
+            _ => None,
+        });
+        assert_eq!(limits.max_concurrency, Some(64));
+        assert_eq!(limits.request_timeout, None);
+        assert!(!limits.keep_alive);
+    }
+}
@@ -63,6 +63,29 @@ impl Signal {
             _ => None,
         }
     }
+
+    /// Infer the request signal from a path and optional query string:
+    /// `?signal=logs|traces|metrics` wins, otherwise a path ending in
+    /// `/v1/logs`, `/v1/traces` or `/v1/metrics`. Metric requests map to
+    /// `Gauge`, the signal metric handling runs under.
+    pub fn from_path(path_and_query: &str) -> Option<Signal> {
+        let (path, query) = path_and_query
+            .split_once('?')
+            .unwrap_or((path_and_query, ""));
+        let from_query = query
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("signal="));
+        let name = match from_query {
+            Some(name) => name,
+            None => path.trim_end_matches('/').rsplit_once("/v1/")?.1,
+        };
+        match name.to_ascii_lowercase().as_str() {
+            "logs" => Some(Signal::Logs),
+            "traces" => Some(Signal::Traces),
+            "metrics" => Some(Signal::Gauge),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -78,6 +101,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn signal_from_standard_paths_and_query() {
+        let cases = [
+            ("/v1/logs", Some(Signal::Logs)),
+            ("/v1/traces", Some(Signal::Traces)),
+            ("/v1/metrics", Some(Signal::Gauge)),
+            ("/otlp/v1/traces/", Some(Signal::Traces)),
+            ("/ingest?signal=logs", Some(Signal::Logs)),
+            ("/ingest?verbose=1&signal=METRICS", Some(Signal::Gauge)),
+            ("/v1/logs?signal=traces", Some(Signal::Traces)),
+            ("/v1/profiles", None),
+            ("/ingest", None),
+            ("/ingest?signal=events", None),
+        ];
+        for (path, expected) in cases {
+            assert_eq!(Signal::from_path(path), expected, "{}", path);
+        }
+    }
+
     #[test]
     fn env_var_names_are_uppercase() {
         for signal in Signal::all() {
@@ -9,11 +9,11 @@ use worker::*;
 use crate::handler;
 use crate::livetail::WasmLiveTailSender;
 use crate::parse_content_metadata;
+use crate::pipeline::health::snapshot as pipeline_health;
 use crate::pipeline::PipelineClient;
 use crate::registry::{RegistrySender, WasmRegistrySender};
 use crate::signal::Signal;
 use crate::stats::{handle_all_services_stats, handle_stats_query};
-use crate::InputFormat;
 
 /// Add CORS headers to a response.
 /// Creates a new response to handle immutable headers from Durable Objects.
@@ -128,7 +128,11 @@ pub async fn main(req: Request, env: Env, ctx: Context) -> Result<Response> {
         (Method::Get, "/health") => Response::ok("ok"),
         (Method::Get, "/v1/config") => handle_config(env),
         (Method::Get, "/v1/services") => handle_services_list(env).await,
+        (Method::Post, "/v1/services/prune") => {
+            crate::registry::prune::handle_prune_request(req, env).await
+        }
         (Method::Get, "/v1/metrics") => handle_metrics_list(env).await,
+        (Method::Get, "/v1/pipelines/health") => Response::from_json(&pipeline_health()),
         // All-services stats: /v1/services/stats?signal=logs|traces
         (Method::Get, "/v1/services/stats") => handle_all_services_stats(req, env).await,
         // Per-service stats: /v1/services/:service/:signal/stats
@@ -158,22 +162,34 @@ async fn handle_signal_worker<H: handler::SignalHandler>(
     ctx: Context,
 ) -> Result<Response> {
     let body_bytes = req.bytes().await?;
-    let (is_gzipped, decode_format) = parse_worker_headers(&req);
-    let client = PipelineClient::from_worker_env(&env)?;
+    let (compression, decode_format) = parse_worker_headers(&req);
+    let request_id = crate::request_id::resolve_request_id(
+        req.headers()
+            .get(crate::request_id::REQUEST_ID_HEADER)
+            .ok()
+            .flatten()
+            .as_deref(),
+    );
+    let config =
+        handler::HandlerConfig::from_lookup(|name| env.var(name).ok().map(|v| v.to_string()));
+    let extra_tables = config.extra_table_names();
+    let client = PipelineClient::from_worker_env(&env, &extra_tables)?;
 
-    // Initialize aggregator sender for dual-write
+    // Initialize aggregator (dual-write) and livetail (triple-write) senders
     let cache = crate::aggregator::WasmAggregatorSender::new(env.clone());
-
-    // Initialize livetail sender for triple-write
     let livetail = WasmLiveTailSender::new(env.clone());
 
-    match handler::handle_signal_with_cache::<H, _, _, _>(
+    let mut response = match handler::handle_signal_with_cache::<H, _, _, _>(
         Bytes::from(body_bytes),
-        is_gzipped,
+        compression,
         decode_format,
         &client,
-        Some(&cache),
-        Some(&livetail),
+        handler::RequestContext {
+            config: &config,
+            request_id: &request_id,
+            cache: Some(&cache),
+            livetail: Some(&livetail),
+        },
     )
     .await
     {
@@ -195,10 +211,22 @@ async fn handle_signal_worker<H: handler::SignalHandler>(
                     register_metrics(&env_clone, &metric_names).await;
                 });
             }
-            Response::from_json(&resp)
+            if resp.is_no_content(&config) {
+                Response::empty()?.with_status(204)
+            } else {
+                let verbose = handler::verbose_requested(req.url()?.query());
+                Response::from_json(&resp.for_verbosity(verbose))?
+            }
         }
-        Err(e) => Response::error(e.to_string(), 400),
-    }
+        Err(e) => Response::error(
+            crate::request_id::error_with_request_id(&e.to_string(), &request_id),
+            400,
+        )?,
+    };
+    response
+        .headers_mut()
+        .set(crate::request_id::REQUEST_ID_HEADER, &request_id)?;
+    Ok(response)
 }
 
 async fn handle_metrics_worker(req: Request, env: Env, ctx: Context) -> Result<Response> {
@@ -213,7 +241,7 @@ async fn handle_traces_worker(req: Request, env: Env, ctx: Context) -> Result<Re
     handle_signal_worker::<handler::TracesHandler>(req, env, ctx).await
 }
 
-fn parse_worker_headers(req: &Request) -> (bool, InputFormat) {
+fn parse_worker_headers(req: &Request) -> (handler::Compression, handler::BodyFormat) {
     parse_content_metadata(|name| {
         req.headers()
             .get(name)
@@ -307,13 +335,13 @@ fn handle_config(env: Env) -> Result<Response> {
 }
 
 /// Proxy requests to R2 Data Catalog to work around browser CORS restrictions.
-/// Forwards the client's Authorization header to catalog.cloudflarestorage.com.
+/// Forwards the client's Authorization header (an R2 API token, required)
+/// to catalog.cloudflarestorage.com.
 ///
 /// Path format: /v1/iceberg/{rest_of_path}
 /// Environment variables required:
 ///   - R2_CATALOG_ACCOUNT_ID: Cloudflare account ID
 ///   - R2_CATALOG_BUCKET: R2 bucket name
-/// Client must provide Authorization header with R2 API token.
 async fn handle_iceberg_proxy(path: &str, mut req: Request, env: Env) -> Result<Response> {
     // Require Authorization header from client
     let auth_header = req
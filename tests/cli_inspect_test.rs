@@ -0,0 +1,73 @@
+// tests/cli_inspect_test.rs
+use serde_json::Value;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Get the path to the built binary
+fn get_binary_path() -> PathBuf {
+    let build_status = Command::new("cargo")
+        .args(["build", "--quiet"])
+        .status()
+        .expect("Failed to build");
+    assert!(build_status.success(), "Build failed");
+
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("otlp2pipeline");
+    path
+}
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+#[test]
+fn test_inspect_decodes_protobuf_metrics() {
+    let output = Command::new(get_binary_path())
+        .args(["inspect", "--signal", "metrics", "--format", "protobuf"])
+        .arg("--input")
+        .arg(fixture("metrics_gauge.pb"))
+        .arg("--transform")
+        .output()
+        .expect("Failed to run command");
+
+    assert!(
+        output.status.success(),
+        "Command failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let json: Value = serde_json::from_slice(&output.stdout).expect("stdout is not JSON");
+
+    let decoded = json["decoded"].as_array().expect("decoded array");
+    assert!(!decoded.is_empty());
+    let first = &decoded[0];
+    assert_eq!(first["_metric_type"], "gauge");
+    assert_eq!(first["metric_name"], "cpu.usage");
+    assert_eq!(
+        first["resource"]["attributes"]["service.name"],
+        "demo-service"
+    );
+    assert_eq!(first["scope"]["name"], "demo-instrumentation");
+
+    let gauge = json["transformed"]["gauge"]
+        .as_array()
+        .expect("transformed gauge");
+    assert_eq!(gauge.len(), decoded.len());
+    assert_eq!(gauge[0]["service_name"], "demo-service");
+}
+
+#[test]
+fn test_inspect_rejects_mismatched_payload() {
+    let output = Command::new(get_binary_path())
+        .args(["inspect", "--signal", "logs", "--format", "json"])
+        .arg("--input")
+        .arg(fixture("metrics_gauge.pb"))
+        .output()
+        .expect("Failed to run command");
+
+    assert!(!output.status.success());
+}
@@ -0,0 +1,45 @@
+// tests/cli_schema_export_test.rs
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Get the path to the built binary
+fn get_binary_path() -> PathBuf {
+    let build_status = Command::new("cargo")
+        .args(["build", "--quiet"])
+        .status()
+        .expect("Failed to build");
+    assert!(build_status.success(), "Build failed");
+
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("target");
+    path.push("debug");
+    path.push("otlp2pipeline");
+    path
+}
+
+#[test]
+fn test_schema_export_matches_committed_schemas() {
+    let dir = tempfile::tempdir().unwrap();
+    let output = Command::new(get_binary_path())
+        .args(["schema", "export", "--dir"])
+        .arg(dir.path())
+        .output()
+        .expect("Failed to run command");
+
+    assert!(
+        output.status.success(),
+        "Command failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let exported = std::fs::read_to_string(dir.path().join("logs.schema.json")).unwrap();
+    let committed = include_str!("../schemas/logs.schema.json");
+    assert_eq!(exported, committed);
+
+    for name in ["spans", "gauge", "sum"] {
+        assert!(dir.path().join(format!("{}.schema.json", name)).exists());
+        let table = std::fs::read_to_string(dir.path().join(format!("{}.schema.txt", name)))
+            .expect("table export");
+        assert!(table.starts_with(name));
+    }
+}
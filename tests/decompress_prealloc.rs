@@ -0,0 +1,88 @@
+// tests/decompress_prealloc.rs
+//! Counts buffer reallocations while decompressing under different
+//! `decompress_prealloc_factor` settings. Lives in its own binary because it
+//! installs a counting global allocator.
+
+use flate2::{write::GzEncoder, Compression as GzLevel};
+use otlp2pipeline::{decompress_body, Bytes, Compression, HandlerConfig};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::io::Write;
+
+struct CountingAlloc;
+
+thread_local! {
+    // Per thread, so tests running in parallel don't see each other's reallocs
+    static REALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        REALLOCS.with(|count| count.set(count.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOC: CountingAlloc = CountingAlloc;
+
+/// ~1MB of repetitive JSON-ish text, gzipped well past 20:1
+fn payload() -> (Vec<u8>, Bytes) {
+    let raw = "{\"service.name\":\"checkout\",\"severity_text\":\"INFO\"}\n"
+        .repeat(20_000)
+        .into_bytes();
+    let mut encoder = GzEncoder::new(Vec::new(), GzLevel::best());
+    encoder.write_all(&raw).unwrap();
+    let gzipped = Bytes::from(encoder.finish().unwrap());
+    assert!(
+        raw.len() / gzipped.len() > 20,
+        "payload should compress well"
+    );
+    (raw, gzipped)
+}
+
+/// Reallocations during one decompression with the given prealloc factor
+fn reallocs_with_factor(gzipped: &Bytes, expected: &[u8], factor: usize) -> usize {
+    let config = HandlerConfig {
+        decompress_prealloc_factor: factor,
+        max_compression_ratio: 0,
+        ..HandlerConfig::default()
+    };
+    let before = REALLOCS.with(Cell::get);
+    let body = decompress_body(gzipped.clone(), Compression::Gzip, &config).unwrap();
+    let after = REALLOCS.with(Cell::get);
+    assert_eq!(body.as_ref(), expected);
+    after - before
+}
+
+#[test]
+fn test_prealloc_factor_covering_the_ratio_avoids_regrowth() {
+    let (raw, gzipped) = payload();
+    let ratio = raw.len().div_ceil(gzipped.len());
+
+    let default_factor = reallocs_with_factor(
+        &gzipped,
+        &raw,
+        HandlerConfig::default().decompress_prealloc_factor,
+    );
+    let covering = reallocs_with_factor(&gzipped, &raw, ratio + 1);
+    // Factors past the limit are capped rather than reserving huge buffers
+    let capped = reallocs_with_factor(&gzipped, &raw, usize::MAX);
+
+    assert!(
+        default_factor > covering,
+        "default factor reallocated {} times, covering factor {}",
+        default_factor,
+        covering
+    );
+    assert_eq!(covering, 0);
+    assert_eq!(capped, 0);
+}
@@ -0,0 +1,55 @@
+// tests/e2e_base_path.rs
+mod helpers;
+
+use helpers::{
+    can_bind_loopback, free_port, reset_events, spawn_mock_pipeline, spawn_router_with_env,
+    wait_for_events, wait_for_health,
+};
+use reqwest::Client;
+
+#[tokio::test]
+async fn test_routes_mount_under_base_path() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e base path test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+
+    let mock_port = free_port().await;
+    let (mock_proc, mock_url) = spawn_mock_pipeline(mock_port).await;
+    wait_for_health(&client, &mock_url).await;
+    reset_events(&client, &mock_url).await;
+
+    // Only test in this binary, so the env var can't leak into other routers
+    let app_url = spawn_router_with_env(&mock_url, &[("INGEST_BASE_PATH", "/otlp")]).await;
+
+    let otlp_payload = include_str!("fixtures/sample_otlp_metrics.json");
+    let resp = client
+        .post(format!("{}/otlp/v1/metrics", app_url))
+        .header("content-type", "application/json")
+        .body(otlp_payload)
+        .send()
+        .await
+        .expect("failed to send request");
+    assert!(
+        resp.status().is_success(),
+        "response was not success: {:?}",
+        resp.status()
+    );
+
+    let events = wait_for_events(&client, &mock_url, 3).await;
+    assert_eq!(events.len(), 3, "expected 3 events (2 gauges + 1 sum)");
+
+    // The unprefixed path is no longer mounted
+    let resp = client
+        .post(format!("{}/v1/metrics", app_url))
+        .header("content-type", "application/json")
+        .body(otlp_payload)
+        .send()
+        .await
+        .expect("failed to send request");
+    assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+
+    mock_proc.stop().await;
+}
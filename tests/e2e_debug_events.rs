@@ -0,0 +1,67 @@
+// tests/e2e_debug_events.rs
+mod helpers;
+
+use helpers::{
+    can_bind_loopback, free_port, spawn_mock_pipeline, spawn_router_with_env, wait_for_health,
+};
+use reqwest::Client;
+
+#[tokio::test]
+async fn test_debug_events_returns_forwarded_records() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e debug events test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+
+    let mock_port = free_port().await;
+    let (mock_proc, mock_url) = spawn_mock_pipeline(mock_port).await;
+    wait_for_health(&client, &mock_url).await;
+
+    // Off by default
+    let app_url = spawn_router_with_env(&mock_url, &[]).await;
+    let resp = client
+        .get(format!("{}/debug/events", app_url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+
+    // Only test in this binary, so the env var can't leak into other routers.
+    let app_url = spawn_router_with_env(&mock_url, &[("OTLP2PIPELINE_DEBUG", "1")]).await;
+
+    let events: Vec<serde_json::Value> = client
+        .get(format!("{}/debug/events", app_url))
+        .send()
+        .await
+        .expect("failed to read debug events")
+        .json()
+        .await
+        .unwrap();
+    assert!(events.is_empty());
+
+    let resp = client
+        .post(format!("{}/v1/logs", app_url))
+        .header("content-type", "application/json")
+        .body(include_str!("fixtures/sample_otlp.json"))
+        .send()
+        .await
+        .expect("failed to send request");
+    assert!(resp.status().is_success(), "{:?}", resp.status());
+
+    // Records are buffered once the send completes, before the response
+    let events: Vec<serde_json::Value> = client
+        .get(format!("{}/debug/events", app_url))
+        .send()
+        .await
+        .expect("failed to read debug events")
+        .json()
+        .await
+        .unwrap();
+    assert!(!events.is_empty(), "no debug events recorded");
+    assert!(events[0].get("severity_text").is_some());
+    assert!(events[0].get("body").is_some());
+
+    mock_proc.stop().await;
+}
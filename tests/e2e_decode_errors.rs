@@ -0,0 +1,62 @@
+// tests/e2e_decode_errors.rs
+mod helpers;
+
+use helpers::{can_bind_loopback, free_port, wait_for_health};
+use reqwest::Client;
+
+/// Start the router (decode failures never reach the pipeline) and return its URL
+async fn spawn_app() -> String {
+    let app_port = free_port().await;
+    let app = otlp2pipeline::build_router("http://127.0.0.1:1".to_string());
+    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", app_port))
+        .await
+        .unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let app_url = format!("http://127.0.0.1:{}", app_port);
+    wait_for_health(&Client::new(), &app_url).await;
+    app_url
+}
+
+async fn post_protobuf(app_url: &str, body: Vec<u8>) -> (reqwest::StatusCode, String) {
+    let resp = Client::new()
+        .post(format!("{}/v1/logs", app_url))
+        .header("content-type", "application/x-protobuf")
+        .body(body)
+        .send()
+        .await
+        .expect("failed to send request");
+    let status = resp.status();
+    (status, resp.text().await.unwrap())
+}
+
+#[tokio::test]
+async fn test_protobuf_decode_diagnostics() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e decode test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let app_url = spawn_app().await;
+
+    // Valid ExportLogsServiceRequest framing followed by trailing garbage
+    let (status, body) = post_protobuf(&app_url, vec![0x0a, 0x00, 0xff, 0xff, 0xff]).await;
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+    assert!(body.contains("byte 2 of 5"), "unexpected body: {}", body);
+    assert!(!body.contains("JSON"), "unexpected body: {}", body);
+
+    // JSON sent with a protobuf content-type
+    let json = include_str!("fixtures/sample_otlp.json")
+        .as_bytes()
+        .to_vec();
+    let (status, body) = post_protobuf(&app_url, json).await;
+    assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+    assert!(
+        body.contains("looks like JSON"),
+        "unexpected body: {}",
+        body
+    );
+}
@@ -0,0 +1,47 @@
+// tests/e2e_empty_status.rs
+mod helpers;
+
+use helpers::{can_bind_loopback, spawn_router_with_env};
+use reqwest::Client;
+
+#[tokio::test]
+async fn test_empty_request_returns_configured_status() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e empty status test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+
+    // Nothing is sent for an empty request, so no pipeline needs to be listening.
+    // Only test in this binary, so the env var can't leak into other routers.
+    let app_url =
+        spawn_router_with_env("http://127.0.0.1:9", &[("EMPTY_RESPONSE_STATUS", "204")]).await;
+
+    for (path, body) in [
+        ("/v1/logs", r#"{"resourceLogs":[]}"#),
+        ("/v1/traces", r#"{"resourceSpans":[]}"#),
+        ("/v1/metrics", r#"{"resourceMetrics":[]}"#),
+    ] {
+        let resp = client
+            .post(format!("{}{}", app_url, path))
+            .header("content-type", "application/json")
+            .body(body)
+            .send()
+            .await
+            .expect("failed to send request");
+        assert_eq!(resp.status(), reqwest::StatusCode::NO_CONTENT, "{}", path);
+        assert!(resp.headers().contains_key("x-request-id"), "{}", path);
+        assert!(resp.bytes().await.unwrap().is_empty(), "{}", path);
+    }
+
+    // Malformed bodies are still errors, not empty successes
+    let resp = client
+        .post(format!("{}/v1/logs", app_url))
+        .header("content-type", "application/json")
+        .body("not json")
+        .send()
+        .await
+        .expect("failed to send request");
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+}
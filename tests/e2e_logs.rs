@@ -71,3 +71,55 @@ async fn test_otlp_logs_flow() {
     // 5. Cleanup
     mock_proc.stop().await;
 }
+
+#[tokio::test]
+async fn test_plain_text_logs_flow() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e plain-text logs test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+
+    let mock_port = free_port().await;
+    let (mock_proc, mock_url) = spawn_mock_pipeline(mock_port).await;
+    wait_for_health(&client, &mock_url).await;
+    reset_events(&client, &mock_url).await;
+
+    let app_port = free_port().await;
+    let app = otlp2pipeline::build_router(mock_url.clone());
+    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", app_port))
+        .await
+        .unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    let app_url = format!("http://127.0.0.1:{}", app_port);
+    wait_for_health(&client, &app_url).await;
+
+    let post = |path: &str| {
+        client
+            .post(format!("{}{}", app_url, path))
+            .header("content-type", "text/plain; charset=utf-8")
+            .body("disk full on /var\r\n\r\nretrying in 5s\n")
+            .send()
+    };
+
+    let resp = post("/v1/logs").await.expect("failed to send request");
+    assert!(
+        resp.status().is_success(),
+        "response was not success: {:?}",
+        resp.status()
+    );
+
+    let events = wait_for_events(&client, &mock_url, 2).await;
+    let bodies: Vec<_> = events.iter().map(|e| e["body"].clone()).collect();
+    assert_eq!(bodies, vec!["disk full on /var", "retrying in 5s"]);
+    assert_eq!(events[0]["severity_number"], 9);
+
+    // Plain text is only a logs format
+    let resp = post("/v1/traces").await.expect("failed to send request");
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    mock_proc.stop().await;
+}
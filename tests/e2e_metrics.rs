@@ -393,3 +393,61 @@ async fn test_otlp_metrics_mixed_protobuf() {
 
     mock_proc.stop().await;
 }
+
+/// ExportMetricsServiceRequest with one gauge data point whose value is NaN.
+/// JSON cannot carry NaN, so the payload is hand-encoded protobuf.
+const NAN_GAUGE_PROTOBUF: &[u8] = &[
+    0x0a, 31, // resource_metrics
+    0x12, 29, // scope_metrics
+    0x12, 27, // metrics
+    0x0a, 3, b'c', b'p', b'u', // name
+    0x2a, 20, // gauge
+    0x0a, 18, // data_points
+    0x19, 0, 0, 0, 0, 0, 0, 0, 0, // time_unix_nano (fixed64)
+    0x21, 0, 0, 0, 0, 0, 0, 0xf8, 0x7f, // as_double = NaN
+];
+
+#[tokio::test]
+async fn test_verbose_response_reports_skipped_metrics() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e verbose test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+
+    // Nothing is sent to the pipeline: the only data point is skipped
+    let app_port = free_port().await;
+    let app = otlp2pipeline::build_router("http://127.0.0.1:1".to_string());
+    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", app_port))
+        .await
+        .unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let app_url = format!("http://127.0.0.1:{}", app_port);
+    wait_for_health(&client, &app_url).await;
+
+    let send = |query: &'static str| {
+        client
+            .post(format!("{}/v1/metrics{}", app_url, query))
+            .header("content-type", "application/x-protobuf")
+            .body(NAN_GAUGE_PROTOBUF)
+            .send()
+    };
+
+    // Warnings are hidden by default
+    let resp = send("").await.expect("failed to send request");
+    assert!(resp.status().is_success(), "status: {:?}", resp.status());
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert!(body.get("warnings").is_none(), "unexpected: {}", body);
+
+    // ...and reported when the caller opts in
+    let resp = send("?verbose=1").await.expect("failed to send request");
+    assert!(resp.status().is_success(), "status: {:?}", resp.status());
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["warnings"][0]["kind"], "skipped_metrics", "{}", body);
+    assert_eq!(body["warnings"][0]["count"], 1, "{}", body);
+}
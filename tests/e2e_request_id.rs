@@ -0,0 +1,117 @@
+// tests/e2e_request_id.rs
+mod helpers;
+
+use helpers::{
+    can_bind_loopback, free_port, reset_events, spawn_mock_pipeline, wait_for_events,
+    wait_for_health,
+};
+use reqwest::Client;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+/// Log sink shared between the tracing subscriber and the test
+#[derive(Clone, Default)]
+struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+impl Write for CapturedLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl CapturedLogs {
+    fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.0.lock().unwrap()).into_owned()
+    }
+}
+
+#[tokio::test]
+async fn test_request_id_echoed_and_logged() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e request id test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    // Current-thread runtime: the router task runs on this thread, so a
+    // thread-local default subscriber captures its logs.
+    let logs = CapturedLogs::default();
+    let writer = logs.clone();
+    let subscriber = tracing_subscriber::fmt()
+        .with_writer(move || writer.clone())
+        .with_ansi(false)
+        .with_max_level(tracing::Level::DEBUG)
+        .finish();
+    let _guard = tracing::subscriber::set_default(subscriber);
+
+    let client = Client::new();
+
+    // 1. Start mock pipeline
+    let mock_port = free_port().await;
+    let (mock_proc, mock_url) = spawn_mock_pipeline(mock_port).await;
+    wait_for_health(&client, &mock_url).await;
+    reset_events(&client, &mock_url).await;
+
+    // 2. Start otlp2pipeline router
+    let app_port = free_port().await;
+    let app = otlp2pipeline::build_router(mock_url.clone());
+    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", app_port))
+        .await
+        .unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let app_url = format!("http://127.0.0.1:{}", app_port);
+    wait_for_health(&client, &app_url).await;
+
+    // 3. Provided request ID is echoed and attached to the ingest span
+    let resp = client
+        .post(format!("{}/v1/logs", app_url))
+        .header("content-type", "application/json")
+        .header("x-request-id", "e2e-req-42")
+        .body(include_str!("fixtures/sample_otlp.json"))
+        .send()
+        .await
+        .expect("failed to send request");
+
+    assert!(resp.status().is_success(), "status: {:?}", resp.status());
+    assert_eq!(resp.headers()["x-request-id"], "e2e-req-42");
+    wait_for_events(&client, &mock_url, 1).await;
+
+    let captured = logs.contents();
+    assert!(
+        captured
+            .lines()
+            .any(|line| line.contains("request complete") && line.contains("e2e-req-42")),
+        "request id missing from logs:\n{}",
+        captured
+    );
+
+    // 4. Errors echo a generated request ID in both header and body
+    let resp = client
+        .post(format!("{}/v1/logs", app_url))
+        .header("content-type", "application/json")
+        .body("not json")
+        .send()
+        .await
+        .expect("failed to send request");
+
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    let request_id = resp.headers()["x-request-id"].to_str().unwrap().to_string();
+    assert!(!request_id.is_empty());
+    let body = resp.text().await.unwrap();
+    assert!(
+        body.contains(&request_id),
+        "error body missing request id: {}",
+        body
+    );
+
+    // 5. Cleanup
+    mock_proc.stop().await;
+}
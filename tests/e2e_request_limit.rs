@@ -0,0 +1,61 @@
+// tests/e2e_request_limit.rs
+mod helpers;
+
+use flate2::{write::GzEncoder, Compression};
+use helpers::{
+    can_bind_loopback, free_port, reset_events, spawn_mock_pipeline, spawn_router_with_env,
+    wait_for_events, wait_for_health,
+};
+use reqwest::Client;
+use std::io::Write;
+
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[tokio::test]
+async fn test_oversized_compressed_body_rejected_before_decode() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e request limit test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+
+    let mock_port = free_port().await;
+    let (mock_proc, mock_url) = spawn_mock_pipeline(mock_port).await;
+    wait_for_health(&client, &mock_url).await;
+    reset_events(&client, &mock_url).await;
+
+    // Only test in this binary, so the env var can't leak into other routers.
+    let app_url = spawn_router_with_env(&mock_url, &[("MAX_REQUEST_BYTES", "1024")]).await;
+
+    // Not valid gzip: a body that reached decompression would be a 400, not a 413
+    let resp = client
+        .post(format!("{}/v1/logs", app_url))
+        .header("content-type", "application/json")
+        .header("content-encoding", "gzip")
+        .body(vec![0u8; 4096])
+        .send()
+        .await
+        .expect("failed to send request");
+    assert_eq!(resp.status(), reqwest::StatusCode::PAYLOAD_TOO_LARGE);
+
+    // A compressed body under the cap is still accepted
+    let small = gzip(include_bytes!("fixtures/sample_otlp.json"));
+    assert!(small.len() < 1024);
+    let resp = client
+        .post(format!("{}/v1/logs", app_url))
+        .header("content-type", "application/json")
+        .header("content-encoding", "gzip")
+        .body(small)
+        .send()
+        .await
+        .expect("failed to send request");
+    assert!(resp.status().is_success(), "{:?}", resp.status());
+    assert_eq!(wait_for_events(&client, &mock_url, 1).await.len(), 1);
+
+    mock_proc.stop().await;
+}
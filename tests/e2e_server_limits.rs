@@ -0,0 +1,67 @@
+// tests/e2e_server_limits.rs
+mod helpers;
+
+use helpers::{can_bind_loopback, spawn_app_with_env};
+use otlp2pipeline::Signal;
+use reqwest::Client;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Pipeline mock answering after `delay`
+async fn pipeline(delay: Duration) -> String {
+    let app = axum::Router::new().route(
+        "/",
+        axum::routing::post(move || async move {
+            tokio::time::sleep(delay).await;
+            "ok"
+        }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let endpoint = format!("http://{}/", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    endpoint
+}
+
+#[tokio::test]
+async fn test_slow_request_times_out_while_fast_one_succeeds() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e server limits test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+    let endpoints = HashMap::from([
+        (Signal::Logs, pipeline(Duration::ZERO).await),
+        (Signal::Traces, pipeline(Duration::from_secs(3)).await),
+    ]);
+
+    // Only test in this binary, so the env vars can't leak into other routers
+    let env = [
+        ("SERVER_REQUEST_TIMEOUT_MS", "300"),
+        ("SERVER_MAX_CONCURRENCY", "4"),
+    ];
+    let app_url = spawn_app_with_env(&env, || {
+        otlp2pipeline::native::build_router_multi(endpoints)
+    })
+    .await;
+
+    let resp = client
+        .post(format!("{}/v1/logs", app_url))
+        .header("content-type", "application/json")
+        .body(include_str!("fixtures/sample_otlp.json"))
+        .send()
+        .await
+        .expect("failed to send request");
+    assert!(resp.status().is_success(), "{:?}", resp.status());
+
+    let resp = client
+        .post(format!("{}/v1/traces", app_url))
+        .header("content-type", "application/json")
+        .body(include_str!("fixtures/sample_otlp_traces.json"))
+        .send()
+        .await
+        .expect("failed to send request");
+    assert_eq!(resp.status(), reqwest::StatusCode::REQUEST_TIMEOUT);
+}
@@ -0,0 +1,58 @@
+// tests/e2e_signal_dispatch.rs
+mod helpers;
+
+use helpers::{
+    can_bind_loopback, free_port, reset_events, spawn_mock_pipeline, wait_for_events,
+    wait_for_health,
+};
+use reqwest::Client;
+
+#[tokio::test]
+async fn test_generic_path_dispatches_on_signal_param() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e signal dispatch test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+
+    let mock_port = free_port().await;
+    let (mock_proc, mock_url) = spawn_mock_pipeline(mock_port).await;
+    wait_for_health(&client, &mock_url).await;
+    reset_events(&client, &mock_url).await;
+
+    let app = otlp2pipeline::build_router(mock_url.clone());
+    let app_port = free_port().await;
+    let listener = tokio::net::TcpListener::bind(format!("127.0.0.1:{}", app_port))
+        .await
+        .unwrap();
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    let app_url = format!("http://127.0.0.1:{}", app_port);
+    wait_for_health(&client, &app_url).await;
+
+    let resp = client
+        .post(format!("{}/ingest?signal=logs", app_url))
+        .header("content-type", "application/json")
+        .body(include_str!("fixtures/sample_otlp.json"))
+        .send()
+        .await
+        .expect("failed to send request");
+    assert!(resp.status().is_success(), "{:?}", resp.status());
+    let events = wait_for_events(&client, &mock_url, 1).await;
+    assert_eq!(events.len(), 1);
+
+    let resp = client
+        .post(format!("{}/ingest?signal=profiles", app_url))
+        .header("content-type", "application/json")
+        .body("{}")
+        .send()
+        .await
+        .expect("failed to send request");
+    assert_eq!(resp.status(), reqwest::StatusCode::NOT_FOUND);
+    assert!(resp.text().await.unwrap().contains("no signal for path"));
+
+    mock_proc.stop().await;
+}
@@ -0,0 +1,100 @@
+// tests/e2e_span_links.rs
+mod helpers;
+
+use helpers::{
+    can_bind_loopback, free_port, reset_events, spawn_mock_pipeline, spawn_router_with_env,
+    wait_for_events, wait_for_health,
+};
+use reqwest::Client;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_span_with_two_links_writes_link_rows() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e span links test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+
+    let mock_port = free_port().await;
+    let (mock_proc, mock_url) = spawn_mock_pipeline(mock_port).await;
+    wait_for_health(&client, &mock_url).await;
+    reset_events(&client, &mock_url).await;
+
+    // Only test in this binary, so the env var can't leak into other routers
+    let app_url = spawn_router_with_env(&mock_url, &[("SPAN_LINKS_TABLE", "true")]).await;
+
+    let payload = json!({
+        "resourceSpans": [{
+            "resource": {"attributes": [{"key": "service.name", "value": {"stringValue": "api"}}]},
+            "scopeSpans": [{
+                "spans": [{
+                    "traceId": "5b8efff798038103d269b633813fc60c",
+                    "spanId": "eee19b7ec3c1b174",
+                    "name": "consume",
+                    "kind": 5,
+                    "startTimeUnixNano": "1703265600000000000",
+                    "endTimeUnixNano": "1703265601000000000",
+                    "links": [
+                        {
+                            "traceId": "0af7651916cd43dd8448eb211c80319c",
+                            "spanId": "b7ad6b7169203331",
+                            "attributes": [{"key": "reason", "value": {"stringValue": "batch"}}]
+                        },
+                        {
+                            "traceId": "1af7651916cd43dd8448eb211c80319c",
+                            "spanId": "c7ad6b7169203331",
+                            "traceState": "k=v"
+                        }
+                    ]
+                }]
+            }]
+        }]
+    });
+    let resp = client
+        .post(format!("{}/v1/traces", app_url))
+        .header("content-type", "application/json")
+        .body(payload.to_string())
+        .send()
+        .await
+        .expect("failed to send request");
+    assert!(
+        resp.status().is_success(),
+        "response was not success: {:?}",
+        resp.status()
+    );
+
+    let events = wait_for_events(&client, &mock_url, 3).await;
+    assert_eq!(events.len(), 3, "expected 1 span + 2 link rows");
+
+    // The span keeps its embedded links
+    let span = events
+        .iter()
+        .find(|e| e.get("span_name").is_some())
+        .expect("span record");
+    let embedded: Vec<serde_json::Value> =
+        serde_json::from_str(span["links_json"].as_str().unwrap()).unwrap();
+    assert_eq!(embedded.len(), 2);
+
+    let mut links: Vec<_> = events
+        .iter()
+        .filter(|e| e.get("linked_trace_id").is_some())
+        .collect();
+    links.sort_by_key(|e| e["linked_trace_id"].as_str().unwrap().to_string());
+    assert_eq!(links.len(), 2);
+    for link in &links {
+        assert_eq!(link["trace_id"], "5b8efff798038103d269b633813fc60c");
+        assert_eq!(link["span_id"], "eee19b7ec3c1b174");
+        assert_eq!(link["timestamp"], span["timestamp"]);
+    }
+    assert_eq!(
+        links[0]["linked_trace_id"],
+        "0af7651916cd43dd8448eb211c80319c"
+    );
+    assert_eq!(links[0]["linked_span_id"], "b7ad6b7169203331");
+    assert_eq!(links[0]["attributes"], r#"{"reason":"batch"}"#);
+    assert_eq!(links[1]["linked_trace_state"], "k=v");
+
+    mock_proc.stop().await;
+}
@@ -0,0 +1,44 @@
+// tests/e2e_strict_json.rs
+mod helpers;
+
+use helpers::{can_bind_loopback, spawn_router_with_env};
+use reqwest::Client;
+
+/// Serve a router built with `STRICT_JSON` set to `strict`, returning its URL
+async fn serve(strict: &str) -> String {
+    // Only test in this binary, so the env var can't leak into other routers.
+    spawn_router_with_env("http://127.0.0.1:9", &[("STRICT_JSON", strict)]).await
+}
+
+#[tokio::test]
+async fn test_misspelled_top_level_field_only_rejected_when_strict() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e strict JSON test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+    let lenient = serve("false").await;
+    let strict = serve("true").await;
+    let misspelled = r#"{"resourceMetric":[{"scopeMetrics":[]}]}"#;
+
+    let post = |url: String, body: &'static str| {
+        client
+            .post(format!("{}/v1/metrics", url))
+            .header("content-type", "application/json")
+            .body(body)
+            .send()
+    };
+
+    // Lenient mode decodes the typo to zero records, as before
+    let resp = post(lenient, misspelled).await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+
+    let resp = post(strict.clone(), misspelled).await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::BAD_REQUEST);
+    let body = resp.text().await.unwrap();
+    assert!(body.contains("resourceMetric"), "{}", body);
+
+    let resp = post(strict, r#"{"resourceMetrics":[]}"#).await.unwrap();
+    assert_eq!(resp.status(), reqwest::StatusCode::OK);
+}
@@ -0,0 +1,68 @@
+// tests/e2e_uds.rs
+#![cfg(unix)]
+
+mod helpers;
+
+use helpers::{
+    can_bind_loopback, free_port, reset_events, spawn_mock_pipeline, wait_for_events,
+    wait_for_health,
+};
+use reqwest::Client;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+
+/// Send a minimal HTTP/1.1 POST over a Unix socket and return the raw response
+async fn post_over_uds(socket: &std::path::Path, path: &str, body: &str) -> String {
+    let mut stream = UnixStream::connect(socket)
+        .await
+        .expect("failed to connect to unix socket");
+    let request = format!(
+        "POST {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+        path,
+        body.len(),
+        body
+    );
+    stream.write_all(request.as_bytes()).await.unwrap();
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+#[tokio::test]
+async fn test_logs_over_unix_socket_reach_pipeline() {
+    if !can_bind_loopback().await {
+        eprintln!("skipping e2e uds test: cannot bind to loopback in this environment");
+        return;
+    }
+
+    let client = Client::new();
+
+    let mock_port = free_port().await;
+    let (mock_proc, mock_url) = spawn_mock_pipeline(mock_port).await;
+    wait_for_health(&client, &mock_url).await;
+    reset_events(&client, &mock_url).await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let socket = dir.path().join("otlp.sock");
+    // A leftover socket file from a previous run must not block binding
+    drop(std::os::unix::net::UnixListener::bind(&socket).unwrap());
+    let listener = otlp2pipeline::bind_uds(&socket).expect("failed to bind unix socket");
+    let app = otlp2pipeline::build_router(mock_url.clone());
+    tokio::spawn(otlp2pipeline::serve_uds(listener, app));
+
+    let otlp_payload = include_str!("fixtures/sample_otlp.json");
+    let response = post_over_uds(&socket, "/v1/logs", otlp_payload).await;
+    assert!(
+        response.starts_with("HTTP/1.1 200"),
+        "response was not success: {}",
+        response
+    );
+
+    let events = wait_for_events(&client, &mock_url, 1).await;
+    assert!(!events.is_empty(), "expected log events at the pipeline");
+    assert!(events[0].get("service_name").is_some());
+
+    mock_proc.stop().await;
+}
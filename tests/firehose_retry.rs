@@ -26,17 +26,15 @@ fn test_backoff_calculation() {
     // Create config matching Firehose defaults: 100ms base, 10s max, 3 attempts
     let config = RetryConfig::exponential(3, 100, 10_000);
 
-    // Attempt 0: 100ms base + jitter (up to 50ms)
+    // Full jitter draws each delay from [0, min(base * 2^attempt, max)]
     let delay0 = config.delay_for_attempt(0).as_millis() as u64;
-    assert!((100..=150).contains(&delay0), "attempt 0 delay: {}", delay0);
+    assert!(delay0 <= 100, "attempt 0 delay: {}", delay0);
 
-    // Attempt 1: 200ms base + jitter (up to 100ms)
     let delay1 = config.delay_for_attempt(1).as_millis() as u64;
-    assert!((200..=300).contains(&delay1), "attempt 1 delay: {}", delay1);
+    assert!(delay1 <= 200, "attempt 1 delay: {}", delay1);
 
-    // Attempt 2: 400ms base + jitter (up to 200ms)
     let delay2 = config.delay_for_attempt(2).as_millis() as u64;
-    assert!((400..=600).contains(&delay2), "attempt 2 delay: {}", delay2);
+    assert!(delay2 <= 400, "attempt 2 delay: {}", delay2);
 
     // Should cap at max_ms (10000)
     let delay10 = config.delay_for_attempt(10).as_millis() as u64;
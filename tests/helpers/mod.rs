@@ -95,6 +95,38 @@ pub async fn spawn_mock_pipeline(port: u16) -> (MockPipeline, String) {
     )
 }
 
+/// Build the native router for `pipeline_url` with `env` set, serve it on a
+/// free port and wait until it's healthy, returning its base URL.
+/// The variables are removed once the router is built; only set them from one
+/// test per binary, so they can't leak into routers built by other tests.
+pub async fn spawn_router_with_env(pipeline_url: &str, env: &[(&str, &str)]) -> String {
+    spawn_app_with_env(env, || {
+        otlp2pipeline::build_router(pipeline_url.to_string())
+    })
+    .await
+}
+
+/// Like `spawn_router_with_env`, for routers built some other way
+pub async fn spawn_app_with_env(env: &[(&str, &str)], build: impl FnOnce() -> Router) -> String {
+    for (key, value) in env {
+        std::env::set_var(key, value);
+    }
+    let app = build();
+    for (key, _) in env {
+        std::env::remove_var(key);
+    }
+
+    let listener = TcpListener::bind(("127.0.0.1", free_port().await))
+        .await
+        .expect("failed to bind app listener");
+    let app_url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    wait_for_health(&Client::new(), &app_url).await;
+    app_url
+}
+
 /// Wait for a server to respond to /health
 pub async fn wait_for_health(client: &Client, base_url: &str) {
     poll_until(|| async {
@@ -5,7 +5,8 @@
 
 use bytes::Bytes;
 use otlp2pipeline::{
-    handle_signal, InputFormat, LogsHandler, PipelineSender, SendResult, TracesHandler,
+    handle_signal, Compression, HandlerConfig, InputFormat, LogsHandler, PipelineSender,
+    SendResult, TracesHandler,
 };
 use serde_json::Value;
 use std::collections::HashMap;
@@ -62,8 +63,10 @@ async fn test_logs_handler_json() {
     let sender = MockSender::new();
     let result = handle_signal::<LogsHandler, _>(
         Bytes::from(json_payload),
-        false,
-        InputFormat::Json,
+        Compression::None,
+        InputFormat::Json.into(),
+        &HandlerConfig::default(),
+        "test-request",
         &sender,
     )
     .await;
@@ -102,8 +105,10 @@ async fn test_traces_handler_json() {
     let sender = MockSender::new();
     let result = handle_signal::<TracesHandler, _>(
         Bytes::from(json_payload),
-        false,
-        InputFormat::Json,
+        Compression::None,
+        InputFormat::Json.into(),
+        &HandlerConfig::default(),
+        "test-request",
         &sender,
     )
     .await;